@@ -1,6 +1,9 @@
 pub mod activity;
 pub mod collection;
 pub mod context;
+pub mod image;
 pub mod key;
 pub mod note;
 pub mod person;
+pub mod property_value;
+pub mod tombstone;
@@ -1,9 +1,19 @@
 mod accept;
+mod announce;
 mod create;
+mod delete;
 mod follow;
+mod like;
+mod reject;
 mod undo;
+mod update;
 
 pub use accept::{Accept, AcceptPersonFollow};
+pub use announce::{Announce, AnnouncePersonNote};
 pub use create::{Create, CreatePersonNote};
+pub use delete::{Delete, DeletePersonObject};
 pub use follow::{Follow, FollowPerson};
-pub use undo::{Undo, UndoPersonFollow};
+pub use like::{Like, LikePersonNote};
+pub use reject::{Reject, RejectPersonFollow};
+pub use undo::{Undo, UndoPersonAnnounce, UndoPersonFollow, UndoPersonLike};
+pub use update::{Update, UpdateAnyActor, UpdatePerson, UpdatePersonNote};
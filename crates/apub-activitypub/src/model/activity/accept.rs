@@ -35,8 +35,8 @@ pub struct Accept<Act, Obj> {
     #[serde(rename = "type")]
     #[builder(default)]
     kind: AcceptKind,
-    actor: UrlId<Act>,
-    object: Obj,
+    pub actor: UrlId<Act>,
+    pub object: Obj,
 }
 
 impl<Act, Obj> Object for Accept<Act, Obj> {
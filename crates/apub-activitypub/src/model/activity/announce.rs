@@ -0,0 +1,108 @@
+use apub_shared::model::{id::UrlId, resource_url::ResourceUrl};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    core::{
+        activity::Activity,
+        actor::Actor,
+        object::{EmptyObject, Object},
+    },
+    model::{context::Context, note::Note, person::Person},
+    shared::SingleOrMany,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum AnnounceKind {
+    #[default]
+    Announce,
+}
+
+/// Announce activity
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#announce-activity-inbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-announce
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Announce<Act, Obj> {
+    #[serde(rename = "@context")]
+    #[builder(default, setter(strip_option))]
+    context: Option<Context>,
+    id: UrlId<Announce<Act, Obj>>,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: AnnounceKind,
+    /// `Announce`した`Actor`
+    pub actor: UrlId<Act>,
+    /// `Announce`された`Object`
+    pub object: UrlId<Obj>,
+    #[builder(default, setter(strip_option))]
+    published: Option<String>,
+    #[builder(default, setter(strip_option))]
+    to: Option<SingleOrMany<ResourceUrl>>,
+    #[builder(default, setter(strip_option))]
+    cc: Option<SingleOrMany<ResourceUrl>>,
+}
+
+impl<Act, Obj> Object for Announce<Act, Obj> {
+    type Kind = AnnounceKind;
+}
+
+impl<Act, Obj> Activity for Announce<Act, Obj>
+where
+    Act: Actor,
+    Obj: Object,
+{
+    type ActorType = Act;
+    type ObjectType = Obj;
+    type TargetType = EmptyObject;
+}
+
+/// `Person`による`Note`の`Announce`
+pub type AnnouncePersonNote = Announce<Person, Note>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_deserialize_announce() {
+        let announce = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.example/users/bob/statuses/1/activity",
+                "type": "Announce",
+                "actor": "https://mastodon.example/users/bob",
+                "published": "2024-11-20T12:00:00Z",
+                "to": ["https://www.w3.org/ns/activitystreams#Public"],
+                "cc": ["https://mastodon.example/users/bob/followers"],
+                "object": "https://example.com/notes/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69"
+            }
+        "#;
+
+        let deserialized = serde_json::from_str::<AnnouncePersonNote>(announce).unwrap();
+        let expected = AnnouncePersonNote::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id("https://mastodon.example/users/bob/statuses/1/activity"
+                .parse()
+                .unwrap())
+            .actor("https://mastodon.example/users/bob".parse().unwrap())
+            .object(
+                "https://example.com/notes/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69"
+                    .parse()
+                    .unwrap(),
+            )
+            .published("2024-11-20T12:00:00Z".to_string())
+            .to(vec![Note::public_address().clone()].into())
+            .cc(vec!["https://mastodon.example/users/bob/followers"
+                .parse()
+                .unwrap()]
+            .into())
+            .build();
+        assert_eq!(expected, deserialized)
+    }
+}
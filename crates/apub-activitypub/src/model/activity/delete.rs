@@ -0,0 +1,122 @@
+use apub_shared::model::{id::UrlId, resource_url::ResourceUrl};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    core::{
+        activity::Activity,
+        actor::Actor,
+        object::{EmptyObject, Object},
+    },
+    model::{context::Context, person::Person, tombstone::Tombstone},
+    shared::{SingleOrMany, UrlOrObj},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum DeleteKind {
+    #[default]
+    Delete,
+}
+
+/// Delete activity
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#delete-activity-inbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-delete
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Delete<Act, Obj> {
+    #[serde(rename = "@context")]
+    #[builder(default, setter(strip_option))]
+    context: Option<Context>,
+    id: UrlId<Delete<Act, Obj>>,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: DeleteKind,
+    pub actor: UrlId<Act>,
+    /// 削除された`Object`
+    pub object: Obj,
+    #[builder(default, setter(strip_option))]
+    to: Option<SingleOrMany<ResourceUrl>>,
+    #[builder(default, setter(strip_option))]
+    cc: Option<SingleOrMany<ResourceUrl>>,
+}
+
+impl<Act, Obj> Object for Delete<Act, Obj> {
+    type Kind = DeleteKind;
+}
+
+impl<Act, Obj> Activity for Delete<Act, Obj>
+where
+    Act: Actor,
+    Obj: Object,
+{
+    type ActorType = Act;
+    type ObjectType = Obj;
+    type TargetType = EmptyObject;
+}
+
+/// `Person`による`Object`の`Delete`
+///
+/// `object`は`Tombstone`か、`Actor`自身の削除ではそのURLになる
+pub type DeletePersonObject = Delete<Person, UrlOrObj<Tombstone>>;
+
+impl DeletePersonObject {
+    /// 削除された`Object`のURL
+    pub fn object_url(&self) -> &ResourceUrl {
+        match &self.object {
+            UrlOrObj::Url(url) => url.as_ref(),
+            UrlOrObj::Obj(tombstone) => tombstone.id().as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_deserialize_delete_note() {
+        let delete = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.example/users/bob/statuses/1#delete",
+                "type": "Delete",
+                "actor": "https://mastodon.example/users/bob",
+                "to": ["https://www.w3.org/ns/activitystreams#Public"],
+                "object": {
+                    "id": "https://mastodon.example/users/bob/statuses/1",
+                    "type": "Tombstone"
+                }
+            }
+        "#;
+
+        let deserialized = serde_json::from_str::<DeletePersonObject>(delete).unwrap();
+        assert_eq!(
+            deserialized.object_url().as_str(),
+            "https://mastodon.example/users/bob/statuses/1"
+        );
+        assert!(matches!(deserialized.object, UrlOrObj::Obj(_)));
+    }
+
+    #[test]
+    fn test_deserialize_delete_actor() {
+        let delete = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.example/users/bob#delete",
+                "type": "Delete",
+                "actor": "https://mastodon.example/users/bob",
+                "to": ["https://www.w3.org/ns/activitystreams#Public"],
+                "object": "https://mastodon.example/users/bob"
+            }
+        "#;
+
+        let deserialized = serde_json::from_str::<DeletePersonObject>(delete).unwrap();
+        assert_eq!(deserialized.object_url(), deserialized.actor.as_ref());
+        assert!(matches!(deserialized.object, UrlOrObj::Url(_)));
+    }
+}
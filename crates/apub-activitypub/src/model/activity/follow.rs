@@ -37,6 +37,12 @@ pub struct Follow<Act, Obj> {
     pub object: UrlId<Obj>,
 }
 
+impl<Act, Obj> Follow<Act, Obj> {
+    pub fn id(&self) -> &UrlId<Follow<Act, Obj>> {
+        &self.id
+    }
+}
+
 impl<Act, Obj> Object for Follow<Act, Obj> {
     type Kind = FollowKind;
 }
@@ -0,0 +1,92 @@
+use apub_shared::model::id::UrlId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    core::{
+        activity::Activity,
+        actor::Actor,
+        object::{EmptyObject, Object},
+    },
+    model::{context::Context, note::Note, person::Person},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum LikeKind {
+    #[default]
+    Like,
+}
+
+/// Like activity
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#like-activity-inbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-like
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Like<Act, Obj> {
+    #[serde(rename = "@context")]
+    #[builder(default, setter(strip_option))]
+    context: Option<Context>,
+    id: UrlId<Like<Act, Obj>>,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: LikeKind,
+    /// `Like`した`Actor`
+    pub actor: UrlId<Act>,
+    /// `Like`された`Object`
+    pub object: UrlId<Obj>,
+}
+
+impl<Act, Obj> Object for Like<Act, Obj> {
+    type Kind = LikeKind;
+}
+
+impl<Act, Obj> Activity for Like<Act, Obj>
+where
+    Act: Actor,
+    Obj: Object,
+{
+    type ActorType = Act;
+    type ObjectType = Obj;
+    type TargetType = EmptyObject;
+}
+
+/// `Person`による`Note`への`Like`
+pub type LikePersonNote = Like<Person, Note>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_deserialize_like() {
+        let like = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.example/users/bob#likes/1",
+                "type": "Like",
+                "actor": "https://mastodon.example/users/bob",
+                "object": "https://example.com/notes/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69"
+            }
+        "#;
+
+        let deserialized = serde_json::from_str::<LikePersonNote>(like).unwrap();
+        let expected = LikePersonNote::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id("https://mastodon.example/users/bob#likes/1"
+                .parse()
+                .unwrap())
+            .actor("https://mastodon.example/users/bob".parse().unwrap())
+            .object(
+                "https://example.com/notes/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69"
+                    .parse()
+                    .unwrap(),
+            )
+            .build();
+        assert_eq!(expected, deserialized)
+    }
+}
@@ -0,0 +1,107 @@
+use apub_shared::model::id::UrlId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    core::{
+        activity::Activity,
+        actor::Actor,
+        object::{EmptyObject, Object},
+    },
+    model::{context::Context, person::Person},
+};
+
+use super::follow::FollowPerson;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum RejectKind {
+    #[default]
+    Reject,
+}
+
+/// Reject activity
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#follow-activity-outbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-reject
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Reject<Act, Obj> {
+    #[serde(rename = "@context")]
+    context: Context,
+    id: UrlId<Reject<Act, Obj>>,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: RejectKind,
+    pub actor: UrlId<Act>,
+    pub object: Obj,
+}
+
+impl<Act, Obj> Object for Reject<Act, Obj> {
+    type Kind = RejectKind;
+}
+
+impl<Act, Obj> Activity for Reject<Act, Obj>
+where
+    Act: Actor,
+    Obj: Actor,
+{
+    type ActorType = Act;
+    type ObjectType = Obj;
+    type TargetType = EmptyObject;
+}
+
+/// `Person`が何かからの`Follow`を`Reject`する
+pub type RejectPersonFollow<Act> = Reject<Person, FollowPerson<Act>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_deserialize_reject() {
+        let reject = r#"
+            {
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/activities/12345",
+            "type": "Reject",
+            "actor": "https://example.com/users/bob",
+            "object": {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/activities/67890",
+                "type": "Follow",
+                "actor": "https://example.com/users/alice",
+                "object": "https://example.com/users/bob"
+                }
+            }
+        "#;
+        let deserialized = serde_json::from_str::<RejectPersonFollow<Person>>(reject).unwrap();
+
+        let follow = FollowPerson::<Person>::builder()
+            .context(Some(Context::activity_context_url().clone().into()))
+            .id("https://example.com/activities/67890"
+                .parse::<UrlId<_>>()
+                .unwrap())
+            .actor(
+                "https://example.com/users/alice"
+                    .parse::<UrlId<_>>()
+                    .unwrap(),
+            )
+            .object("https://example.com/users/bob".parse::<UrlId<_>>().unwrap())
+            .build();
+
+        let expected = RejectPersonFollow::<Person>::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id("https://example.com/activities/12345"
+                .parse::<UrlId<_>>()
+                .unwrap())
+            .actor("https://example.com/users/bob".parse::<UrlId<_>>().unwrap())
+            .object(follow)
+            .build();
+
+        assert_eq!(expected, deserialized)
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     model::{context::Context, person::Person},
 };
 
-use super::FollowPerson;
+use super::{AnnouncePersonNote, FollowPerson, LikePersonNote};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum UndoKind {
@@ -35,7 +35,7 @@ pub struct Undo<Act, Obj> {
     #[serde(rename = "type")]
     #[builder(default)]
     kind: UndoKind,
-    actor: UrlId<Act>,
+    pub actor: UrlId<Act>,
     pub object: Obj,
 }
 
@@ -56,6 +56,12 @@ where
 /// `Person`が何かからの`Follow`を`Undo`する
 pub type UndoPersonFollow<Act> = Undo<Person, FollowPerson<Act>>;
 
+/// `Person`が`Note`への`Like`を`Undo`する
+pub type UndoPersonLike = Undo<Person, LikePersonNote>;
+
+/// `Person`が`Note`の`Announce`を`Undo`する
+pub type UndoPersonAnnounce = Undo<Person, AnnouncePersonNote>;
+
 #[cfg(test)]
 mod tests {
     use crate::model::person::PersonUrl;
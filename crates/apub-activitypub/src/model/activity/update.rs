@@ -0,0 +1,112 @@
+use apub_shared::model::{id::UrlId, resource_url::ResourceUrl};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    core::{
+        activity::Activity,
+        actor::Actor,
+        object::{EmptyObject, Object},
+    },
+    model::{
+        context::Context,
+        note::Note,
+        person::{Person, SecurityAnyActor, SecurityPerson},
+    },
+    shared::SingleOrMany,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum UpdateKind {
+    #[default]
+    Update,
+}
+
+/// Update activity
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#update-activity-inbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-update
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Update<Act, Obj> {
+    #[serde(rename = "@context")]
+    #[builder(default, setter(strip_option))]
+    context: Option<Context>,
+    id: UrlId<Update<Act, Obj>>,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: UpdateKind,
+    pub actor: UrlId<Act>,
+    /// 更新後の`Object`
+    pub object: Obj,
+    #[builder(default, setter(strip_option))]
+    to: Option<SingleOrMany<ResourceUrl>>,
+    #[builder(default, setter(strip_option))]
+    cc: Option<SingleOrMany<ResourceUrl>>,
+}
+
+impl<Act, Obj> Object for Update<Act, Obj> {
+    type Kind = UpdateKind;
+}
+
+impl<Act, Obj> Activity for Update<Act, Obj>
+where
+    Act: Actor,
+    Obj: Object,
+{
+    type ActorType = Act;
+    type ObjectType = Obj;
+    type TargetType = EmptyObject;
+}
+
+/// `Person`が`Note`を更新する`Activity`
+pub type UpdatePersonNote = Update<Person, Note>;
+
+/// `Person`が自身のプロフィールを更新する`Activity`
+pub type UpdatePerson = Update<Person, SecurityPerson>;
+
+/// リモートの`Actor`がプロフィールを更新する`Activity`
+pub type UpdateAnyActor = Update<Person, SecurityAnyActor>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_deserialize_update_actor() {
+        let update = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://mastodon.example/users/bob#updates/1",
+                "type": "Update",
+                "actor": "https://mastodon.example/users/bob",
+                "to": ["https://www.w3.org/ns/activitystreams#Public"],
+                "object": {
+                    "@context": "https://www.w3.org/ns/activitystreams",
+                    "id": "https://mastodon.example/users/bob",
+                    "type": "Person",
+                    "preferredUsername": "bob",
+                    "name": "Bob",
+                    "inbox": "https://mastodon.example/users/bob/inbox",
+                    "publicKey": {
+                        "id": "https://mastodon.example/users/bob#main-key",
+                        "owner": "https://mastodon.example/users/bob",
+                        "publicKeyPem": "-----BEGIN PUBLIC KEY-----\n-----END PUBLIC KEY-----"
+                    }
+                }
+            }
+        "#;
+
+        let deserialized = serde_json::from_str::<UpdateAnyActor>(update).unwrap();
+        assert_eq!(
+            deserialized.object.id().as_ref(),
+            deserialized.actor.as_ref()
+        );
+        assert_eq!(deserialized.object.display_name(), Some("Bob"));
+        assert!(serde_json::from_str::<UpdatePersonNote>(update).is_err());
+    }
+}
@@ -265,6 +265,10 @@ impl<T> OrderedCollectionPage<T> {
     pub fn prev(&self) -> Option<&ResourceUrl> {
         self.page.prev.as_ref()
     }
+
+    pub fn part_of(&self) -> Option<&ResourceUrl> {
+        self.page.part_of.as_ref()
+    }
 }
 
 /// `CollectionPage` or `OrderedCollectionPage`
@@ -364,10 +368,15 @@ impl<T> Default for OrderedCollectionBase<T> {
 #[skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
 #[serde(rename_all = "camelCase")]
-#[builder(field_defaults(default, setter(strip_option)))]
+#[builder(field_defaults(default))]
 pub struct CollectionPageBase {
+    #[builder(setter(strip_option(fallback = next_opt)))]
     next: Option<ResourceUrl>,
+    #[builder(setter(strip_option(fallback = prev_opt)))]
     prev: Option<ResourceUrl>,
+    /// ページが属する`Collection`
+    #[builder(setter(strip_option))]
+    part_of: Option<ResourceUrl>,
 }
 
 #[cfg(test)]
@@ -516,6 +525,7 @@ mod tests {
                     .parse()
                     .unwrap(),
             )
+            .part_of("https://example.com/users/User1/followers".parse().unwrap())
             .build();
 
         let expected = OrderedCollectionPage::<ResourceUrl>::builder()
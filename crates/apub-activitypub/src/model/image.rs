@@ -0,0 +1,37 @@
+use apub_shared::model::resource_url::ResourceUrl;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::core::object::Object;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum ImageKind {
+    #[default]
+    Image,
+}
+
+/// Activity Image Object
+///
+/// See https://www.w3.org/TR/activitystreams-vocabulary/#dfn-image
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: ImageKind,
+    url: ResourceUrl,
+    #[builder(default, setter(strip_option))]
+    media_type: Option<String>,
+}
+
+impl Image {
+    pub fn url(&self) -> &ResourceUrl {
+        &self.url
+    }
+}
+
+impl Object for Image {
+    type Kind = ImageKind;
+}
@@ -12,3 +12,51 @@ pub struct PublicKeyPem {
     owner: UrlId<Person>,
     public_key_pem: String,
 }
+
+impl PublicKeyPem {
+    pub fn id(&self) -> &ResourceUrl {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &UrlId<Person> {
+        &self.owner
+    }
+
+    pub fn public_key_pem(&self) -> &str {
+        &self.public_key_pem
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum MultikeyKind {
+    #[default]
+    Multikey,
+}
+
+/// `assertionMethod`で公開する鍵
+///
+/// See https://codeberg.org/fediverse/fep/src/branch/main/fep/521a/fep-521a.md
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Multikey {
+    id: ResourceUrl,
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: MultikeyKind,
+    controller: UrlId<Person>,
+    public_key_multibase: String,
+}
+
+impl Multikey {
+    pub fn id(&self) -> &ResourceUrl {
+        &self.id
+    }
+
+    pub fn controller(&self) -> &UrlId<Person> {
+        &self.controller
+    }
+
+    pub fn public_key_multibase(&self) -> &str {
+        &self.public_key_multibase
+    }
+}
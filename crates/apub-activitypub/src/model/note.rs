@@ -32,10 +32,17 @@ pub struct Note {
     #[builder(setter(!strip_option))]
     content: String,
     published: Option<String>,
+    /// 編集された日時。編集されていなければ`None`
+    #[builder(setter(!strip_option))]
+    updated: Option<String>,
     to: Option<SingleOrMany<ResourceUrl>>,
     cc: Option<SingleOrMany<ResourceUrl>>,
     in_reply_to: Option<UrlId<Note>>,
     attributed_to: Option<ResourceUrl>,
+    /// この`Note`を`Like`した`Actor`のコレクション
+    likes: Option<ResourceUrl>,
+    /// この`Note`を`Announce`した`Actor`のコレクション
+    shares: Option<ResourceUrl>,
 }
 
 impl Note {
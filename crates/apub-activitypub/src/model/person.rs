@@ -3,9 +3,17 @@ use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use typed_builder::TypedBuilder;
 
-use crate::core::{actor::Actor, object::Object};
+use crate::{
+    core::{actor::Actor, object::Object},
+    shared::SingleOrMany,
+};
 
-use super::{context::Context, key::PublicKeyPem};
+use super::{
+    context::Context,
+    image::Image,
+    key::{Multikey, PublicKeyPem},
+    property_value::PropertyValue,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -108,9 +116,39 @@ pub struct AnyActorImpl<Kind> {
     preferred_username: String,
     inbox: ResourceUrl,
     #[builder(default, setter(strip_option))]
+    outbox: Option<ResourceUrl>,
+    #[builder(default, setter(strip_option))]
     shared_inbox: Option<ResourceUrl>,
     #[builder(default, setter(strip_option))]
+    endpoints: Option<Endpoints>,
+    #[builder(default, setter(strip_option))]
     followers: Option<ResourceUrl>,
+    #[builder(default, setter(strip_option))]
+    following: Option<ResourceUrl>,
+    /// 表示名
+    #[builder(default)]
+    name: Option<String>,
+    #[builder(default)]
+    summary: Option<String>,
+    #[builder(default)]
+    icon: Option<Image>,
+    /// プロフィールの項目
+    #[builder(default)]
+    attachment: Option<Vec<PropertyValue>>,
+    /// フォローに承認が必要か
+    #[builder(default)]
+    manually_approves_followers: Option<bool>,
+}
+
+/// `Actor`が公開している追加のエンドポイント
+///
+/// See https://www.w3.org/TR/activitypub/#actor-objects
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoints {
+    #[builder(default, setter(strip_option))]
+    shared_inbox: Option<ResourceUrl>,
 }
 
 impl<Kind: ActorKind> Object for AnyActorImpl<Kind> {
@@ -130,7 +168,7 @@ impl<Kind: ActorKind> Actor for AnyActorImpl<Kind> {
         &self.inbox
     }
     fn outbox(&self) -> Option<&ResourceUrl> {
-        None
+        self.outbox.as_ref()
     }
 }
 
@@ -138,6 +176,44 @@ impl<Kind> AnyActorImpl<Kind> {
     pub fn username(&self) -> &str {
         &self.preferred_username
     }
+
+    /// サーバ全体で共有する`inbox`
+    ///
+    /// `endpoints.sharedInbox`を優先し、なければ直下の`sharedInbox`を見る
+    pub fn shared_inbox(&self) -> Option<&ResourceUrl> {
+        self.endpoints
+            .as_ref()
+            .and_then(|v| v.shared_inbox.as_ref())
+            .or(self.shared_inbox.as_ref())
+    }
+
+    pub fn followers(&self) -> Option<&ResourceUrl> {
+        self.followers.as_ref()
+    }
+
+    pub fn following(&self) -> Option<&ResourceUrl> {
+        self.following.as_ref()
+    }
+
+    pub fn display_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn icon(&self) -> Option<&Image> {
+        self.icon.as_ref()
+    }
+
+    pub fn attachment(&self) -> &[PropertyValue] {
+        self.attachment.as_deref().unwrap_or_default()
+    }
+
+    pub fn manually_approves_followers(&self) -> bool {
+        self.manually_approves_followers.unwrap_or_default()
+    }
 }
 
 pub type AnyActor = AnyActorImpl<AnyActorKind>;
@@ -159,7 +235,12 @@ pub type ServiceUrl = UrlId<Service>;
 pub struct Security<T> {
     #[serde(flatten)]
     inner: T,
-    public_key: PublicKeyPem,
+    /// 鍵を替えた直後は猶予期間中の古い鍵も並べる。使っている鍵が先頭
+    #[builder(setter(into))]
+    public_key: SingleOrMany<PublicKeyPem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    assertion_method: Vec<Multikey>,
 }
 
 impl<T: Object> Object for Security<T> {
@@ -179,6 +260,21 @@ impl<T: Actor> Actor for Security<T> {
     }
 }
 
+impl<T> Security<T> {
+    pub fn public_keys(&self) -> &[PublicKeyPem] {
+        self.public_key.as_slice()
+    }
+
+    /// `id`の公開鍵
+    pub fn find_public_key(&self, id: &ResourceUrl) -> Option<&PublicKeyPem> {
+        self.public_keys().iter().find(|v| v.id() == id)
+    }
+
+    pub fn assertion_method(&self) -> &[Multikey] {
+        &self.assertion_method
+    }
+}
+
 impl<T> std::ops::Deref for Security<T> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
@@ -212,4 +308,160 @@ mod tests {
 
         let _: Person = serde_json::from_str(v).unwrap();
     }
+
+    #[test]
+    fn test_deserialize_person_multikey() {
+        // https://codeberg.org/fediverse/fep/src/branch/main/fep/521a/fep-521a.md#example
+        let v = r#"
+            {
+                "@context": [
+                    "https://www.w3.org/ns/activitystreams",
+                    "https://w3id.org/security/data-integrity/v1"
+                ],
+                "id": "https://server.example/users/alice",
+                "type": "Person",
+                "preferredUsername": "alice",
+                "inbox": "https://server.example/users/alice/inbox",
+                "publicKey": {
+                    "id": "https://server.example/users/alice#main-key",
+                    "owner": "https://server.example/users/alice",
+                    "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+                },
+                "assertionMethod": [
+                    {
+                        "id": "https://server.example/users/alice#ed25519-key",
+                        "type": "Multikey",
+                        "controller": "https://server.example/users/alice",
+                        "publicKeyMultibase": "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+                    }
+                ]
+            }
+        "#;
+
+        let person: SecurityPerson = serde_json::from_str(v).unwrap();
+        let key = person.assertion_method().first().unwrap();
+        assert_eq!(
+            key.id().as_str(),
+            "https://server.example/users/alice#ed25519-key"
+        );
+        assert_eq!(key.controller(), person.id());
+
+        // `assertionMethod`のない`Actor`も読める
+        let v = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://server.example/users/bob",
+            "type": "Person",
+            "preferredUsername": "bob",
+            "inbox": "https://server.example/users/bob/inbox",
+            "publicKey": {
+                "id": "https://server.example/users/bob#main-key",
+                "owner": "https://server.example/users/bob",
+                "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+            }
+        });
+        let person: SecurityPerson = serde_json::from_value(v).unwrap();
+        assert!(person.assertion_method().is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_person_shared_inbox() {
+        let v = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/users/foo",
+                "type": "Person",
+                "preferredUsername": "foo",
+                "inbox": "https://example.com/users/foo/inbox",
+                "endpoints": {
+                    "sharedInbox": "https://example.com/inbox"
+                }
+            }
+        "#;
+
+        let person: Person = serde_json::from_str(v).unwrap();
+        assert_eq!(
+            person.shared_inbox().map(|v| v.as_str()),
+            Some("https://example.com/inbox")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_person_profile() {
+        let v = r#"
+            {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/user/foo",
+                "type": "Person",
+                "preferredUsername": "foo",
+                "name": "Foo",
+                "summary": "<p>hello</p>",
+                "manuallyApprovesFollowers": true,
+                "inbox": "https://example.com/user/foo/inbox",
+                "icon": {
+                    "type": "Image",
+                    "mediaType": "image/png",
+                    "url": "https://example.com/foo.png"
+                },
+                "attachment": [
+                    {
+                        "type": "PropertyValue",
+                        "name": "Website",
+                        "value": "https://example.com"
+                    }
+                ]
+            }
+        "#;
+
+        let person: Person = serde_json::from_str(v).unwrap();
+        assert_eq!(person.display_name(), Some("Foo"));
+        assert_eq!(person.summary(), Some("<p>hello</p>"));
+        assert_eq!(
+            person.icon().map(|v| v.url().as_str()),
+            Some("https://example.com/foo.png")
+        );
+        assert_eq!(person.attachment().len(), 1);
+        assert_eq!(person.attachment()[0].name, "Website");
+        assert!(person.manually_approves_followers());
+    }
+
+    #[test]
+    fn test_person_public_keys() {
+        let v = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/users/foo",
+            "type": "Person",
+            "preferredUsername": "foo",
+            "inbox": "https://example.com/users/foo/inbox",
+            "publicKey": [
+                {
+                    "id": "https://example.com/users/foo#rsa-key-2",
+                    "owner": "https://example.com/users/foo",
+                    "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+                },
+                {
+                    "id": "https://example.com/users/foo#main-key",
+                    "owner": "https://example.com/users/foo",
+                    "publicKeyPem": "-----BEGIN PUBLIC KEY-----"
+                }
+            ]
+        });
+        let person: SecurityPerson = serde_json::from_value(v.clone()).unwrap();
+        assert_eq!(person.public_keys().len(), 2);
+        let old_key = "https://example.com/users/foo#main-key".parse().unwrap();
+        assert_eq!(person.find_public_key(&old_key).unwrap().id(), &old_key);
+        assert_eq!(serde_json::to_value(&person).unwrap(), v);
+
+        // 鍵が1つならオブジェクトのまま出力する
+        let person = SecurityPerson::builder()
+            .inner((*person).clone())
+            .public_key(SingleOrMany::from_vec(
+                vec![person.public_keys()[0].clone()],
+            ))
+            .build();
+        let value = serde_json::to_value(&person).unwrap();
+        assert_eq!(
+            value["publicKey"]["id"],
+            "https://example.com/users/foo#rsa-key-2"
+        );
+    }
 }
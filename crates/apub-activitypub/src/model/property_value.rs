@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::core::object::Object;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum PropertyValueKind {
+    #[default]
+    PropertyValue,
+}
+
+/// プロフィールの項目
+///
+/// See https://docs.joinmastodon.org/spec/activitypub/#PropertyValue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+pub struct PropertyValue {
+    #[serde(rename = "type")]
+    #[builder(default)]
+    kind: PropertyValueKind,
+    pub name: String,
+    pub value: String,
+}
+
+impl Object for PropertyValue {
+    type Kind = PropertyValueKind;
+}
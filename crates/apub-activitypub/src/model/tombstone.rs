@@ -0,0 +1,72 @@
+use apub_shared::model::id::UrlId;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use typed_builder::TypedBuilder;
+
+use crate::core::object::Object;
+
+use super::context::Context;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub enum TombstoneKind {
+    #[default]
+    Tombstone,
+}
+
+/// 削除された`Object`の代わりに置かれる`Tombstone`
+///
+/// See
+/// - https://www.w3.org/TR/activitypub/#delete-activity-outbox
+/// - https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tombstone
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct Tombstone {
+    #[serde(rename = "@context")]
+    context: Option<Context>,
+    #[builder(!default, setter(!strip_option))]
+    id: UrlId<Tombstone>,
+    #[serde(rename = "type")]
+    #[builder(setter(!strip_option))]
+    kind: TombstoneKind,
+    /// 削除される前の`type`
+    former_type: Option<String>,
+    /// 削除された日時
+    deleted: Option<String>,
+}
+
+impl Tombstone {
+    pub fn id(&self) -> &UrlId<Tombstone> {
+        &self.id
+    }
+}
+
+impl Object for Tombstone {
+    type Kind = TombstoneKind;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_serialize_tombstone() {
+        let tombstone = Tombstone::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id("https://example.com/notes/1".parse().unwrap())
+            .former_type("Note".to_string())
+            .deleted("2024-11-20T12:00:00Z".to_string())
+            .build();
+
+        let expected = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/notes/1",
+            "type": "Tombstone",
+            "formerType": "Note",
+            "deleted": "2024-11-20T12:00:00Z"
+        });
+        assert_eq!(serde_json::to_value(&tombstone).unwrap(), expected);
+    }
+}
@@ -8,6 +8,23 @@ pub enum SingleOrMany<T> {
     Single(T),
 }
 
+impl<T> SingleOrMany<T> {
+    /// 要素が1つなら`Single`、それ以外は`Many`にする
+    pub fn from_vec(values: Vec<T>) -> Self {
+        match <[T; 1]>::try_from(values) {
+            Ok([value]) => Self::Single(value),
+            Err(values) => Self::Many(values),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        match self {
+            Self::Many(values) => values,
+            Self::Single(value) => std::slice::from_ref(value),
+        }
+    }
+}
+
 impl<T> From<T> for SingleOrMany<T> {
     fn from(value: T) -> Self {
         Self::Single(value)
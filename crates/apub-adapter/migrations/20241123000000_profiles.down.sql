@@ -0,0 +1,10 @@
+-- Add down migration script here
+ALTER TABLE actors
+    DROP COLUMN IF EXISTS display_name;
+
+DROP TABLE IF EXISTS user_profile_fields;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS display_name,
+    DROP COLUMN IF EXISTS summary,
+    DROP COLUMN IF EXISTS icon_url;
@@ -0,0 +1,21 @@
+-- Add up migration script here
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS display_name TEXT,
+    ADD COLUMN IF NOT EXISTS summary TEXT,
+    ADD COLUMN IF NOT EXISTS icon_url TEXT CHECK (icon_url <> '');
+
+CREATE TABLE IF NOT EXISTS user_profile_fields (
+    user_id UUID NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users (user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+
+    PRIMARY KEY (user_id, position)
+);
+
+ALTER TABLE actors
+    ADD COLUMN IF NOT EXISTS display_name TEXT;
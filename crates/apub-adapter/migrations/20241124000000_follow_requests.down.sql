@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS follow_requests;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS manually_approves_followers;
@@ -0,0 +1,20 @@
+-- Add up migration script here
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS manually_approves_followers BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS follow_requests (
+    follower_actor_id UUID NOT NULL,
+    followed_user_id UUID NOT NULL,
+    follow_url TEXT NOT NULL CHECK (follow_url <> ''),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+
+    FOREIGN KEY (follower_actor_id) REFERENCES actors (actor_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+
+    FOREIGN KEY (followed_user_id) REFERENCES users (user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE,
+
+    PRIMARY KEY (follower_actor_id, followed_user_id)
+);
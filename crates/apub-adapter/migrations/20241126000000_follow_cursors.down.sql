@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS following_user_id_following_id_idx;
+ALTER TABLE following DROP COLUMN IF EXISTS following_id;
+
+DROP INDEX IF EXISTS actor_follows_followed_user_id_follow_id_idx;
+ALTER TABLE actor_follows DROP COLUMN IF EXISTS follow_id;
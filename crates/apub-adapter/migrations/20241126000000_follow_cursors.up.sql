@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE actor_follows ADD COLUMN follow_id BIGINT GENERATED ALWAYS AS IDENTITY;
+CREATE UNIQUE INDEX IF NOT EXISTS actor_follows_followed_user_id_follow_id_idx ON actor_follows (followed_user_id, follow_id);
+
+ALTER TABLE following ADD COLUMN following_id BIGINT GENERATED ALWAYS AS IDENTITY;
+CREATE UNIQUE INDEX IF NOT EXISTS following_user_id_following_id_idx ON following (user_id, following_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS notes_user_id_note_seq_idx;
+ALTER TABLE notes DROP COLUMN IF EXISTS note_seq;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE notes ADD COLUMN note_seq BIGINT GENERATED ALWAYS AS IDENTITY;
+CREATE UNIQUE INDEX IF NOT EXISTS notes_user_id_note_seq_idx ON notes (user_id, note_seq);
@@ -0,0 +1,7 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS sent_activities (
+    activity_id UUID PRIMARY KEY,
+    actor_url TEXT NOT NULL CHECK (actor_url <> ''),
+    activity JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE notes DROP COLUMN IF EXISTS visibility;
+ALTER TABLE notes DROP COLUMN IF EXISTS published;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TABLE notes ADD COLUMN published TIMESTAMPTZ;
+UPDATE notes SET published = created_at;
+ALTER TABLE notes ALTER COLUMN published SET NOT NULL;
+ALTER TABLE notes ALTER COLUMN published SET DEFAULT current_timestamp;
+
+ALTER TABLE notes ADD COLUMN visibility TEXT NOT NULL DEFAULT 'public'
+    CHECK (visibility IN ('public', 'unlisted', 'followers'));
@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS instances (
+    host TEXT PRIMARY KEY CHECK (host <> ''),
+    failure_count INTEGER NOT NULL DEFAULT 0,
+    -- 最後に成功してから最初に失敗した時刻
+    failing_since TIMESTAMPTZ,
+    last_failure_at TIMESTAMPTZ,
+    last_success_at TIMESTAMPTZ,
+    unreachable_since TIMESTAMPTZ,
+    next_probe_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS inbox_jobs (
+    job_id UUID PRIMARY KEY,
+    -- 同じActivityを二度処理しないよう`id`で重複を除く
+    activity_id TEXT UNIQUE,
+    -- 共有`inbox`で受け取った場合は`NULL`
+    user_id UUID,
+    activity JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'done', 'dead')),
+    last_error TEXT,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+    locked_until TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+
+    FOREIGN KEY (user_id) REFERENCES users (user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS inbox_jobs_due_idx ON inbox_jobs (next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS actor_ed25519_keys (
+    actor_id UUID NOT NULL,
+    key_url TEXT NOT NULL,
+    -- `publicKeyMultibase`の値
+    public_key TEXT NOT NULL CHECK (public_key <> ''),
+    private_key TEXT CHECK (private_key <> ''),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT current_timestamp,
+
+    PRIMARY KEY (actor_id, key_url),
+
+    FOREIGN KEY (actor_id) REFERENCES actors (actor_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
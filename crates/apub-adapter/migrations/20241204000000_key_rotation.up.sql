@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE actor_rsa_keys
+    -- 新しい鍵に置き換えられた日時
+    ADD COLUMN retired_at TIMESTAMPTZ,
+    -- 置き換えられた鍵で署名を検証できる期限
+    ADD COLUMN expires_at TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS actor_rsa_keys_key_url_idx;
@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- 同じ鍵のURLを複数の`Actor`が持たないようにする。重複していれば新しいものを残す
+DELETE FROM actor_rsa_keys AS old
+USING actor_rsa_keys AS new
+WHERE
+    old.key_url = new.key_url
+    AND (old.created_at, old.actor_id) < (new.created_at, new.actor_id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS actor_rsa_keys_key_url_idx ON actor_rsa_keys (key_url);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE actor_ed25519_keys
+    DROP COLUMN IF EXISTS expires_at,
+    DROP COLUMN IF EXISTS retired_at;
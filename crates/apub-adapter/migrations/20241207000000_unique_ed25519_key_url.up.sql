@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- Ed25519の鍵もURLから探して署名を検証するので、同じ鍵のURLを複数の`Actor`が持たないようにする
+DELETE FROM actor_ed25519_keys AS old
+USING actor_ed25519_keys AS new
+WHERE
+    old.key_url = new.key_url
+    AND (old.created_at, old.actor_id) < (new.created_at, new.actor_id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS actor_ed25519_keys_key_url_idx ON actor_ed25519_keys (key_url);
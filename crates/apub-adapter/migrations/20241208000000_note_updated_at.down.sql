@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE notes DROP COLUMN IF EXISTS updated_at;
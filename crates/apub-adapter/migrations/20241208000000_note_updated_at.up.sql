@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- 編集されていない`Note`は`NULL`のままにする
+ALTER TABLE notes ADD COLUMN updated_at TIMESTAMPTZ;
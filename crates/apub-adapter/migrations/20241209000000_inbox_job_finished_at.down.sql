@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS inbox_jobs_finished_idx;
+ALTER TABLE inbox_jobs DROP COLUMN IF EXISTS finished_at;
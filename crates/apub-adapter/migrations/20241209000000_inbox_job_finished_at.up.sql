@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- 処理し終えたジョブは重複の判定のためにしばらく残し、この時刻から一定期間が経ったら消す
+ALTER TABLE inbox_jobs ADD COLUMN finished_at TIMESTAMPTZ;
+UPDATE inbox_jobs SET finished_at = created_at WHERE status IN ('done', 'dead');
+
+CREATE INDEX IF NOT EXISTS inbox_jobs_finished_idx ON inbox_jobs (finished_at)
+    WHERE status IN ('done', 'dead');
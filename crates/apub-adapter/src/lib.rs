@@ -1,3 +1,4 @@
 pub(crate) mod model;
 pub mod persistence;
 pub mod repository;
+pub mod worker;
@@ -1,5 +1,12 @@
 pub(crate) mod actor;
+pub(crate) mod delivery;
+pub(crate) mod follow_request;
 pub(crate) mod follower;
+pub(crate) mod following;
+pub(crate) mod inbox;
+pub(crate) mod like;
 pub(crate) mod note;
 pub(crate) mod rsa_key;
+pub(crate) mod sent_activity;
+pub(crate) mod share;
 pub(crate) mod user;
@@ -8,6 +8,7 @@ pub struct ActorRow {
     pub actor_id: Uuid,
     pub actor_url: String,
     pub preferred_username: String,
+    pub display_name: Option<String>,
     pub inbox_url: String,
     pub shared_inbox_url: Option<String>,
     pub local_user_id: Option<Uuid>,
@@ -30,7 +31,7 @@ impl TryFrom<ActorRow> for Actor {
             .preferred_name(row.preferred_username)
             .local_id(local_user_id)
             .shared_inbox(shared_inbox_url)
-            .display_name(None)
+            .display_name(row.display_name)
             .build();
         Ok(a)
     }
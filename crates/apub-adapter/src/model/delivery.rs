@@ -0,0 +1,33 @@
+use apub_kernel::delivery::model::DeliveryJob;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct DeliveryJobRow {
+    pub job_id: Uuid,
+    pub user_id: Uuid,
+    pub inbox_url: String,
+    pub activity: String,
+    pub attempts: i32,
+}
+
+impl TryFrom<DeliveryJobRow> for DeliveryJob {
+    type Error = anyhow::Error;
+    fn try_from(value: DeliveryJobRow) -> Result<Self, Self::Error> {
+        let DeliveryJobRow {
+            job_id,
+            user_id,
+            inbox_url,
+            activity,
+            attempts,
+        } = value;
+
+        let job = DeliveryJob::builder()
+            .id(job_id.into())
+            .user_id(user_id.into())
+            .inbox(inbox_url.parse::<ResourceUrl>()?)
+            .activity(serde_json::from_str(&activity)?)
+            .attempts(attempts)
+            .build();
+        Ok(job)
+    }
+}
@@ -0,0 +1,27 @@
+use apub_kernel::follow_request::model::FollowRequest;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct FollowRequestRow {
+    pub user_id: Uuid,
+    pub actor_url: String,
+    pub follow_url: String,
+}
+
+impl TryFrom<FollowRequestRow> for FollowRequest {
+    type Error = anyhow::Error;
+    fn try_from(value: FollowRequestRow) -> Result<Self, Self::Error> {
+        let FollowRequestRow {
+            user_id,
+            actor_url,
+            follow_url,
+        } = value;
+
+        let request = FollowRequest::builder()
+            .user_id(user_id.into())
+            .actor_url(actor_url.parse::<ResourceUrl>()?)
+            .follow_url(follow_url.parse::<ResourceUrl>()?)
+            .build();
+        Ok(request)
+    }
+}
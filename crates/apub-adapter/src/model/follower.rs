@@ -19,6 +19,7 @@ pub struct FollowerRow {
     pub host: String,
     pub preferred_username: String,
     pub inbox_url: String,
+    pub shared_inbox_url: Option<String>,
 }
 
 impl TryFrom<FollowerRow> for Follower {
@@ -29,6 +30,7 @@ impl TryFrom<FollowerRow> for Follower {
             follower_url,
             host,
             inbox_url,
+            shared_inbox_url,
             preferred_username,
             ..
         } = value;
@@ -36,14 +38,53 @@ impl TryFrom<FollowerRow> for Follower {
         let user_id = user_id.into();
         let actor_url = follower_url.parse::<ResourceUrl>()?;
         let inbox_url = inbox_url.parse::<ResourceUrl>()?;
+        let shared_inbox_url = shared_inbox_url
+            .map(|v| v.parse::<ResourceUrl>())
+            .transpose()?;
         let acct = AcctUri::new(host, preferred_username)?;
 
         let follower = Follower::builder()
             .acct(acct)
             .inbox(inbox_url)
+            .shared_inbox(shared_inbox_url)
             .actor_url(actor_url)
             .user_id(user_id)
             .build();
         Ok(follower)
     }
 }
+
+/// ページ取得用に`follow_id`を含めた`FollowerRow`
+pub struct FollowerCursorRow {
+    pub follow_id: i64,
+    pub user_id: Uuid,
+    pub follower_url: String,
+    pub host: String,
+    pub preferred_username: String,
+    pub inbox_url: String,
+    pub shared_inbox_url: Option<String>,
+}
+
+impl FollowerCursorRow {
+    pub fn into_entry(self) -> anyhow::Result<(i64, Follower)> {
+        let FollowerCursorRow {
+            follow_id,
+            user_id,
+            follower_url,
+            host,
+            preferred_username,
+            inbox_url,
+            shared_inbox_url,
+        } = self;
+        let row = FollowerRow {
+            user_id,
+            follower_url,
+            host,
+            preferred_username,
+            inbox_url,
+            shared_inbox_url,
+        };
+
+        Ok((follow_id, row.try_into()?))
+    }
+}
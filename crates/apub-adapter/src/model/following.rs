@@ -0,0 +1,59 @@
+use apub_kernel::following::model::Following;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct FollowingRow {
+    pub user_id: Uuid,
+    pub actor_url: String,
+    pub follow_url: String,
+    pub accepted: bool,
+}
+
+impl TryFrom<FollowingRow> for Following {
+    type Error = anyhow::Error;
+    fn try_from(value: FollowingRow) -> Result<Self, Self::Error> {
+        let FollowingRow {
+            user_id,
+            actor_url,
+            follow_url,
+            accepted,
+        } = value;
+
+        let following = Following::builder()
+            .user_id(user_id.into())
+            .actor_url(actor_url.parse::<ResourceUrl>()?)
+            .follow_url(follow_url.parse::<ResourceUrl>()?)
+            .accepted(accepted)
+            .build();
+        Ok(following)
+    }
+}
+
+/// ページ取得用に`following_id`を含めた`FollowingRow`
+pub struct FollowingCursorRow {
+    pub following_id: i64,
+    pub user_id: Uuid,
+    pub actor_url: String,
+    pub follow_url: String,
+    pub accepted: bool,
+}
+
+impl FollowingCursorRow {
+    pub fn into_entry(self) -> anyhow::Result<(i64, Following)> {
+        let FollowingCursorRow {
+            following_id,
+            user_id,
+            actor_url,
+            follow_url,
+            accepted,
+        } = self;
+        let row = FollowingRow {
+            user_id,
+            actor_url,
+            follow_url,
+            accepted,
+        };
+
+        Ok((following_id, row.try_into()?))
+    }
+}
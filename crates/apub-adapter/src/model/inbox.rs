@@ -0,0 +1,29 @@
+use apub_kernel::inbox::model::InboxJob;
+use sqlx::types::Uuid;
+
+pub struct InboxJobRow {
+    pub job_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub activity: String,
+    pub attempts: i32,
+}
+
+impl TryFrom<InboxJobRow> for InboxJob {
+    type Error = anyhow::Error;
+    fn try_from(value: InboxJobRow) -> Result<Self, Self::Error> {
+        let InboxJobRow {
+            job_id,
+            user_id,
+            activity,
+            attempts,
+        } = value;
+
+        let job = InboxJob::builder()
+            .id(job_id.into())
+            .user_id(user_id.map(Into::into))
+            .activity(serde_json::from_str(&activity)?)
+            .attempts(attempts)
+            .build();
+        Ok(job)
+    }
+}
@@ -0,0 +1,21 @@
+use apub_kernel::like::model::Like;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct LikeRow {
+    pub note_id: Uuid,
+    pub actor_url: String,
+}
+
+impl TryFrom<LikeRow> for Like {
+    type Error = anyhow::Error;
+    fn try_from(value: LikeRow) -> Result<Self, Self::Error> {
+        let LikeRow { note_id, actor_url } = value;
+
+        let like = Like::builder()
+            .note_id(note_id.into())
+            .actor_url(actor_url.parse::<ResourceUrl>()?)
+            .build();
+        Ok(like)
+    }
+}
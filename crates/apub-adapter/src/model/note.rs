@@ -1,24 +1,91 @@
-use apub_kernel::note::model::Note;
+use apub_kernel::note::model::{DeletedNote, Note};
 use sqlx::types::Uuid;
 
 pub struct NoteRow {
     pub note_id: Uuid,
     pub user_id: Uuid,
     pub content: String,
+    pub published: String,
+    pub updated: Option<String>,
+    pub visibility: String,
 }
 
-impl From<NoteRow> for Note {
-    fn from(value: NoteRow) -> Self {
+impl TryFrom<NoteRow> for Note {
+    type Error = anyhow::Error;
+    fn try_from(value: NoteRow) -> Result<Self, Self::Error> {
         let NoteRow {
             note_id,
             user_id,
             content,
+            published,
+            updated,
+            visibility,
         } = value;
 
-        Note {
+        Ok(Note {
             id: note_id.into(),
             user_id: user_id.into(),
             content,
+            published,
+            updated,
+            visibility: visibility.parse()?,
+        })
+    }
+}
+
+/// ページ取得用に`note_seq`を含めた`NoteRow`
+pub struct NoteCursorRow {
+    pub note_seq: i64,
+    pub note_id: Uuid,
+    pub user_id: Uuid,
+    pub content: String,
+    pub published: String,
+    pub updated: Option<String>,
+    pub visibility: String,
+}
+
+impl NoteCursorRow {
+    pub fn into_entry(self) -> anyhow::Result<(i64, Note)> {
+        let NoteCursorRow {
+            note_seq,
+            note_id,
+            user_id,
+            content,
+            published,
+            updated,
+            visibility,
+        } = self;
+        let row = NoteRow {
+            note_id,
+            user_id,
+            content,
+            published,
+            updated,
+            visibility,
+        };
+
+        Ok((note_seq, row.try_into()?))
+    }
+}
+
+pub struct DeletedNoteRow {
+    pub note_id: Uuid,
+    pub user_id: Uuid,
+    pub deleted_at: String,
+}
+
+impl From<DeletedNoteRow> for DeletedNote {
+    fn from(value: DeletedNoteRow) -> Self {
+        let DeletedNoteRow {
+            note_id,
+            user_id,
+            deleted_at,
+        } = value;
+
+        DeletedNote {
+            id: note_id.into(),
+            user_id: user_id.into(),
+            deleted_at,
         }
     }
 }
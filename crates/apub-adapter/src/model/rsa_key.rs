@@ -1,4 +1,10 @@
-use apub_kernel::rsa_key::model::{RsaSingingKey, RsaVerifyingKey};
+use std::str::FromStr;
+
+use apub_kernel::rsa_key::model::{
+    ActorEd25519PublicKey, ActorPublicKey, Ed25519SigningKey, Ed25519VerifyingKey, RsaSingingKey,
+    RsaVerifyingKey,
+};
+use apub_shared::model::resource_url::ResourceUrl;
 
 pub struct UserPublicRsaKeyRow {
     pub public_key: String,
@@ -12,6 +18,7 @@ impl TryFrom<UserPublicRsaKeyRow> for RsaVerifyingKey {
 }
 
 pub struct UserPrivateRsaKeyRow {
+    pub key_url: Option<String>,
     pub private_key: Option<String>,
 }
 
@@ -24,3 +31,69 @@ impl TryFrom<UserPrivateRsaKeyRow> for RsaSingingKey {
         }
     }
 }
+
+pub struct ActorPublicKeyRow {
+    pub actor_url: String,
+    pub key_url: String,
+    pub public_key: String,
+}
+
+impl TryFrom<ActorPublicKeyRow> for ActorPublicKey {
+    type Error = anyhow::Error;
+    fn try_from(row: ActorPublicKeyRow) -> Result<Self, Self::Error> {
+        let actor_url = ResourceUrl::from_str(&row.actor_url)?;
+        let key_url = ResourceUrl::from_str(&row.key_url)?;
+        let public_key = RsaVerifyingKey::from_pem(&row.public_key)?;
+        Ok(ActorPublicKey::builder()
+            .actor_url(actor_url)
+            .key_url(key_url)
+            .public_key(public_key)
+            .build())
+    }
+}
+
+pub struct UserPublicEd25519KeyRow {
+    pub public_key: String,
+}
+
+impl TryFrom<UserPublicEd25519KeyRow> for Ed25519VerifyingKey {
+    type Error = anyhow::Error;
+    fn try_from(row: UserPublicEd25519KeyRow) -> Result<Self, Self::Error> {
+        Ed25519VerifyingKey::from_multibase(&row.public_key)
+    }
+}
+
+pub struct ActorEd25519PublicKeyRow {
+    pub actor_url: String,
+    pub key_url: String,
+    pub public_key: String,
+}
+
+impl TryFrom<ActorEd25519PublicKeyRow> for ActorEd25519PublicKey {
+    type Error = anyhow::Error;
+    fn try_from(row: ActorEd25519PublicKeyRow) -> Result<Self, Self::Error> {
+        let actor_url = ResourceUrl::from_str(&row.actor_url)?;
+        let key_url = ResourceUrl::from_str(&row.key_url)?;
+        let public_key = Ed25519VerifyingKey::from_multibase(&row.public_key)?;
+        Ok(ActorEd25519PublicKey::builder()
+            .actor_url(actor_url)
+            .key_url(key_url)
+            .public_key(public_key)
+            .build())
+    }
+}
+
+pub struct UserPrivateEd25519KeyRow {
+    pub key_url: String,
+    pub private_key: Option<String>,
+}
+
+impl TryFrom<UserPrivateEd25519KeyRow> for Ed25519SigningKey {
+    type Error = anyhow::Error;
+    fn try_from(row: UserPrivateEd25519KeyRow) -> Result<Self, Self::Error> {
+        match row.private_key {
+            Some(ref k) => Ed25519SigningKey::from_pkcs8(k),
+            None => Err(anyhow::anyhow!("private key is not found")),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use apub_kernel::sent_activity::model::SentActivity;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct SentActivityRow {
+    pub activity_id: Uuid,
+    pub actor_url: String,
+    pub activity: String,
+}
+
+impl TryFrom<SentActivityRow> for SentActivity {
+    type Error = anyhow::Error;
+    fn try_from(value: SentActivityRow) -> Result<Self, Self::Error> {
+        let SentActivityRow {
+            activity_id,
+            actor_url,
+            activity,
+        } = value;
+
+        let activity = SentActivity::builder()
+            .id(activity_id.into())
+            .actor_url(actor_url.parse::<ResourceUrl>()?)
+            .activity(serde_json::from_str(&activity)?)
+            .build();
+        Ok(activity)
+    }
+}
@@ -0,0 +1,21 @@
+use apub_kernel::share::model::Share;
+use apub_shared::model::resource_url::ResourceUrl;
+use sqlx::types::Uuid;
+
+pub struct ShareRow {
+    pub note_id: Uuid,
+    pub actor_url: String,
+}
+
+impl TryFrom<ShareRow> for Share {
+    type Error = anyhow::Error;
+    fn try_from(value: ShareRow) -> Result<Self, Self::Error> {
+        let ShareRow { note_id, actor_url } = value;
+
+        let share = Share::builder()
+            .note_id(note_id.into())
+            .actor_url(actor_url.parse::<ResourceUrl>()?)
+            .build();
+        Ok(share)
+    }
+}
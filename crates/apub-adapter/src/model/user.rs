@@ -1,4 +1,5 @@
-use apub_kernel::user::model::User;
+use apub_kernel::user::model::{ProfileField, User, UserProfile};
+use apub_shared::model::resource_url::ResourceUrl;
 use sqlx::types::Uuid;
 
 pub struct UserRow {
@@ -12,3 +13,42 @@ impl From<UserRow> for User {
         User::builder().name(name).id(user_id.into()).build()
     }
 }
+
+pub struct UserProfileRow {
+    pub display_name: Option<String>,
+    pub summary: Option<String>,
+    pub icon_url: Option<String>,
+    pub manually_approves_followers: bool,
+}
+
+pub struct ProfileFieldRow {
+    pub name: String,
+    pub value: String,
+}
+
+impl From<ProfileFieldRow> for ProfileField {
+    fn from(value: ProfileFieldRow) -> Self {
+        let ProfileFieldRow { name, value } = value;
+        ProfileField::builder().name(name).value(value).build()
+    }
+}
+
+impl UserProfileRow {
+    pub fn into_profile(self, fields: Vec<ProfileFieldRow>) -> anyhow::Result<UserProfile> {
+        let UserProfileRow {
+            display_name,
+            summary,
+            icon_url,
+            manually_approves_followers,
+        } = self;
+        let icon = icon_url.map(|v| v.parse::<ResourceUrl>()).transpose()?;
+
+        Ok(UserProfile {
+            display_name,
+            summary,
+            icon,
+            fields: fields.into_iter().map(ProfileField::from).collect(),
+            manually_approves_followers,
+        })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod http_client;
+pub mod key_encryption;
 pub mod postgres;
@@ -1,14 +1,85 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use apub_kernel::rsa_key::http_signature::SignatureScheme;
 use reqwest::Client;
 
-#[derive(Clone, Debug, Default)]
-pub struct HttpClient(Client);
+/// 応答のないサーバを待つ時間
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+pub struct HttpClient {
+    client: Client,
+    /// ホストごとに受け入れられた署名の形式
+    ///
+    /// メモリ上のキャッシュでDBには保存しない。再起動後は既定の形式から試し、
+    /// 401が返ればもう一方の形式で送り直して覚え直す
+    schemes: Arc<RwLock<HashMap<String, SignatureScheme>>>,
+    /// ホストごとにEd25519の署名を受け入れたかどうか
+    ///
+    /// `schemes`と同じくメモリ上のキャッシュで、まだ送っていないホストは記録がない
+    ed25519: Arc<RwLock<HashMap<String, bool>>>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl HttpClient {
     pub fn new() -> Self {
-        Default::default()
+        Self::with_timeout(REQUEST_TIMEOUT)
+    }
+
+    /// 応答を`timeout`まで待つクライアントを作る
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("failed to build http client");
+        Self {
+            client,
+            schemes: Default::default(),
+            ed25519: Default::default(),
+        }
     }
 
     pub(crate) fn inner_ref(&self) -> &Client {
-        &self.0
+        &self.client
+    }
+
+    /// `host`に最初に試す署名の形式
+    pub(crate) fn signature_scheme(&self, host: &str) -> SignatureScheme {
+        self.schemes
+            .read()
+            .ok()
+            .and_then(|schemes| schemes.get(host).copied())
+            .unwrap_or_default()
+    }
+
+    /// `host`で受け入れられた署名の形式を記録する
+    pub(crate) fn record_signature_scheme(&self, host: &str, scheme: SignatureScheme) {
+        if let Ok(mut schemes) = self.schemes.write() {
+            schemes.insert(host.to_string(), scheme);
+        }
+    }
+
+    /// `host`がEd25519の署名を受け入れたかどうか。まだ送っていなければ`None`
+    pub(crate) fn accepts_ed25519(&self, host: &str) -> Option<bool> {
+        self.ed25519
+            .read()
+            .ok()
+            .and_then(|ed25519| ed25519.get(host).copied())
+    }
+
+    /// `host`がEd25519の署名を受け入れたかどうかを記録する
+    pub(crate) fn record_ed25519(&self, host: &str, accepted: bool) {
+        if let Ok(mut ed25519) = self.ed25519.write() {
+            ed25519.insert(host.to_string(), accepted);
+        }
     }
 }
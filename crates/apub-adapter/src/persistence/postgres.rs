@@ -1,22 +1,76 @@
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
+use super::key_encryption::MasterKey;
+
 /// `Pool`は内部で`Arc`を使っているのでここで包む必要はない
 #[derive(Clone, Debug)]
-pub struct PostgresDb(PgPool);
+pub struct PostgresDb {
+    pool: PgPool,
+    /// 設定されていれば秘密鍵を暗号化して保存する
+    master_key: Option<MasterKey>,
+}
 
 impl PostgresDb {
     pub fn new(pool: PgPool) -> Self {
-        Self(pool)
+        Self {
+            pool,
+            master_key: None,
+        }
     }
 
     pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
         let pool: sqlx::Pool<sqlx::Postgres> = PgPoolOptions::new().connect(database_url).await?;
 
-        Ok(Self(pool))
+        Ok(Self::new(pool))
+    }
+
+    pub fn with_master_key(mut self, master_key: MasterKey) -> Self {
+        self.master_key = Some(master_key);
+        self
     }
 
     pub(crate) fn inner_ref(&self) -> &PgPool {
-        &self.0
+        &self.pool
+    }
+
+    /// 秘密鍵をDBに保存する形にする
+    ///
+    /// マスター鍵がなければそのまま保存する。暗号化するときは鍵のURLを関連データにする
+    pub(crate) fn seal_private_key(
+        &self,
+        private_key: &str,
+        key_url: &str,
+    ) -> anyhow::Result<String> {
+        match self.master_key {
+            Some(ref master_key) => master_key.encrypt(private_key, key_url.as_bytes()),
+            None => Ok(private_key.to_string()),
+        }
+    }
+
+    /// DBから読んだ秘密鍵を戻す
+    ///
+    /// 暗号化する前に保存された平文の秘密鍵はそのまま返す
+    pub(crate) fn open_private_key(
+        &self,
+        private_key: Option<String>,
+        key_url: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(private_key) = private_key else {
+            return Ok(None);
+        };
+        if !MasterKey::is_encrypted(&private_key) {
+            return Ok(Some(private_key));
+        }
+        match self.master_key {
+            Some(ref master_key) => master_key
+                .decrypt(&private_key, key_url.as_bytes())
+                .map(Some),
+            None => Err(anyhow::anyhow!("master key is not configured")),
+        }
+    }
+
+    pub(crate) fn master_key(&self) -> Option<&MasterKey> {
+        self.master_key.as_ref()
     }
 }
 
@@ -1,7 +1,15 @@
 pub mod activity;
 pub mod actor;
+pub mod delivery;
+pub mod follow_request;
 pub mod follower;
+pub mod following;
+pub mod inbox;
+pub mod instance;
+pub mod like;
 pub mod note;
 pub mod rsa_key;
+pub mod sent_activity;
+pub mod share;
 pub mod user;
 pub mod webfinger;
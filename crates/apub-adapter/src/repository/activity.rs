@@ -1,66 +1,100 @@
 use crate::persistence::http_client::HttpClient;
 use apub_activitypub::shared::activity_json::APPLICATION_ACTIVITY_JSON;
-use apub_kernel::{activitypub::activity::ActivityRepository, rsa_key::model::RsaSingingKey};
+use apub_kernel::{
+    activitypub::activity::ActivityRepository,
+    delivery::model::DeliveryError,
+    rsa_key::{
+        http_signature::HttpSigner,
+        model::{HttpSigningKey, RsaSingingKey},
+    },
+};
 use apub_shared::model::resource_url::ResourceUrl;
-use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
-use base64::{prelude::BASE64_STANDARD, Engine};
+use axum::http::{header, HeaderMap, Method, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
-use sha2::{Digest, Sha256};
+
+/// 署名してリクエストを送る
+///
+/// 401が返ってきた場合はもう一方の形式で署名し直して送る(double-knocking)
+async fn send_signed(
+    client: &HttpClient,
+    method: Method,
+    req: &ResourceUrl,
+    headers: HeaderMap,
+    body: Option<Vec<u8>>,
+    signer: &dyn HttpSigningKey,
+    key_uri: &ResourceUrl,
+) -> anyhow::Result<reqwest::Response> {
+    let host = req.host();
+    let first = client.signature_scheme(host);
+
+    let mut target = req.clone();
+    target.clear_fragment();
+
+    for scheme in [first, first.other()] {
+        let mut headers = headers.clone();
+        HttpSigner::new(signer, key_uri).with_scheme(scheme).sign(
+            &method,
+            &target,
+            &mut headers,
+            body.as_deref(),
+        )?;
+
+        let mut builder = client
+            .inner_ref()
+            .request(method.clone(), target.as_str())
+            .headers(headers);
+        if let Some(body) = &body {
+            builder = builder.body(body.clone());
+        }
+        let res = builder
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Network(e.to_string()))?;
+
+        if res.status() == StatusCode::UNAUTHORIZED && scheme == first {
+            tracing::info!(
+                ?scheme,
+                host,
+                "signature rejected, retrying with other scheme"
+            );
+            continue;
+        }
+        if res.status().is_success() {
+            client.record_signature_scheme(host, scheme);
+        }
+        return Ok(res);
+    }
+
+    unreachable!("the second attempt always returns")
+}
 
 /// Activityを署名して送信する
+///
+/// 2xx以外が返ってきた場合は`DeliveryError::Status`を返す
 async fn post_activity<T: Serialize>(
     client: &HttpClient,
     activity: &T,
     inbox: &ResourceUrl,
-    signer: &RsaSingingKey,
+    signer: &dyn HttpSigningKey,
     key_uri: &ResourceUrl,
 ) -> anyhow::Result<()> {
     let body = serde_json::to_vec(&activity)?;
 
-    // Mastdon needs digest
-    // https://docs.joinmastodon.org/spec/security/#digest
-    let digest = Sha256::digest(&body);
-    let digest_64 = format!("SHA-256={}", BASE64_STANDARD.encode(digest));
-
-    let date = httpdate::fmt_http_date(std::time::SystemTime::now());
-    let signed_string = format!(
-        "(request-target): post {}\nhost: {}\ndate: {}\ndigest: {}",
-        inbox.path(),
-        inbox.host(),
-        date,
-        digest_64
-    );
-
-    let signature = signer.sign(signed_string.as_bytes());
-    let signature_64 = BASE64_STANDARD.encode(signature);
-
-    let signature_header = format!(
-        r#"keyId="{}",algorithm="rsa-sha256",headers="(request-target) host date digest",signature="{}""#,
-        key_uri, signature_64
-    );
-
     let headers = HeaderMap::from_iter([
         (header::CONTENT_TYPE, APPLICATION_ACTIVITY_JSON),
         (header::ACCEPT, APPLICATION_ACTIVITY_JSON),
-        (header::HOST, HeaderValue::from_str(inbox.host()).unwrap()),
-        (header::DATE, HeaderValue::from_str(&date).unwrap()),
-        (
-            HeaderName::from_static("signature"),
-            HeaderValue::from_str(&signature_header).unwrap(),
-        ),
-        (
-            HeaderName::from_static("digest"),
-            HeaderValue::from_str(&digest_64).unwrap(),
-        ),
     ]);
 
-    let res = client
-        .inner_ref()
-        .post(inbox.to_string())
-        .headers(headers)
-        .body(body)
-        .send()
-        .await?;
+    let res = send_signed(
+        client,
+        Method::POST,
+        inbox,
+        headers,
+        Some(body),
+        signer,
+        key_uri,
+    )
+    .await?;
     let res_status = res.status();
 
     let body = serde_json::to_string_pretty(&activity).unwrap();
@@ -72,6 +106,10 @@ async fn post_activity<T: Serialize>(
         status_code = ?res_status
     );
 
+    if !res_status.is_success() {
+        return Err(DeliveryError::Status(res_status.as_u16()).into());
+    }
+
     Ok(())
 }
 
@@ -98,38 +136,9 @@ async fn get_activity_with_sign<T: DeserializeOwned>(
     signer: &RsaSingingKey,
     key_uri: &ResourceUrl,
 ) -> anyhow::Result<T> {
-    let date = httpdate::fmt_http_date(std::time::SystemTime::now());
-    let signed_string = format!(
-        "(request-target): get {}\nhost: {}\ndate: {}",
-        req.path(),
-        req.host(),
-        date,
-    );
-
-    let signature = signer.sign(signed_string.as_bytes());
-    let signature_64 = BASE64_STANDARD.encode(signature);
-
-    let signature_header = format!(
-        r#"keyId="{}",algorithm="rsa-sha256",headers="(request-target) host date digest",signature="{}""#,
-        key_uri, signature_64
-    );
-
-    let headers = HeaderMap::from_iter([
-        (header::ACCEPT, APPLICATION_ACTIVITY_JSON),
-        (header::HOST, HeaderValue::from_str(req.host()).unwrap()),
-        (header::DATE, HeaderValue::from_str(&date).unwrap()),
-        (
-            HeaderName::from_static("signature"),
-            HeaderValue::from_str(&signature_header).unwrap(),
-        ),
-    ]);
+    let headers = HeaderMap::from_iter([(header::ACCEPT, APPLICATION_ACTIVITY_JSON)]);
 
-    let res = client
-        .inner_ref()
-        .get(req.as_str())
-        .headers(headers)
-        .send()
-        .await?;
+    let res = send_signed(client, Method::GET, req, headers, None, signer, key_uri).await?;
 
     let res_status = res.status();
 
@@ -151,7 +160,7 @@ impl ActivityRepository for HttpClient {
         &self,
         activity: &T,
         inbox: &ResourceUrl,
-        signer: &RsaSingingKey,
+        signer: &dyn HttpSigningKey,
         key_uri: &ResourceUrl,
     ) -> anyhow::Result<()> {
         post_activity(self, activity, inbox, signer, key_uri).await
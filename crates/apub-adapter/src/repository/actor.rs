@@ -1,5 +1,7 @@
 use apub_activitypub::webfinger::AcctUri;
-use apub_kernel::activitypub::actor::{Actor, ActorId, ActorRepository, CreateActorEvent};
+use apub_kernel::activitypub::actor::{
+    Actor, ActorId, ActorRepository, CreateActorEvent, UpdateActorEvent,
+};
 use apub_shared::model::resource_url::ResourceUrl;
 
 use crate::{model::actor::ActorRow, persistence::postgres::PostgresDb};
@@ -12,7 +14,7 @@ impl ActorRepository for PostgresDb {
             ActorRow,
             r#"
         SELECT 
-            actor_id, actor_url, preferred_username, inbox_url, shared_inbox_url, local_user_id
+            actor_id, actor_url, preferred_username, display_name, inbox_url, shared_inbox_url, local_user_id
         FROM
             actors
         WHERE
@@ -31,14 +33,14 @@ impl ActorRepository for PostgresDb {
             ActorRow,
             r#"
         SELECT 
-            actor_id, actor_url, preferred_username, inbox_url, shared_inbox_url, local_user_id
+            actor_id, actor_url, preferred_username, display_name, inbox_url, shared_inbox_url, local_user_id
         FROM
             actors
         WHERE
             actors.host = $1 AND actors.preferred_username = $2
         "#,
-            name,
-            host
+            host,
+            name
         )
         .fetch_one(self.inner_ref())
         .await?;
@@ -49,7 +51,7 @@ impl ActorRepository for PostgresDb {
             ActorRow,
             r#"
         SELECT 
-            actor_id, actor_url, preferred_username, inbox_url, shared_inbox_url, local_user_id
+            actor_id, actor_url, preferred_username, display_name, inbox_url, shared_inbox_url, local_user_id
         FROM
             actors
         WHERE
@@ -69,14 +71,15 @@ impl ActorRepository for PostgresDb {
         sqlx::query!(
             r#"
             INSERT INTO actors
-                (actor_id, actor_url, host, preferred_username, inbox_url, shared_inbox_url, local_user_id)
+                (actor_id, actor_url, host, preferred_username, display_name, inbox_url, shared_inbox_url, local_user_id)
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7)
+                ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
             actor.actor_id.as_ref(),
             actor.actor_url.as_str(),
             host,
             actor.preferred_name,
+            actor.display_name,
             actor.inbox.as_str(),
             shared_inbox,
             local_id
@@ -84,4 +87,115 @@ impl ActorRepository for PostgresDb {
 
         Ok(actor)
     }
+    async fn update(&self, event: UpdateActorEvent) -> anyhow::Result<Actor> {
+        let row = sqlx::query_as!(
+            ActorRow,
+            r#"
+            UPDATE
+                actors
+            SET
+                preferred_username = $2,
+                display_name = $3,
+                inbox_url = $4,
+                shared_inbox_url = $5
+            WHERE
+                actors.actor_url = $1
+                AND actors.local_user_id IS NULL
+            RETURNING
+                actor_id, actor_url, preferred_username, display_name, inbox_url, shared_inbox_url, local_user_id
+            "#,
+            event.actor_url.as_str(),
+            event.preferred_name,
+            event.display_name,
+            event.inbox.as_str(),
+            event.shared_inbox.as_ref().map(|v| v.as_str())
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+        row.try_into()
+    }
+    async fn delete(&self, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        let count = sqlx::query!(
+            r#"
+            DELETE FROM
+                actors
+            WHERE
+                actors.actor_url = $1
+                AND actors.local_user_id IS NULL
+            "#,
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        if count.rows_affected() != 1 {
+            Err(anyhow::anyhow!("No rows deleted"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::{
+        follower::repository::FollowerRepository, rsa_key::repository::RsaKeyRepository,
+        user::model::UserId,
+    };
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_find_by_acct(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let acct = "acct:bob@sub1.example.com".parse::<AcctUri>().unwrap();
+
+        let bob = repo.find_by_acct(&acct).await.unwrap();
+        assert_eq!(bob.actor_url, *BOB_URL);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_update_actor(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let event = UpdateActorEvent::builder()
+            .actor_url(BOB_URL.clone())
+            .preferred_name("bob")
+            .display_name(Some("Bob".to_string()))
+            .inbox(
+                "https://sub1.example.com/inbox/bob"
+                    .parse::<ResourceUrl>()
+                    .unwrap(),
+            )
+            .build();
+
+        repo.update(event).await.unwrap();
+
+        let bob = repo.find_by_url(&BOB_URL).await.unwrap();
+        assert_eq!(bob.display_name.as_deref(), Some("Bob"));
+        assert_eq!(bob.inbox.as_str(), "https://sub1.example.com/inbox/bob");
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_delete_actor(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        FollowerRepository::create(&repo, &USER_ID, &BOB_URL)
+            .await
+            .unwrap();
+
+        ActorRepository::delete(&repo, &BOB_URL).await.unwrap();
+
+        assert!(repo.find_by_url(&BOB_URL).await.is_err());
+        assert!(!repo.find(&USER_ID, &BOB_URL).await.unwrap());
+        let key_url = "https://sub1.example.com/users/bob#main-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        assert!(repo.find_public_key_by_key_url(&key_url).await.is_err());
+    }
 }
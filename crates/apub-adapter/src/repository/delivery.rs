@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use apub_kernel::delivery::{
+    model::{DeliveryJob, DeliveryJobId, NewDeliveryJob},
+    repository::DeliveryRepository,
+};
+use sqlx::types::Uuid;
+
+use crate::{model::delivery::DeliveryJobRow, persistence::postgres::PostgresDb};
+
+#[async_trait::async_trait]
+impl DeliveryRepository for PostgresDb {
+    #[tracing::instrument(skip_all, fields(jobs = jobs.len()))]
+    async fn enqueue(&self, jobs: &[NewDeliveryJob]) -> anyhow::Result<()> {
+        let job_ids = jobs.iter().map(|j| *j.id.as_ref()).collect::<Vec<Uuid>>();
+        let user_ids = jobs
+            .iter()
+            .map(|j| *j.user_id.as_ref())
+            .collect::<Vec<Uuid>>();
+        let inboxes = jobs.iter().map(|j| j.inbox.to_string()).collect::<Vec<_>>();
+        let activities = jobs
+            .iter()
+            .map(|j| j.activity.to_string())
+            .collect::<Vec<_>>();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO delivery_jobs
+                (job_id, user_id, inbox_url, activity)
+            SELECT
+                job_id, user_id, inbox_url, activity::JSONB
+            FROM
+                UNNEST($1::UUID[], $2::UUID[], $3::TEXT[], $4::TEXT[])
+                AS t(job_id, user_id, inbox_url, activity)
+            "#,
+            &job_ids,
+            &user_ids,
+            &inboxes,
+            &activities
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn lock_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<DeliveryJob>> {
+        // 複数のワーカーが同じジョブを取らないよう`SKIP LOCKED`で取り出す
+        let rows = sqlx::query_as!(
+            DeliveryJobRow,
+            r#"
+            UPDATE delivery_jobs
+            SET
+                attempts = delivery_jobs.attempts + 1,
+                locked_until = current_timestamp + make_interval(secs => $2)
+            WHERE
+                delivery_jobs.job_id IN (
+                    SELECT
+                        job_id
+                    FROM
+                        delivery_jobs
+                    WHERE
+                        status = 'pending'
+                        AND next_attempt_at <= current_timestamp
+                        AND (locked_until IS NULL OR locked_until <= current_timestamp)
+                    ORDER BY
+                        next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+            RETURNING
+                job_id,
+                user_id,
+                inbox_url,
+                activity::TEXT AS "activity!",
+                attempts
+            "#,
+            limit,
+            lock_for.as_secs_f64()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        rows.into_iter().map(DeliveryJob::try_from).collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn complete(&self, id: &DeliveryJobId) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM delivery_jobs
+            WHERE
+                delivery_jobs.job_id = $1
+            "#,
+            id.as_ref()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn retry(&self, id: &DeliveryJobId, delay: Duration, error: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE delivery_jobs
+            SET
+                next_attempt_at = current_timestamp + make_interval(secs => $2),
+                locked_until = NULL,
+                last_error = $3
+            WHERE
+                delivery_jobs.job_id = $1
+            "#,
+            id.as_ref(),
+            delay.as_secs_f64(),
+            error
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn postpone(&self, id: &DeliveryJobId, delay: Duration) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE delivery_jobs
+            SET
+                next_attempt_at = current_timestamp + make_interval(secs => $2),
+                attempts = GREATEST(delivery_jobs.attempts - 1, 0),
+                locked_until = NULL
+            WHERE
+                delivery_jobs.job_id = $1
+            "#,
+            id.as_ref(),
+            delay.as_secs_f64()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn dead(&self, id: &DeliveryJobId, error: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE delivery_jobs
+            SET
+                status = 'dead',
+                locked_until = NULL,
+                last_error = $2
+            WHERE
+                delivery_jobs.job_id = $1
+            "#,
+            id.as_ref(),
+            error
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::user::model::UserId;
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+
+    const LOCK_FOR: Duration = Duration::from_secs(60);
+
+    fn new_job(inbox: &str) -> NewDeliveryJob {
+        NewDeliveryJob::builder()
+            .user_id(USER_ID.clone())
+            .inbox(inbox.parse().unwrap())
+            .activity(serde_json::json!({ "type": "Create" }))
+            .build()
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_lock_due(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let jobs = [
+            new_job("https://sub1.example.com/users/bob/inbox"),
+            new_job("https://sub2.example.com/inbox"),
+        ];
+        repo.enqueue(&jobs).await.unwrap();
+
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked.len(), 2);
+        assert!(locked.iter().all(|j| j.attempts == 1));
+
+        // ロック中のジョブは取り出されない
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+
+        repo.complete(&jobs[0].id).await.unwrap();
+        repo.retry(&jobs[1].id, Duration::ZERO, "503")
+            .await
+            .unwrap();
+
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked[0].id, jobs[1].id);
+        assert_eq!(locked[0].attempts, 2);
+        assert_eq!(locked[0].activity, jobs[1].activity);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_retry_and_dead(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let job = new_job("https://sub1.example.com/users/bob/inbox");
+        repo.enqueue(std::slice::from_ref(&job)).await.unwrap();
+
+        // 送り直す時刻になるまでは取り出されない
+        repo.lock_due(10, LOCK_FOR).await.unwrap();
+        repo.retry(&job.id, Duration::from_secs(60), "timed out")
+            .await
+            .unwrap();
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+
+        repo.retry(&job.id, Duration::ZERO, "timed out")
+            .await
+            .unwrap();
+        repo.dead(&job.id, "404").await.unwrap();
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_postpone(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let job = new_job("https://sub1.example.com/users/bob/inbox");
+        repo.enqueue(std::slice::from_ref(&job)).await.unwrap();
+
+        repo.lock_due(10, LOCK_FOR).await.unwrap();
+        repo.postpone(&job.id, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+
+        // 送らずに待たせた分は`attempts`に数えない
+        repo.postpone(&job.id, Duration::ZERO).await.unwrap();
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked[0].attempts, 1);
+    }
+}
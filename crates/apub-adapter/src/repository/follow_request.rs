@@ -0,0 +1,165 @@
+use crate::{model::follow_request::FollowRequestRow, persistence::postgres::PostgresDb};
+use apub_kernel::{
+    follow_request::{model::FollowRequest, repository::FollowRequestRepository},
+    user::model::UserId,
+};
+use apub_shared::model::resource_url::ResourceUrl;
+
+#[async_trait::async_trait]
+impl FollowRequestRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn find(
+        &self,
+        user_id: &UserId,
+        actor_url: &ResourceUrl,
+    ) -> anyhow::Result<FollowRequest> {
+        let row = sqlx::query_as!(
+            FollowRequestRow,
+            r#"
+            SELECT
+                follow_requests.followed_user_id AS user_id,
+                actors.actor_url AS actor_url,
+                follow_requests.follow_url AS follow_url
+            FROM
+                follow_requests
+            INNER JOIN
+                actors
+            ON
+                follow_requests.follower_actor_id = actors.actor_id
+            WHERE
+                follow_requests.followed_user_id = $1 AND actors.actor_url = $2
+            "#,
+            user_id.as_ref(),
+            actor_url.as_str()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_by_user(&self, user_id: &UserId) -> anyhow::Result<Vec<FollowRequest>> {
+        let rows = sqlx::query_as!(
+            FollowRequestRow,
+            r#"
+            SELECT
+                follow_requests.followed_user_id AS user_id,
+                actors.actor_url AS actor_url,
+                follow_requests.follow_url AS follow_url
+            FROM
+                follow_requests
+            INNER JOIN
+                actors
+            ON
+                follow_requests.follower_actor_id = actors.actor_id
+            WHERE
+                follow_requests.followed_user_id = $1
+            ORDER BY
+                follow_requests.created_at
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let requests = rows
+            .into_iter()
+            .filter_map(|row| FollowRequest::try_from(row).ok())
+            .collect();
+
+        Ok(requests)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn create(&self, request: &FollowRequest) -> anyhow::Result<()> {
+        let count = sqlx::query!(
+            r#"
+            INSERT INTO follow_requests
+                (followed_user_id, follower_actor_id, follow_url)
+            VALUES
+                (
+                $1,
+                (SELECT actor_id FROM actors WHERE actor_url = $2),
+                $3
+            )
+            ON CONFLICT (follower_actor_id, followed_user_id)
+            DO UPDATE SET follow_url = EXCLUDED.follow_url
+            "#,
+            request.user_id.as_ref(),
+            request.actor_url.as_str(),
+            request.follow_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        if count.rows_affected() != 1 {
+            return Err(anyhow::anyhow!("follow request is not added"));
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<bool> {
+        let count = sqlx::query!(
+            r#"
+            DELETE FROM
+                follow_requests
+            WHERE
+                follow_requests.followed_user_id = $1
+                AND follow_requests.follower_actor_id IN (SELECT actor_id FROM actors WHERE actor_url = $2)
+            "#,
+            user_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(count.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_follow_request(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        for follow_url in [
+            "https://sub1.example.com/follows/1",
+            "https://sub1.example.com/follows/2",
+        ] {
+            let request = FollowRequest::builder()
+                .user_id(USER_ID.clone())
+                .actor_url(BOB_URL.clone())
+                .follow_url(follow_url.parse().unwrap())
+                .build();
+            repo.create(&request).await.unwrap();
+        }
+
+        // 同じ`Actor`からの`Follow`は新しいもので置き換わる
+        let requests = repo.find_by_user(&USER_ID).await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let request = repo.find(&USER_ID, &BOB_URL).await.unwrap();
+        assert_eq!(
+            request.follow_url.as_str(),
+            "https://sub1.example.com/follows/2"
+        );
+
+        assert!(repo.delete(&USER_ID, &BOB_URL).await.unwrap());
+        assert!(repo.find(&USER_ID, &BOB_URL).await.is_err());
+        // 承認待ちでなければ何も削除しない
+        assert!(!repo.delete(&USER_ID, &BOB_URL).await.unwrap());
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    model::follower::{FollowerCount, FollowerRow},
+    model::follower::{FollowerCount, FollowerCursorRow, FollowerRow},
     persistence::postgres::PostgresDb,
 };
 use apub_kernel::{
     follower::{model::Follower, repository::FollowerRepository},
+    pagination::{Cursor, Page},
     user::model::UserId,
 };
 use apub_shared::model::resource_url::ResourceUrl;
@@ -46,7 +47,8 @@ impl FollowerRepository for PostgresDb {
                 actors.actor_url AS follower_url,
                 actors.host AS host,
                 actors.preferred_username AS preferred_username,
-                actors.inbox_url AS inbox_url
+                actors.inbox_url AS inbox_url,
+                actors.shared_inbox_url AS shared_inbox_url
             FROM
                 actor_follows
             LEFT JOIN
@@ -69,6 +71,75 @@ impl FollowerRepository for PostgresDb {
         Ok(followers)
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn count(&self, user_id: &UserId) -> anyhow::Result<i64> {
+        let r = sqlx::query_as!(
+            FollowerCount,
+            r#"
+            SELECT
+                COUNT(*) AS count
+            FROM
+                actor_follows
+            WHERE
+                actor_follows.followed_user_id = $1
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(r.count())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_page(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Follower>> {
+        // `min_id`の場合は古い順に取得し、`Page`で並べ直す
+        let rows = sqlx::query_as!(
+            FollowerCursorRow,
+            r#"
+            SELECT
+                actor_follows.follow_id AS follow_id,
+                actor_follows.followed_user_id AS user_id,
+                actors.actor_url AS follower_url,
+                actors.host AS host,
+                actors.preferred_username AS preferred_username,
+                actors.inbox_url AS inbox_url,
+                actors.shared_inbox_url AS shared_inbox_url
+            FROM
+                actor_follows
+            INNER JOIN
+                actors
+            ON
+                actor_follows.follower_actor_id = actors.actor_id
+            WHERE
+                actor_follows.followed_user_id = $1
+                AND ($2::BIGINT IS NULL OR actor_follows.follow_id < $2)
+                AND ($3::BIGINT IS NULL OR actor_follows.follow_id > $3)
+            ORDER BY
+                CASE WHEN $3::BIGINT IS NULL THEN -actor_follows.follow_id ELSE actor_follows.follow_id END
+            LIMIT $4
+            "#,
+            user_id.as_ref(),
+            cursor.max_id(),
+            cursor.min_id(),
+            limit + 1
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .filter_map(|row| row.into_entry().ok())
+            .collect();
+
+        Ok(Page::from_rows(rows, cursor, limit))
+    }
+
     #[tracing::instrument(skip(self))]
     async fn create(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
         let count = sqlx::query!(
@@ -153,7 +224,46 @@ mod tests {
         assert!(!repo.find(&USER_ID, &CHARLIE_URL).await.unwrap());
 
         let list = repo.find_followee(&USER_ID).await.unwrap();
-        assert_eq!(list.len(), 2)
+        assert_eq!(list.len(), 2);
+
+        // 共有`inbox`も一緒に取得する
+        let alice = list.iter().find(|f| f.actor_url == *ALICE_URL).unwrap();
+        assert_eq!(
+            alice.shared_inbox.as_ref().map(|v| v.as_str()),
+            Some("https://example.com/inbox")
+        );
+        let bob = list.iter().find(|f| f.actor_url == *BOB_URL).unwrap();
+        assert_eq!(bob.shared_inbox, None);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_find_followers_page(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        for url in [&*ALICE_URL, &*BOB_URL, &*CHARLIE_URL] {
+            repo.create(&USER_ID, url).await.unwrap();
+        }
+        assert_eq!(repo.count(&USER_ID).await.unwrap(), 3);
+
+        // 新しい順に並ぶ
+        let first = repo.find_page(&USER_ID, Cursor::Latest, 2).await.unwrap();
+        let urls = first.items.iter().map(|v| &v.actor_url).collect::<Vec<_>>();
+        assert_eq!(urls, vec![&*CHARLIE_URL, &*BOB_URL]);
+        assert_eq!(first.prev, None);
+
+        let next = Cursor::Before(first.next.unwrap());
+        let second = repo.find_page(&USER_ID, next, 2).await.unwrap();
+        let urls = second
+            .items
+            .iter()
+            .map(|v| &v.actor_url)
+            .collect::<Vec<_>>();
+        assert_eq!(urls, vec![&*ALICE_URL]);
+        assert_eq!(second.next, None);
+
+        let prev = Cursor::After(second.prev.unwrap());
+        let back = repo.find_page(&USER_ID, prev, 2).await.unwrap();
+        assert_eq!(back.items, first.items);
     }
 
     #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
@@ -0,0 +1,340 @@
+use crate::{
+    model::{
+        follower::FollowerCount,
+        following::{FollowingCursorRow, FollowingRow},
+    },
+    persistence::postgres::PostgresDb,
+};
+use apub_kernel::{
+    following::{
+        model::{CreatedFollowing, Following},
+        repository::FollowingRepository,
+    },
+    pagination::{Cursor, Page},
+    user::model::UserId,
+};
+use apub_shared::model::resource_url::ResourceUrl;
+
+#[async_trait::async_trait]
+impl FollowingRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn find(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<Following> {
+        let row = sqlx::query_as!(
+            FollowingRow,
+            r#"
+            SELECT
+                following.user_id AS user_id,
+                actors.actor_url AS actor_url,
+                following.follow_url AS follow_url,
+                following.accepted AS accepted
+            FROM
+                following
+            INNER JOIN
+                actors
+            ON
+                following.actor_id = actors.actor_id
+            WHERE
+                following.user_id = $1 AND actors.actor_url = $2
+            "#,
+            user_id.as_ref(),
+            actor_url.as_str()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_by_user(&self, user_id: &UserId) -> anyhow::Result<Vec<Following>> {
+        let rows = sqlx::query_as!(
+            FollowingRow,
+            r#"
+            SELECT
+                following.user_id AS user_id,
+                actors.actor_url AS actor_url,
+                following.follow_url AS follow_url,
+                following.accepted AS accepted
+            FROM
+                following
+            INNER JOIN
+                actors
+            ON
+                following.actor_id = actors.actor_id
+            WHERE
+                following.user_id = $1
+            ORDER BY
+                following.created_at
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let following = rows
+            .into_iter()
+            .filter_map(|row| Following::try_from(row).ok())
+            .collect();
+
+        Ok(following)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_by_actor(&self, actor_url: &ResourceUrl) -> anyhow::Result<Vec<Following>> {
+        let rows = sqlx::query_as!(
+            FollowingRow,
+            r#"
+            SELECT
+                following.user_id AS user_id,
+                actors.actor_url AS actor_url,
+                following.follow_url AS follow_url,
+                following.accepted AS accepted
+            FROM
+                following
+            INNER JOIN
+                actors
+            ON
+                following.actor_id = actors.actor_id
+            WHERE
+                actors.actor_url = $1
+                AND following.accepted
+            ORDER BY
+                following.created_at
+            "#,
+            actor_url.as_str()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let following = rows
+            .into_iter()
+            .filter_map(|row| Following::try_from(row).ok())
+            .collect();
+
+        Ok(following)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count(&self, user_id: &UserId) -> anyhow::Result<i64> {
+        let r = sqlx::query_as!(
+            FollowerCount,
+            r#"
+            SELECT
+                COUNT(*) AS count
+            FROM
+                following
+            WHERE
+                following.user_id = $1 AND following.accepted
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(r.count())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_page(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Following>> {
+        // `min_id`の場合は古い順に取得し、`Page`で並べ直す
+        let rows = sqlx::query_as!(
+            FollowingCursorRow,
+            r#"
+            SELECT
+                following.following_id AS following_id,
+                following.user_id AS user_id,
+                actors.actor_url AS actor_url,
+                following.follow_url AS follow_url,
+                following.accepted AS accepted
+            FROM
+                following
+            INNER JOIN
+                actors
+            ON
+                following.actor_id = actors.actor_id
+            WHERE
+                following.user_id = $1
+                AND following.accepted
+                AND ($2::BIGINT IS NULL OR following.following_id < $2)
+                AND ($3::BIGINT IS NULL OR following.following_id > $3)
+            ORDER BY
+                CASE WHEN $3::BIGINT IS NULL THEN -following.following_id ELSE following.following_id END
+            LIMIT $4
+            "#,
+            user_id.as_ref(),
+            cursor.max_id(),
+            cursor.min_id(),
+            limit + 1
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .filter_map(|row| row.into_entry().ok())
+            .collect();
+
+        Ok(Page::from_rows(rows, cursor, limit))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn create(&self, following: &Following) -> anyhow::Result<CreatedFollowing> {
+        // 承認済みの行は書き換えず、何も返さない
+        let created = sqlx::query_scalar!(
+            r#"
+            INSERT INTO following
+                (user_id, actor_id, follow_url)
+            VALUES
+                (
+                $1,
+                (SELECT actor_id FROM actors WHERE actor_url = $2),
+                $3
+            )
+            ON CONFLICT (user_id, actor_id)
+            DO UPDATE SET follow_url = EXCLUDED.follow_url
+            WHERE following.accepted = FALSE
+            RETURNING (xmax = 0) AS "created!"
+            "#,
+            following.user_id.as_ref(),
+            following.actor_url.as_str(),
+            following.follow_url.as_str()
+        )
+        .fetch_optional(self.inner_ref())
+        .await?;
+
+        match created {
+            Some(true) => Ok(CreatedFollowing::Created),
+            Some(false) => Ok(CreatedFollowing::Replaced),
+            None => {
+                let accepted = self.find(&following.user_id, &following.actor_url).await?;
+                Ok(CreatedFollowing::Accepted(accepted))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn accept(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        let count = sqlx::query!(
+            r#"
+            UPDATE
+                following
+            SET
+                accepted = TRUE
+            WHERE
+                following.user_id = $1
+                AND following.actor_id IN (SELECT actor_id FROM actors WHERE actor_url = $2)
+            "#,
+            user_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        if count.rows_affected() != 1 {
+            Err(anyhow::anyhow!("No rows updated"))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        let count = sqlx::query!(
+            r#"
+            DELETE FROM
+                following
+            WHERE
+                following.user_id = $1
+                AND following.actor_id IN (SELECT actor_id FROM actors WHERE actor_url = $2)
+            "#,
+            user_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        if count.rows_affected() != 1 {
+            Err(anyhow::anyhow!("No rows deleted"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_following(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        let following = Following::builder()
+            .user_id(USER_ID.clone())
+            .actor_url(BOB_URL.clone())
+            .follow_url("https://example.com/activities/1".parse().unwrap())
+            .build();
+        assert_eq!(
+            repo.create(&following).await.unwrap(),
+            CreatedFollowing::Created
+        );
+
+        // `Accept`が届くまでは承認待ち
+        let pending = repo.find(&USER_ID, &BOB_URL).await.unwrap();
+        assert_eq!(pending, following);
+        assert!(!pending.accepted);
+
+        // 承認待ちのものは`follow_url`だけを置き換える
+        let following = Following::builder()
+            .user_id(USER_ID.clone())
+            .actor_url(BOB_URL.clone())
+            .follow_url("https://example.com/activities/2".parse().unwrap())
+            .build();
+        assert_eq!(
+            repo.create(&following).await.unwrap(),
+            CreatedFollowing::Replaced
+        );
+        assert_eq!(repo.find(&USER_ID, &BOB_URL).await.unwrap(), following);
+
+        // 承認待ちは`following`に含めない
+        assert_eq!(repo.count(&USER_ID).await.unwrap(), 0);
+        assert!(repo.find_by_actor(&BOB_URL).await.unwrap().is_empty());
+
+        repo.accept(&USER_ID, &BOB_URL).await.unwrap();
+        assert_eq!(repo.find_by_actor(&BOB_URL).await.unwrap().len(), 1);
+        let accepted = repo.find_by_user(&USER_ID).await.unwrap();
+        assert_eq!(accepted.len(), 1);
+        assert!(accepted[0].accepted);
+        assert_eq!(repo.count(&USER_ID).await.unwrap(), 1);
+        let page = repo.find_page(&USER_ID, Cursor::Latest, 20).await.unwrap();
+        assert_eq!(page.items, accepted);
+
+        // 承認済みのものは承認待ちに戻さない
+        let refollow = Following::builder()
+            .user_id(USER_ID.clone())
+            .actor_url(BOB_URL.clone())
+            .follow_url("https://example.com/activities/3".parse().unwrap())
+            .build();
+        assert_eq!(
+            repo.create(&refollow).await.unwrap(),
+            CreatedFollowing::Accepted(accepted[0].clone())
+        );
+        assert_eq!(repo.find(&USER_ID, &BOB_URL).await.unwrap(), accepted[0]);
+
+        repo.delete(&USER_ID, &BOB_URL).await.unwrap();
+        assert!(repo.find(&USER_ID, &BOB_URL).await.is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use apub_kernel::inbox::{
+    model::{InboxJob, InboxJobId, NewInboxJob},
+    repository::InboxRepository,
+};
+
+use crate::{model::inbox::InboxJobRow, persistence::postgres::PostgresDb};
+
+#[async_trait::async_trait]
+impl InboxRepository for PostgresDb {
+    #[tracing::instrument(skip_all, fields(activity_id = job.activity_id()))]
+    async fn enqueue(&self, job: &NewInboxJob) -> anyhow::Result<bool> {
+        let count = sqlx::query!(
+            r#"
+            INSERT INTO inbox_jobs
+                (job_id, activity_id, user_id, activity)
+            VALUES
+                ($1, $2, $3, $4::TEXT::JSONB)
+            ON CONFLICT (activity_id) DO NOTHING
+            "#,
+            job.id.as_ref(),
+            job.activity_id(),
+            job.user_id.as_ref().map(|v| v.as_ref()),
+            job.activity.to_string()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(count.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn lock_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<InboxJob>> {
+        // 複数のワーカーが同じジョブを取らないよう`SKIP LOCKED`で取り出す
+        let rows = sqlx::query_as!(
+            InboxJobRow,
+            r#"
+            UPDATE inbox_jobs
+            SET
+                attempts = inbox_jobs.attempts + 1,
+                locked_until = current_timestamp + make_interval(secs => $2)
+            WHERE
+                inbox_jobs.job_id IN (
+                    SELECT
+                        job_id
+                    FROM
+                        inbox_jobs
+                    WHERE
+                        status = 'pending'
+                        AND next_attempt_at <= current_timestamp
+                        AND (locked_until IS NULL OR locked_until <= current_timestamp)
+                    ORDER BY
+                        next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+            RETURNING
+                job_id,
+                user_id,
+                activity::TEXT AS "activity!",
+                attempts
+            "#,
+            limit,
+            lock_for.as_secs_f64()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        rows.into_iter().map(InboxJob::try_from).collect()
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn complete(&self, id: &InboxJobId) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE inbox_jobs
+            SET
+                status = 'done',
+                locked_until = NULL,
+                last_error = NULL,
+                finished_at = current_timestamp
+            WHERE
+                inbox_jobs.job_id = $1
+            "#,
+            id.as_ref()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn retry(&self, id: &InboxJobId, delay: Duration, error: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE inbox_jobs
+            SET
+                next_attempt_at = current_timestamp + make_interval(secs => $2),
+                locked_until = NULL,
+                last_error = $3
+            WHERE
+                inbox_jobs.job_id = $1
+            "#,
+            id.as_ref(),
+            delay.as_secs_f64(),
+            error
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn dead(&self, id: &InboxJobId, error: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE inbox_jobs
+            SET
+                status = 'dead',
+                locked_until = NULL,
+                last_error = $2,
+                finished_at = current_timestamp
+            WHERE
+                inbox_jobs.job_id = $1
+            "#,
+            id.as_ref(),
+            error
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn purge_finished(&self, older_than: Duration) -> anyhow::Result<u64> {
+        let count = sqlx::query!(
+            r#"
+            DELETE FROM inbox_jobs
+            WHERE
+                status IN ('done', 'dead')
+                AND finished_at <= current_timestamp - make_interval(secs => $1)
+            "#,
+            older_than.as_secs_f64()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(count.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::user::model::UserId;
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+
+    const LOCK_FOR: Duration = Duration::from_secs(60);
+
+    fn new_job(id: &str) -> NewInboxJob {
+        NewInboxJob::builder()
+            .user_id(Some(USER_ID.clone()))
+            .activity(serde_json::json!({ "id": id, "type": "Follow" }))
+            .build()
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_enqueue_dedup(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let job = new_job("https://sub1.example.com/activities/1");
+
+        assert!(repo.enqueue(&job).await.unwrap());
+        // 同じ`id`のActivityは積まない
+        assert!(!repo
+            .enqueue(&new_job("https://sub1.example.com/activities/1"))
+            .await
+            .unwrap());
+
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked.len(), 1);
+        assert_eq!(locked[0].id, job.id);
+        assert_eq!(locked[0].user_id, job.user_id);
+        assert_eq!(locked[0].activity, job.activity);
+
+        // 処理し終えた後も重複と判定する
+        repo.complete(&job.id).await.unwrap();
+        assert!(!repo.enqueue(&job).await.unwrap());
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_retry_and_dead(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let job = NewInboxJob::builder()
+            .activity(serde_json::json!({ "type": "Delete" }))
+            .build();
+        repo.enqueue(&job).await.unwrap();
+
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked[0].user_id, None);
+        assert_eq!(locked[0].attempts, 1);
+
+        repo.retry(&job.id, Duration::ZERO, "timed out")
+            .await
+            .unwrap();
+        let locked = repo.lock_due(10, LOCK_FOR).await.unwrap();
+        assert_eq!(locked[0].attempts, 2);
+
+        repo.dead(&job.id, "invalid activity").await.unwrap();
+        assert!(repo.lock_due(10, LOCK_FOR).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_purge_finished(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let done = new_job("https://sub1.example.com/activities/1");
+        let dead = new_job("https://sub1.example.com/activities/2");
+        let pending = new_job("https://sub1.example.com/activities/3");
+        for job in [&done, &dead, &pending] {
+            repo.enqueue(job).await.unwrap();
+        }
+        repo.complete(&done.id).await.unwrap();
+        repo.dead(&dead.id, "invalid activity").await.unwrap();
+
+        // 保持期間が過ぎていなければ消さない
+        assert_eq!(
+            repo.purge_finished(Duration::from_secs(60)).await.unwrap(),
+            0
+        );
+        assert!(!repo.enqueue(&done).await.unwrap());
+
+        // 処理し終えたジョブだけを消し、同じActivityを再び積めるようにする
+        assert_eq!(repo.purge_finished(Duration::ZERO).await.unwrap(), 2);
+        assert!(repo
+            .enqueue(&new_job("https://sub1.example.com/activities/1"))
+            .await
+            .unwrap());
+        assert!(!repo.enqueue(&pending).await.unwrap());
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use apub_kernel::instance::repository::InstanceRepository;
+
+use crate::persistence::postgres::PostgresDb;
+
+#[async_trait::async_trait]
+impl InstanceRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn record_success(&self, host: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO instances
+                (host, last_success_at)
+            VALUES
+                ($1, current_timestamp)
+            ON CONFLICT (host) DO UPDATE
+            SET
+                failure_count = 0,
+                failing_since = NULL,
+                last_success_at = current_timestamp,
+                unreachable_since = NULL,
+                next_probe_at = NULL,
+                updated_at = current_timestamp
+            "#,
+            host
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn record_failure(
+        &self,
+        host: &str,
+        unreachable_after: Duration,
+        probe_interval: Duration,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.inner_ref().begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO instances
+                (host, failure_count, failing_since, last_failure_at)
+            VALUES
+                ($1, 1, current_timestamp, current_timestamp)
+            ON CONFLICT (host) DO UPDATE
+            SET
+                failure_count = instances.failure_count + 1,
+                failing_since = COALESCE(instances.failing_since, current_timestamp),
+                last_failure_at = current_timestamp,
+                updated_at = current_timestamp
+            "#,
+            host
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // 失敗し続けている期間が`unreachable_after`を超えたら配送を止める。
+        // 止めている間の失敗は試した結果なので、次に試す時刻を延ばす
+        sqlx::query!(
+            r#"
+            UPDATE instances
+            SET
+                unreachable_since = COALESCE(unreachable_since, current_timestamp),
+                next_probe_at = current_timestamp + make_interval(secs => $3)
+            WHERE
+                instances.host = $1
+                AND instances.failing_since <= current_timestamp - make_interval(secs => $2)
+            "#,
+            host,
+            unreachable_after.as_secs_f64(),
+            probe_interval.as_secs_f64()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_unreachable(&self, hosts: &[&str]) -> anyhow::Result<Vec<String>> {
+        let hosts = hosts.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                host
+            FROM
+                instances
+            WHERE
+                host = ANY($1)
+                AND unreachable_since IS NOT NULL
+                AND next_probe_at > current_timestamp
+            "#,
+            &hosts
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.host).collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn lock_probe_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<String>> {
+        // 複数のワーカーが同じホストを試さないよう`SKIP LOCKED`で取り出す
+        let rows = sqlx::query!(
+            r#"
+            UPDATE instances
+            SET
+                next_probe_at = current_timestamp + make_interval(secs => $2)
+            WHERE
+                instances.host IN (
+                    SELECT
+                        host
+                    FROM
+                        instances
+                    WHERE
+                        unreachable_since IS NOT NULL
+                        AND next_probe_at <= current_timestamp
+                    ORDER BY
+                        next_probe_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+            RETURNING
+                host
+            "#,
+            limit,
+            lock_for.as_secs_f64()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.host).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const HOSTS: [&str; 2] = ["sub1.example.com", "sub2.example.com"];
+    const PROBE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+    #[sqlx::test]
+    async fn test_unreachable(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        // 失敗期間が短いうちは配送を止めない
+        repo.record_failure(HOSTS[0], Duration::from_secs(60), PROBE_INTERVAL)
+            .await
+            .unwrap();
+        assert!(repo.find_unreachable(&HOSTS).await.unwrap().is_empty());
+
+        repo.record_failure(HOSTS[0], Duration::ZERO, PROBE_INTERVAL)
+            .await
+            .unwrap();
+        repo.record_failure(HOSTS[1], Duration::ZERO, PROBE_INTERVAL)
+            .await
+            .unwrap();
+        let mut unreachable = repo.find_unreachable(&HOSTS).await.unwrap();
+        unreachable.sort();
+        assert_eq!(unreachable, HOSTS);
+
+        // 成功すれば解除される
+        repo.record_success(HOSTS[0]).await.unwrap();
+        assert_eq!(repo.find_unreachable(&HOSTS).await.unwrap(), [HOSTS[1]]);
+    }
+
+    #[sqlx::test]
+    async fn test_probe(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        // 次に試す時刻を過ぎたホストには配送する
+        repo.record_failure(HOSTS[0], Duration::ZERO, Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(repo.find_unreachable(&HOSTS).await.unwrap().is_empty());
+
+        // 試すために取り出している間は配送を止めたままにする
+        let locked = repo.lock_probe_due(10, PROBE_INTERVAL).await.unwrap();
+        assert_eq!(locked, [HOSTS[0]]);
+        assert_eq!(repo.find_unreachable(&HOSTS).await.unwrap(), [HOSTS[0]]);
+        assert!(repo
+            .lock_probe_due(10, PROBE_INTERVAL)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // 試して失敗すれば再び止める
+        repo.record_failure(HOSTS[0], Duration::ZERO, PROBE_INTERVAL)
+            .await
+            .unwrap();
+        assert_eq!(repo.find_unreachable(&HOSTS).await.unwrap(), [HOSTS[0]]);
+        assert!(repo
+            .lock_probe_due(10, PROBE_INTERVAL)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{model::like::LikeRow, persistence::postgres::PostgresDb};
+use apub_kernel::{
+    like::{model::Like, repository::LikeRepository},
+    note::model::NoteId,
+};
+use apub_shared::model::resource_url::ResourceUrl;
+
+#[async_trait::async_trait]
+impl LikeRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn find_by_note(&self, note_id: &NoteId) -> anyhow::Result<Vec<Like>> {
+        let rows = sqlx::query_as!(
+            LikeRow,
+            r#"
+            SELECT
+                likes.note_id AS note_id,
+                actors.actor_url AS actor_url
+            FROM
+                likes
+            INNER JOIN
+                actors
+            ON
+                likes.actor_id = actors.actor_id
+            WHERE
+                likes.note_id = $1
+            ORDER BY
+                likes.created_at
+            "#,
+            note_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let likes = rows
+            .into_iter()
+            .filter_map(|row| Like::try_from(row).ok())
+            .collect();
+
+        Ok(likes)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn create(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO likes
+                (note_id, actor_id)
+            VALUES
+                (
+                $1,
+                (SELECT actor_id FROM actors WHERE actor_url = $2)
+            )
+            ON CONFLICT (note_id, actor_id) DO NOTHING
+            "#,
+            note_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                likes
+            WHERE
+                likes.note_id = $1
+                AND likes.actor_id IN (SELECT actor_id FROM actors WHERE actor_url = $2)
+            "#,
+            note_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::{
+        note::{model::CreateNote, repository::NoteRepository},
+        user::model::UserId,
+    };
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+    static ALICE_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://example.com/users/alice".parse::<_>().unwrap());
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_like_note(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let note = CreateNote::new(USER_ID.clone(), "hello".to_string());
+        NoteRepository::create(&repo, &note).await.unwrap();
+
+        LikeRepository::create(&repo, &note.note_id, &ALICE_URL)
+            .await
+            .unwrap();
+        LikeRepository::create(&repo, &note.note_id, &BOB_URL)
+            .await
+            .unwrap();
+        // 同じ`Like`が二度届いても一つだけ
+        LikeRepository::create(&repo, &note.note_id, &BOB_URL)
+            .await
+            .unwrap();
+
+        let likes = repo.find_by_note(&note.note_id).await.unwrap();
+        let actors = likes.into_iter().map(|v| v.actor_url).collect::<Vec<_>>();
+        assert_eq!(actors, vec![ALICE_URL.clone(), BOB_URL.clone()]);
+
+        LikeRepository::delete(&repo, &note.note_id, &ALICE_URL)
+            .await
+            .unwrap();
+        let likes = repo.find_by_note(&note.note_id).await.unwrap();
+        assert_eq!(likes.len(), 1);
+        // `Undo`が二度届いてもエラーにしない
+        LikeRepository::delete(&repo, &note.note_id, &ALICE_URL)
+            .await
+            .unwrap();
+        let likes = repo.find_by_note(&note.note_id).await.unwrap();
+        assert_eq!(likes.len(), 1);
+    }
+}
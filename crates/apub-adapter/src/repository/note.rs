@@ -1,12 +1,16 @@
 use apub_kernel::{
     note::{
-        model::{CreateNote, Note, NoteId},
+        model::{CreateNote, DeletedNote, Note, NoteId},
         repository::NoteRepository,
     },
+    pagination::{Cursor, Page},
     user::model::UserId,
 };
 
-use crate::{model::note::NoteRow, persistence::postgres::PostgresDb};
+use crate::{
+    model::note::{DeletedNoteRow, NoteCursorRow, NoteRow},
+    persistence::postgres::PostgresDb,
+};
 
 #[async_trait::async_trait]
 impl NoteRepository for PostgresDb {
@@ -15,8 +19,13 @@ impl NoteRepository for PostgresDb {
         let row = sqlx::query_as!(
             NoteRow,
             r#"
-            SELECT 
-                note_id, user_id, content
+            SELECT
+                note_id,
+                user_id,
+                content,
+                to_char(published AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS "published!",
+                to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS updated,
+                visibility
             FROM
                 notes
             WHERE
@@ -27,41 +36,86 @@ impl NoteRepository for PostgresDb {
         .fetch_one(self.inner_ref())
         .await?;
 
-        Ok(row.into())
+        row.try_into()
     }
 
     #[tracing::instrument(skip(self))]
-    async fn list_user_notes(&self, user_id: &UserId) -> anyhow::Result<Vec<Note>> {
+    async fn list_user_notes(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Note>> {
+        // `min_id`の場合は古い順に取得し、`Page`で並べ直す
         let rows = sqlx::query_as!(
-            NoteRow,
+            NoteCursorRow,
             r#"
-            SELECT 
-                note_id, user_id, content
+            SELECT
+                note_seq,
+                note_id,
+                user_id,
+                content,
+                to_char(published AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS "published!",
+                to_char(updated_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS updated,
+                visibility
             FROM
                 notes
             WHERE
                 notes.user_id = $1
+                AND notes.visibility <> 'followers'
+                AND ($2::BIGINT IS NULL OR notes.note_seq < $2)
+                AND ($3::BIGINT IS NULL OR notes.note_seq > $3)
+            ORDER BY
+                CASE WHEN $3::BIGINT IS NULL THEN -notes.note_seq ELSE notes.note_seq END
+            LIMIT $4
         "#,
-            user_id.as_ref()
+            user_id.as_ref(),
+            cursor.max_id(),
+            cursor.min_id(),
+            limit + 1
         )
         .fetch_all(self.inner_ref())
         .await?;
 
-        let notes = rows.into_iter().map(|r| r.into()).collect();
+        let rows = rows
+            .into_iter()
+            .map(|r| r.into_entry())
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Page::from_rows(rows, cursor, limit))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn count_user_notes(&self, user_id: &UserId) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                notes
+            WHERE
+                notes.user_id = $1
+                AND notes.visibility <> 'followers'
+        "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
 
-        Ok(notes)
+        Ok(count)
     }
 
     #[tracing::instrument(skip_all)]
     async fn create(&self, event: &CreateNote) -> anyhow::Result<()> {
         let _count = sqlx::query!(
             r#"
-            INSERT INTO notes (note_id, user_id, content)
-            VALUES ($1,$2,$3)
+            INSERT INTO notes (note_id, user_id, content, visibility)
+            VALUES ($1,$2,$3,$4)
         "#,
             event.note_id.as_ref(),
             event.user_id.as_ref(),
-            event.content
+            event.content,
+            event.visibility.as_str()
         )
         .execute(self.inner_ref())
         .await?;
@@ -70,22 +124,167 @@ impl NoteRepository for PostgresDb {
     }
 
     #[tracing::instrument(skip_all)]
-    async fn delete(&self, note_id: &NoteId) -> anyhow::Result<()> {
+    async fn update(&self, note_id: &NoteId, content: &str) -> anyhow::Result<()> {
         let count = sqlx::query!(
             r#"
-            DELETE FROM notes
+            UPDATE notes
+            SET
+                content = $2,
+                updated_at = current_timestamp
             WHERE
                 notes.note_id = $1
         "#,
             note_id.as_ref(),
+            content
         )
         .execute(self.inner_ref())
         .await?;
 
         if count.rows_affected() != 1 {
-            Err(anyhow::anyhow!("No rows deleted"))
+            Err(anyhow::anyhow!("No rows updated"))
         } else {
             Ok(())
         }
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_deleted(&self, note_id: &NoteId) -> anyhow::Result<DeletedNote> {
+        let row = sqlx::query_as!(
+            DeletedNoteRow,
+            r#"
+            SELECT
+                note_id,
+                user_id,
+                to_char(deleted_at AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS "deleted_at!"
+            FROM
+                note_tombstones
+            WHERE
+                note_tombstones.note_id = $1
+        "#,
+            note_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn delete(&self, note_id: &NoteId) -> anyhow::Result<()> {
+        let mut tx = self.inner_ref().begin().await?;
+
+        let count = sqlx::query!(
+            r#"
+            WITH deleted AS (
+                DELETE FROM notes
+                WHERE
+                    notes.note_id = $1
+                RETURNING note_id, user_id
+            )
+            INSERT INTO note_tombstones (note_id, user_id)
+            SELECT note_id, user_id FROM deleted
+        "#,
+            note_id.as_ref(),
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if count.rows_affected() != 1 {
+            return Err(anyhow::anyhow!("No rows deleted"));
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::note::model::Visibility;
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_list_user_notes(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let mut notes = Vec::new();
+        for content in ["first", "second", "third"] {
+            let note = CreateNote::new(USER_ID.clone(), content.to_string());
+            repo.create(&note).await.unwrap();
+            notes.push(note);
+        }
+        // フォロワー限定の`Note`は含まれない
+        let private = CreateNote::new(USER_ID.clone(), "private".to_string())
+            .with_visibility(Visibility::Followers);
+        repo.create(&private).await.unwrap();
+        assert_eq!(repo.count_user_notes(&USER_ID).await.unwrap(), 3);
+
+        // 新しい順に並ぶ
+        let page = repo
+            .list_user_notes(&USER_ID, Cursor::Latest, 2)
+            .await
+            .unwrap();
+        let contents = page
+            .items
+            .iter()
+            .map(|v| v.content.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(contents, vec!["third", "second"]);
+
+        let next = Cursor::Before(page.next.unwrap());
+        let page = repo.list_user_notes(&USER_ID, next, 2).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, notes[0].note_id);
+        assert_eq!(page.next, None);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_create_note(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let note = CreateNote::new(USER_ID.clone(), "hello".to_string())
+            .with_visibility(Visibility::Followers);
+        repo.create(&note).await.unwrap();
+
+        let found = repo.find(&note.note_id).await.unwrap();
+        assert_eq!(found.content, "hello");
+        assert_eq!(found.visibility, Visibility::Followers);
+        assert!(found.published.ends_with('Z'));
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_update_note(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let note = CreateNote::new(USER_ID.clone(), "hello".to_string());
+        repo.create(&note).await.unwrap();
+        assert_eq!(repo.find(&note.note_id).await.unwrap().updated, None);
+
+        repo.update(&note.note_id, "hello, world").await.unwrap();
+
+        let updated = repo.find(&note.note_id).await.unwrap();
+        assert_eq!(updated.content, "hello, world");
+        assert!(updated.updated.unwrap().ends_with('Z'));
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users")))]
+    async fn test_delete_note(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let note = CreateNote::new(USER_ID.clone(), "hello".to_string());
+        repo.create(&note).await.unwrap();
+        assert!(repo.find_deleted(&note.note_id).await.is_err());
+
+        repo.delete(&note.note_id).await.unwrap();
+
+        assert!(repo.find(&note.note_id).await.is_err());
+        let deleted = repo.find_deleted(&note.note_id).await.unwrap();
+        assert_eq!(deleted.id, note.note_id);
+        assert_eq!(deleted.user_id, *USER_ID);
+
+        // 二度目は削除するものがない
+        assert!(repo.delete(&note.note_id).await.is_err());
+    }
 }
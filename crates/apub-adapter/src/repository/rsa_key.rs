@@ -1,11 +1,21 @@
+use std::time::Duration;
+
 use apub_kernel::rsa_key::{
-    model::{RsaSingingKey, RsaVerifyingKey, SaveKeyPairEvent, SavePublicKeyEvent},
+    model::{
+        ActorEd25519PublicKey, ActorPublicKey, Ed25519SigningKey, Ed25519VerifyingKey,
+        RsaSingingKey, RsaVerifyingKey, SaveEd25519KeyPairEvent, SaveEd25519PublicKeyEvent,
+        SaveKeyPairEvent, SavePublicKeyEvent,
+    },
     repository::RsaKeyRepository,
 };
-use apub_kernel::user::model::UserId;
+use apub_kernel::{activitypub::actor::ActorId, user::model::UserId};
+use apub_shared::model::resource_url::ResourceUrl;
 
-use crate::model::rsa_key::{UserPrivateRsaKeyRow, UserPublicRsaKeyRow};
-use crate::persistence::postgres::PostgresDb;
+use crate::model::rsa_key::{
+    ActorEd25519PublicKeyRow, ActorPublicKeyRow, UserPrivateEd25519KeyRow, UserPrivateRsaKeyRow,
+    UserPublicEd25519KeyRow, UserPublicRsaKeyRow,
+};
+use crate::persistence::{key_encryption::MasterKey, postgres::PostgresDb};
 
 #[async_trait::async_trait]
 impl RsaKeyRepository for PostgresDb {
@@ -22,6 +32,7 @@ impl RsaKeyRepository for PostgresDb {
                 actor_rsa_keys
             ON
                 actors.actor_id = actor_rsa_keys.actor_id
+                AND actor_rsa_keys.retired_at IS NULL
             WHERE 
                 actors.local_user_id = $1
         "#,
@@ -33,11 +44,75 @@ impl RsaKeyRepository for PostgresDb {
         row.try_into()
     }
     #[tracing::instrument(skip(self))]
-    async fn find_private_key(&self, user_id: &UserId) -> anyhow::Result<RsaSingingKey> {
+    async fn find_public_keys(&self, user_id: &UserId) -> anyhow::Result<Vec<ActorPublicKey>> {
+        let rows = sqlx::query_as!(
+            ActorPublicKeyRow,
+            r#"
+            SELECT
+                actors.actor_url AS actor_url,
+                actor_rsa_keys.key_url AS key_url,
+                actor_rsa_keys.public_key AS public_key
+            FROM
+                actors
+            INNER JOIN
+                actor_rsa_keys
+            ON
+                actors.actor_id = actor_rsa_keys.actor_id
+            WHERE
+                actors.local_user_id = $1
+                AND (
+                    actor_rsa_keys.expires_at IS NULL
+                    OR actor_rsa_keys.expires_at > current_timestamp
+                )
+            ORDER BY
+                actor_rsa_keys.retired_at DESC NULLS FIRST
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        rows.into_iter().map(ActorPublicKey::try_from).collect()
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_public_key_by_key_url(
+        &self,
+        key_url: &ResourceUrl,
+    ) -> anyhow::Result<ActorPublicKey> {
         let row = sqlx::query_as!(
+            ActorPublicKeyRow,
+            r#"
+            SELECT
+                actors.actor_url AS actor_url,
+                actor_rsa_keys.key_url AS key_url,
+                actor_rsa_keys.public_key AS public_key
+            FROM
+                actor_rsa_keys
+            INNER JOIN
+                actors
+            ON
+                actors.actor_id = actor_rsa_keys.actor_id
+            WHERE
+                actor_rsa_keys.key_url = $1
+                AND (
+                    actor_rsa_keys.expires_at IS NULL
+                    OR actor_rsa_keys.expires_at > current_timestamp
+                )
+            "#,
+            key_url.as_str()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_private_key(&self, user_id: &UserId) -> anyhow::Result<RsaSingingKey> {
+        let mut row = sqlx::query_as!(
             UserPrivateRsaKeyRow,
             r#"
              SELECT 
+                actor_rsa_keys.key_url AS "key_url?",
                 actor_rsa_keys.private_key AS private_key 
             FROM 
                 actors 
@@ -45,6 +120,7 @@ impl RsaKeyRepository for PostgresDb {
                 actor_rsa_keys
             ON
                 actors.actor_id = actor_rsa_keys.actor_id
+                AND actor_rsa_keys.retired_at IS NULL
             WHERE 
                 actors.local_user_id = $1
             "#,
@@ -53,6 +129,9 @@ impl RsaKeyRepository for PostgresDb {
         .fetch_one(self.inner_ref())
         .await?;
 
+        // 鍵の行がなければ秘密鍵もないので、URLは空のままでよい
+        let key_url = row.key_url.take().unwrap_or_default();
+        row.private_key = self.open_private_key(row.private_key, &key_url)?;
         row.try_into()
     }
     #[tracing::instrument(skip_all)]
@@ -60,12 +139,16 @@ impl RsaKeyRepository for PostgresDb {
         let actor_id = event.actor_id.as_ref();
         let key_url = event.key_url.as_str();
         let public_key = event.public_key.to_pkcs8()?;
-        sqlx::query!(
+        // 他の`Actor`の鍵のURLは上書きしない
+        let res = sqlx::query!(
             r#"
              INSERT INTO actor_rsa_keys
                 (actor_id, key_url, public_key)
             VALUES
                 ($1, $2, $3)
+            ON CONFLICT (key_url)
+            DO UPDATE SET public_key = EXCLUDED.public_key
+            WHERE actor_rsa_keys.actor_id = EXCLUDED.actor_id
             "#,
             actor_id,
             key_url,
@@ -73,6 +156,35 @@ impl RsaKeyRepository for PostgresDb {
         )
         .execute(self.inner_ref())
         .await?;
+        if res.rows_affected() != 1 {
+            return Err(anyhow::anyhow!("key url is owned by another actor"));
+        }
+
+        Ok(())
+    }
+    #[tracing::instrument(skip(self))]
+    async fn delete_other_public_keys(
+        &self,
+        actor_id: &ActorId,
+        key_urls: &[ResourceUrl],
+    ) -> anyhow::Result<()> {
+        let key_urls = key_urls
+            .iter()
+            .map(|v| v.as_str().to_string())
+            .collect::<Vec<_>>();
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                actor_rsa_keys
+            WHERE
+                actor_rsa_keys.actor_id = $1
+                AND NOT (actor_rsa_keys.key_url = ANY($2))
+            "#,
+            actor_id.as_ref(),
+            &key_urls
+        )
+        .execute(self.inner_ref())
+        .await?;
 
         Ok(())
     }
@@ -81,7 +193,7 @@ impl RsaKeyRepository for PostgresDb {
         let actor_id = event.actor_id.as_ref();
         let key_url = event.key_url.as_str();
         let public_key = event.public_key.to_pkcs8()?;
-        let private_key = event.private_key.to_pkcs8()?;
+        let private_key = self.seal_private_key(&event.private_key.to_pkcs8()?, key_url)?;
         sqlx::query!(
             r#"
              INSERT INTO actor_rsa_keys
@@ -99,4 +211,885 @@ impl RsaKeyRepository for PostgresDb {
 
         Ok(())
     }
+    #[tracing::instrument(skip(self))]
+    async fn find_key_url(&self, user_id: &UserId) -> anyhow::Result<ResourceUrl> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                actor_rsa_keys.key_url AS key_url
+            FROM
+                actors
+            INNER JOIN
+                actor_rsa_keys
+            ON
+                actors.actor_id = actor_rsa_keys.actor_id
+                AND actor_rsa_keys.retired_at IS NULL
+            WHERE
+                actors.local_user_id = $1
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(row.key_url.parse()?)
+    }
+    #[tracing::instrument(skip_all)]
+    async fn rotate_key_pair(
+        &self,
+        event: SaveKeyPairEvent<'_>,
+        grace_period: Duration,
+    ) -> anyhow::Result<()> {
+        let actor_id = event.actor_id.as_ref();
+        let key_url = event.key_url.as_str();
+        let public_key = event.public_key.to_pkcs8()?;
+        let private_key = self.seal_private_key(&event.private_key.to_pkcs8()?, key_url)?;
+
+        let mut tx = self.inner_ref().begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE actor_rsa_keys
+            SET
+                private_key = NULL,
+                retired_at = current_timestamp,
+                expires_at = current_timestamp + make_interval(secs => $2)
+            WHERE
+                actor_rsa_keys.actor_id = $1
+                AND actor_rsa_keys.retired_at IS NULL
+            "#,
+            actor_id,
+            grace_period.as_secs_f64()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_rsa_keys
+                (actor_id, key_url, public_key, private_key)
+            VALUES
+                ($1, $2, $3, $4)
+            "#,
+            actor_id,
+            key_url,
+            &public_key,
+            &private_key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_ed25519_public_key(
+        &self,
+        user_id: &UserId,
+    ) -> anyhow::Result<Ed25519VerifyingKey> {
+        let row = sqlx::query_as!(
+            UserPublicEd25519KeyRow,
+            r#"
+            SELECT
+                actor_ed25519_keys.public_key AS public_key
+            FROM
+                actors
+            INNER JOIN
+                actor_ed25519_keys
+            ON
+                actors.actor_id = actor_ed25519_keys.actor_id
+                AND actor_ed25519_keys.retired_at IS NULL
+            WHERE
+                actors.local_user_id = $1
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_ed25519_public_keys(
+        &self,
+        user_id: &UserId,
+    ) -> anyhow::Result<Vec<ActorEd25519PublicKey>> {
+        let rows = sqlx::query_as!(
+            ActorEd25519PublicKeyRow,
+            r#"
+            SELECT
+                actors.actor_url AS actor_url,
+                actor_ed25519_keys.key_url AS key_url,
+                actor_ed25519_keys.public_key AS public_key
+            FROM
+                actors
+            INNER JOIN
+                actor_ed25519_keys
+            ON
+                actors.actor_id = actor_ed25519_keys.actor_id
+            WHERE
+                actors.local_user_id = $1
+                AND (
+                    actor_ed25519_keys.expires_at IS NULL
+                    OR actor_ed25519_keys.expires_at > current_timestamp
+                )
+            ORDER BY
+                actor_ed25519_keys.retired_at DESC NULLS FIRST
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        rows.into_iter()
+            .map(ActorEd25519PublicKey::try_from)
+            .collect()
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_ed25519_public_key_by_key_url(
+        &self,
+        key_url: &ResourceUrl,
+    ) -> anyhow::Result<ActorEd25519PublicKey> {
+        let row = sqlx::query_as!(
+            ActorEd25519PublicKeyRow,
+            r#"
+            SELECT
+                actors.actor_url AS actor_url,
+                actor_ed25519_keys.key_url AS key_url,
+                actor_ed25519_keys.public_key AS public_key
+            FROM
+                actor_ed25519_keys
+            INNER JOIN
+                actors
+            ON
+                actors.actor_id = actor_ed25519_keys.actor_id
+            WHERE
+                actor_ed25519_keys.key_url = $1
+                AND (
+                    actor_ed25519_keys.expires_at IS NULL
+                    OR actor_ed25519_keys.expires_at > current_timestamp
+                )
+            "#,
+            key_url.as_str()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_ed25519_private_key(
+        &self,
+        user_id: &UserId,
+    ) -> anyhow::Result<Ed25519SigningKey> {
+        let mut row = sqlx::query_as!(
+            UserPrivateEd25519KeyRow,
+            r#"
+            SELECT
+                actor_ed25519_keys.key_url AS key_url,
+                actor_ed25519_keys.private_key AS private_key
+            FROM
+                actors
+            INNER JOIN
+                actor_ed25519_keys
+            ON
+                actors.actor_id = actor_ed25519_keys.actor_id
+                AND actor_ed25519_keys.retired_at IS NULL
+            WHERE
+                actors.local_user_id = $1
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.private_key = self.open_private_key(row.private_key, &row.key_url)?;
+        row.try_into()
+    }
+    #[tracing::instrument(skip_all)]
+    async fn save_ed25519_public_key(
+        &self,
+        event: SaveEd25519PublicKeyEvent<'_>,
+    ) -> anyhow::Result<()> {
+        // 他の`Actor`の鍵のURLは上書きしない
+        let res = sqlx::query!(
+            r#"
+            INSERT INTO actor_ed25519_keys
+                (actor_id, key_url, public_key)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (key_url)
+            DO UPDATE SET public_key = EXCLUDED.public_key
+            WHERE actor_ed25519_keys.actor_id = EXCLUDED.actor_id
+            "#,
+            event.actor_id.as_ref(),
+            event.key_url.as_str(),
+            event.public_key.to_multibase()
+        )
+        .execute(self.inner_ref())
+        .await?;
+        if res.rows_affected() != 1 {
+            return Err(anyhow::anyhow!("key url is owned by another actor"));
+        }
+
+        Ok(())
+    }
+    #[tracing::instrument(skip(self))]
+    async fn delete_other_ed25519_public_keys(
+        &self,
+        actor_id: &ActorId,
+        key_urls: &[ResourceUrl],
+    ) -> anyhow::Result<()> {
+        let key_urls = key_urls
+            .iter()
+            .map(|v| v.as_str().to_string())
+            .collect::<Vec<_>>();
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                actor_ed25519_keys
+            WHERE
+                actor_ed25519_keys.actor_id = $1
+                AND NOT (actor_ed25519_keys.key_url = ANY($2))
+            "#,
+            actor_id.as_ref(),
+            &key_urls
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+    #[tracing::instrument(skip_all)]
+    async fn save_ed25519_key_pair(
+        &self,
+        event: SaveEd25519KeyPairEvent<'_>,
+    ) -> anyhow::Result<()> {
+        let private_key =
+            self.seal_private_key(&event.private_key.to_pkcs8()?, event.key_url.as_str())?;
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_ed25519_keys
+                (actor_id, key_url, public_key, private_key)
+            VALUES
+                ($1, $2, $3, $4)
+            "#,
+            event.actor_id.as_ref(),
+            event.key_url.as_str(),
+            event.public_key.to_multibase(),
+            &private_key
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+    #[tracing::instrument(skip(self))]
+    async fn find_ed25519_key_url(&self, user_id: &UserId) -> anyhow::Result<ResourceUrl> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                actor_ed25519_keys.key_url AS key_url
+            FROM
+                actors
+            INNER JOIN
+                actor_ed25519_keys
+            ON
+                actors.actor_id = actor_ed25519_keys.actor_id
+                AND actor_ed25519_keys.retired_at IS NULL
+            WHERE
+                actors.local_user_id = $1
+            "#,
+            user_id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(row.key_url.parse()?)
+    }
+    #[tracing::instrument(skip_all)]
+    async fn rotate_ed25519_key_pair(
+        &self,
+        event: SaveEd25519KeyPairEvent<'_>,
+        grace_period: Duration,
+    ) -> anyhow::Result<()> {
+        let actor_id = event.actor_id.as_ref();
+        let private_key =
+            self.seal_private_key(&event.private_key.to_pkcs8()?, event.key_url.as_str())?;
+
+        let mut tx = self.inner_ref().begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE actor_ed25519_keys
+            SET
+                private_key = NULL,
+                retired_at = current_timestamp,
+                expires_at = current_timestamp + make_interval(secs => $2)
+            WHERE
+                actor_ed25519_keys.actor_id = $1
+                AND actor_ed25519_keys.retired_at IS NULL
+            "#,
+            actor_id,
+            grace_period.as_secs_f64()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO actor_ed25519_keys
+                (actor_id, key_url, public_key, private_key)
+            VALUES
+                ($1, $2, $3, $4)
+            "#,
+            actor_id,
+            event.key_url.as_str(),
+            event.public_key.to_multibase(),
+            &private_key
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+    #[tracing::instrument(skip(self))]
+    async fn publishes_ed25519(&self, inbox: &ResourceUrl) -> anyhow::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT EXISTS (
+                SELECT
+                    1
+                FROM
+                    actors
+                INNER JOIN
+                    actor_ed25519_keys
+                ON
+                    actors.actor_id = actor_ed25519_keys.actor_id
+                WHERE
+                    actors.local_user_id IS NULL
+                    AND (actors.inbox_url = $1 OR actors.shared_inbox_url = $1)
+            ) AS "publishes!"
+            "#,
+            inbox.as_str()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        Ok(row.publishes)
+    }
+}
+
+impl PostgresDb {
+    /// 保存されている秘密鍵を今のマスター鍵で暗号化し直す
+    ///
+    /// 平文の秘密鍵は暗号化し、`previous`があればそれで暗号化された秘密鍵を今のマスター鍵で暗号化し直す。
+    /// 途中で止めて再び実行したときのために、今のマスター鍵で開ける秘密鍵はそのままにする
+    /// 暗号化し直した鍵の数を返す
+    ///
+    /// 開けない秘密鍵が1つでもあればエラーを返し、何も書き換えない
+    #[tracing::instrument(skip_all)]
+    pub async fn reencrypt_private_keys(
+        &self,
+        previous: Option<&MasterKey>,
+    ) -> anyhow::Result<u64> {
+        let master_key = self
+            .master_key()
+            .ok_or_else(|| anyhow::anyhow!("master key is not configured"))?;
+        let reencrypt = |private_key: &str, key_url: &str| -> anyhow::Result<Option<String>> {
+            let aad = key_url.as_bytes();
+            if !MasterKey::is_encrypted(private_key) {
+                return master_key.encrypt(private_key, aad).map(Some);
+            }
+            if master_key.decrypt(private_key, aad).is_ok() {
+                return Ok(None);
+            }
+            let Some(previous) = previous else {
+                anyhow::bail!(
+                    "private key of {key_url} cannot be decrypted with the master key, set the previous master key"
+                );
+            };
+            let plaintext = previous.decrypt(private_key, aad).map_err(|_| {
+                anyhow::anyhow!(
+                    "private key of {key_url} cannot be decrypted with either master key"
+                )
+            })?;
+            master_key.encrypt(&plaintext, aad).map(Some)
+        };
+
+        let mut tx = self.inner_ref().begin().await?;
+        let mut count = 0;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                actor_id, key_url, private_key AS "private_key!"
+            FROM
+                actor_rsa_keys
+            WHERE
+                private_key IS NOT NULL
+            FOR UPDATE
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for row in rows {
+            let Some(private_key) = reencrypt(&row.private_key, &row.key_url)? else {
+                continue;
+            };
+            sqlx::query!(
+                r#"
+                UPDATE actor_rsa_keys
+                SET
+                    private_key = $3
+                WHERE
+                    actor_id = $1
+                    AND key_url = $2
+                "#,
+                row.actor_id,
+                row.key_url,
+                private_key
+            )
+            .execute(&mut *tx)
+            .await?;
+            count += 1;
+        }
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                actor_id, key_url, private_key AS "private_key!"
+            FROM
+                actor_ed25519_keys
+            WHERE
+                private_key IS NOT NULL
+            FOR UPDATE
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for row in rows {
+            let Some(private_key) = reencrypt(&row.private_key, &row.key_url)? else {
+                continue;
+            };
+            sqlx::query!(
+                r#"
+                UPDATE actor_ed25519_keys
+                SET
+                    private_key = $3
+                WHERE
+                    actor_id = $1
+                    AND key_url = $2
+                "#,
+                row.actor_id,
+                row.key_url,
+                private_key
+            )
+            .execute(&mut *tx)
+            .await?;
+            count += 1;
+        }
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::activitypub::actor::ActorRepository;
+    use pretty_assertions::assert_eq;
+
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+    static BOB_KEY_URL: LazyLock<ResourceUrl> = LazyLock::new(|| {
+        "https://sub1.example.com/users/bob#main-key"
+            .parse::<_>()
+            .unwrap()
+    });
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_find_public_key_by_key_url(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+
+        let key = repo.find_public_key_by_key_url(&BOB_KEY_URL).await.unwrap();
+        assert_eq!(key.actor_url, *BOB_URL);
+        assert_eq!(key.key_url, *BOB_KEY_URL);
+
+        let unknown = "https://unknown.example.com/users/bob#main-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        assert!(repo.find_public_key_by_key_url(&unknown).await.is_err());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_save_public_key_twice(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let bob = repo.find_by_url(&BOB_URL).await.unwrap();
+        let bob_key = repo.find_public_key_by_key_url(&BOB_KEY_URL).await.unwrap();
+
+        let key_url = "https://sub1.example.com/users/bob#second-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        for _ in 0..2 {
+            let event = SavePublicKeyEvent::builder()
+                .actor_id(&bob.actor_id)
+                .key_url(&key_url)
+                .public_key(&bob_key.public_key)
+                .build();
+            repo.save_public_key(event).await.unwrap();
+        }
+
+        let key = repo.find_public_key_by_key_url(&key_url).await.unwrap();
+        assert_eq!(key.actor_url, *BOB_URL);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_save_other_actors_key_url(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let alice = repo
+            .find_by_url(&"https://example.com/users/alice".parse().unwrap())
+            .await
+            .unwrap();
+        let bob_key = repo.find_public_key_by_key_url(&BOB_KEY_URL).await.unwrap();
+
+        let event = SavePublicKeyEvent::builder()
+            .actor_id(&alice.actor_id)
+            .key_url(&BOB_KEY_URL)
+            .public_key(&bob_key.public_key)
+            .build();
+        assert!(repo.save_public_key(event).await.is_err());
+
+        let key = repo.find_public_key_by_key_url(&BOB_KEY_URL).await.unwrap();
+        assert_eq!(key.actor_url, *BOB_URL);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_delete_other_public_keys(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let bob = repo.find_by_url(&BOB_URL).await.unwrap();
+        let bob_key = repo.find_public_key_by_key_url(&BOB_KEY_URL).await.unwrap();
+
+        let new_key_url = "https://sub1.example.com/users/bob#new-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let event = SavePublicKeyEvent::builder()
+            .actor_id(&bob.actor_id)
+            .key_url(&new_key_url)
+            .public_key(&bob_key.public_key)
+            .build();
+        repo.save_public_key(event).await.unwrap();
+        let ed25519_key_url = "https://sub1.example.com/users/bob#ed25519-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let public_key = Ed25519SigningKey::new().to_public_key();
+        let event = SaveEd25519PublicKeyEvent::builder()
+            .actor_id(&bob.actor_id)
+            .key_url(&ed25519_key_url)
+            .public_key(&public_key)
+            .build();
+        repo.save_ed25519_public_key(event).await.unwrap();
+
+        // 取得し直した`Actor`に載っていない鍵では検証できなくなる
+        repo.delete_other_public_keys(&bob.actor_id, std::slice::from_ref(&new_key_url))
+            .await
+            .unwrap();
+        assert!(repo.find_public_key_by_key_url(&BOB_KEY_URL).await.is_err());
+        assert!(repo.find_public_key_by_key_url(&new_key_url).await.is_ok());
+
+        repo.delete_other_ed25519_public_keys(&bob.actor_id, &[])
+            .await
+            .unwrap();
+        assert!(!repo.publishes_ed25519(&bob.inbox).await.unwrap());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_rotate_key_pair(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let user_id = "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse().unwrap();
+        let testuser = repo
+            .find_by_url(&"https://example.com/users/testuser".parse().unwrap())
+            .await
+            .unwrap();
+        let old_key_url = repo.find_key_url(&user_id).await.unwrap();
+        assert_eq!(
+            old_key_url.as_str(),
+            "https://example.com/users/testuser#main-key"
+        );
+
+        let rotate = |key_url: &str, grace_period| {
+            let repo = &repo;
+            let actor_id = &testuser.actor_id;
+            let key_url = key_url.parse::<ResourceUrl>().unwrap();
+            async move {
+                let skey = RsaSingingKey::new().unwrap();
+                let pkey = skey.to_public_key();
+                let event = SaveKeyPairEvent::builder()
+                    .actor_id(actor_id)
+                    .key_url(&key_url)
+                    .public_key(&pkey)
+                    .private_key(&skey)
+                    .build();
+                repo.rotate_key_pair(event, grace_period).await.unwrap();
+                (key_url, pkey)
+            }
+        };
+
+        // 猶予期間の間は古い鍵でも検証できる
+        let (second_key_url, second_key) = rotate(
+            "https://example.com/users/testuser#rsa-key-2",
+            Duration::from_secs(60 * 60),
+        )
+        .await;
+        assert_eq!(repo.find_key_url(&user_id).await.unwrap(), second_key_url);
+        let second_key = second_key.to_pkcs8().unwrap();
+        let found = repo.find_public_key(&user_id).await.unwrap();
+        assert_eq!(found.to_pkcs8().unwrap(), second_key);
+        let found = repo.find_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key().to_pkcs8().unwrap(), second_key);
+        assert!(repo.find_public_key_by_key_url(&old_key_url).await.is_ok());
+        // 古い鍵のURLで`Actor`を取得した相手も、猶予期間の間はその鍵を見つけられる
+        let keys = repo.find_public_keys(&user_id).await.unwrap();
+        let key_urls = keys.iter().map(|v| v.key_url.clone()).collect::<Vec<_>>();
+        assert_eq!(key_urls, vec![second_key_url.clone(), old_key_url.clone()]);
+        assert_eq!(keys[0].public_key.to_pkcs8().unwrap(), second_key);
+
+        // 猶予期間がなければすぐに検証できなくなる
+        let (third_key_url, _) = rotate(
+            "https://example.com/users/testuser#rsa-key-3",
+            Duration::ZERO,
+        )
+        .await;
+        assert_eq!(repo.find_key_url(&user_id).await.unwrap(), third_key_url);
+        assert!(repo
+            .find_public_key_by_key_url(&second_key_url)
+            .await
+            .is_err());
+        assert!(repo
+            .find_public_key_by_key_url(&third_key_url)
+            .await
+            .is_ok());
+        let keys = repo.find_public_keys(&user_id).await.unwrap();
+        let key_urls = keys.iter().map(|v| v.key_url.clone()).collect::<Vec<_>>();
+        assert_eq!(key_urls, vec![third_key_url.clone(), old_key_url.clone()]);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_reencrypt_private_keys(pool: sqlx::PgPool) {
+        let user_id = "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse().unwrap();
+        let private_keys = || {
+            sqlx::query_scalar!(
+                r#"SELECT private_key AS "private_key!" FROM actor_rsa_keys WHERE private_key IS NOT NULL"#
+            )
+            .fetch_all(&pool)
+        };
+        let stored = private_keys().await.unwrap();
+        let count = u64::try_from(stored.len()).unwrap();
+
+        // 平文で保存された鍵も読める
+        let plain = PostgresDb::new(pool.clone());
+        let expected = plain.find_private_key(&user_id).await.unwrap();
+        let expected = expected.to_public_key().to_pkcs8().unwrap();
+
+        let previous = MasterKey::generate();
+        let repo = PostgresDb::new(pool.clone()).with_master_key(previous.clone());
+        assert_eq!(repo.reencrypt_private_keys(None).await.unwrap(), count);
+        assert_eq!(repo.reencrypt_private_keys(None).await.unwrap(), 0);
+        let stored = private_keys().await.unwrap();
+        assert!(stored.iter().all(|v| MasterKey::is_encrypted(v)));
+        assert!(plain.find_private_key(&user_id).await.is_err());
+
+        // マスター鍵を替えて暗号化し直す
+        let repo = PostgresDb::new(pool.clone()).with_master_key(MasterKey::generate());
+        assert!(repo.find_private_key(&user_id).await.is_err());
+        assert_eq!(
+            repo.reencrypt_private_keys(Some(&previous)).await.unwrap(),
+            count
+        );
+        let found = repo.find_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key().to_pkcs8().unwrap(), expected);
+
+        // 今のマスター鍵で暗号化された鍵が混ざっていても、それ以外だけを暗号化し直す
+        let next = MasterKey::generate();
+        let current = repo.master_key().unwrap().clone();
+        let repo = PostgresDb::new(pool.clone()).with_master_key(next.clone());
+        let row = sqlx::query!(
+            r#"SELECT actor_id, key_url, private_key AS "private_key!" FROM actor_rsa_keys WHERE private_key IS NOT NULL LIMIT 1"#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let aad = row.key_url.as_bytes();
+        let resealed = next
+            .encrypt(&current.decrypt(&row.private_key, aad).unwrap(), aad)
+            .unwrap();
+        sqlx::query!(
+            "UPDATE actor_rsa_keys SET private_key = $3 WHERE actor_id = $1 AND key_url = $2",
+            row.actor_id,
+            row.key_url,
+            resealed
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            repo.reencrypt_private_keys(Some(&current)).await.unwrap(),
+            count - 1
+        );
+        let found = repo.find_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key().to_pkcs8().unwrap(), expected);
+
+        // 開けない鍵があれば、成功したことにせずエラーにする
+        let repo = PostgresDb::new(pool.clone()).with_master_key(MasterKey::generate());
+        assert!(repo.reencrypt_private_keys(None).await.is_err());
+        assert!(repo.reencrypt_private_keys(Some(&current)).await.is_err());
+        let repo = PostgresDb::new(pool.clone()).with_master_key(next);
+        let found = repo.find_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key().to_pkcs8().unwrap(), expected);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_encrypted_private_key_bound_to_key_url(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool.clone()).with_master_key(MasterKey::generate());
+        let user_id = "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse().unwrap();
+        let testuser = repo
+            .find_by_url(&"https://example.com/users/testuser".parse().unwrap())
+            .await
+            .unwrap();
+        let key_url = "https://example.com/users/testuser#ed25519-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let skey = Ed25519SigningKey::new();
+        let pkey = skey.to_public_key();
+        let event = SaveEd25519KeyPairEvent::builder()
+            .actor_id(&testuser.actor_id)
+            .key_url(&key_url)
+            .public_key(&pkey)
+            .private_key(&skey)
+            .build();
+        repo.save_ed25519_key_pair(event).await.unwrap();
+        repo.find_ed25519_private_key(&user_id).await.unwrap();
+
+        // 別の鍵のURLの行に移された暗号文は開けない
+        sqlx::query!(
+            "UPDATE actor_ed25519_keys SET key_url = $2 WHERE key_url = $1",
+            key_url.as_str(),
+            "https://example.com/users/testuser#ed25519-key-2"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(repo.find_ed25519_private_key(&user_id).await.is_err());
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_ed25519_keys(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let user_id = "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse().unwrap();
+        let testuser = repo
+            .find_by_url(&"https://example.com/users/testuser".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(repo.find_ed25519_private_key(&user_id).await.is_err());
+
+        let skey = Ed25519SigningKey::new();
+        let pkey = skey.to_public_key();
+        let key_url = "https://example.com/users/testuser#ed25519-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let event = SaveEd25519KeyPairEvent::builder()
+            .actor_id(&testuser.actor_id)
+            .key_url(&key_url)
+            .public_key(&pkey)
+            .private_key(&skey)
+            .build();
+        repo.save_ed25519_key_pair(event).await.unwrap();
+
+        assert_eq!(repo.find_ed25519_public_key(&user_id).await.unwrap(), pkey);
+        let found = repo.find_ed25519_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key(), pkey);
+        assert_eq!(repo.find_ed25519_key_url(&user_id).await.unwrap(), key_url);
+
+        // 置き換えた古い鍵も猶予期間の間は公開する
+        let new_skey = Ed25519SigningKey::new();
+        let new_pkey = new_skey.to_public_key();
+        let new_key_url = "https://example.com/users/testuser#ed25519-key-2"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let event = SaveEd25519KeyPairEvent::builder()
+            .actor_id(&testuser.actor_id)
+            .key_url(&new_key_url)
+            .public_key(&new_pkey)
+            .private_key(&new_skey)
+            .build();
+        repo.rotate_ed25519_key_pair(event, Duration::from_secs(60 * 60))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.find_ed25519_key_url(&user_id).await.unwrap(),
+            new_key_url
+        );
+        let found = repo.find_ed25519_private_key(&user_id).await.unwrap();
+        assert_eq!(found.to_public_key(), new_pkey);
+        let keys = repo.find_ed25519_public_keys(&user_id).await.unwrap();
+        let keys = keys
+            .into_iter()
+            .map(|v| (v.key_url, v.public_key))
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![(new_key_url, new_pkey), (key_url, pkey)]);
+    }
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_publishes_ed25519(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let bob = repo.find_by_url(&BOB_URL).await.unwrap();
+        let bob_inbox = bob.inbox.clone();
+        assert!(!repo.publishes_ed25519(&bob_inbox).await.unwrap());
+
+        let key_url = "https://sub1.example.com/users/bob#ed25519-key"
+            .parse::<ResourceUrl>()
+            .unwrap();
+        let public_key = Ed25519SigningKey::new().to_public_key();
+        let event = SaveEd25519PublicKeyEvent::builder()
+            .actor_id(&bob.actor_id)
+            .key_url(&key_url)
+            .public_key(&public_key)
+            .build();
+        repo.save_ed25519_public_key(event).await.unwrap();
+
+        assert!(repo.publishes_ed25519(&bob_inbox).await.unwrap());
+        let key = repo
+            .find_ed25519_public_key_by_key_url(&key_url)
+            .await
+            .unwrap();
+        assert_eq!(key.actor_url, *BOB_URL);
+        assert_eq!(key.public_key, public_key);
+
+        // 他の`Actor`の鍵のURLは上書きしない
+        let alice = repo
+            .find_by_url(&"https://example.com/users/alice".parse().unwrap())
+            .await
+            .unwrap();
+        let other_key = Ed25519SigningKey::new().to_public_key();
+        let event = SaveEd25519PublicKeyEvent::builder()
+            .actor_id(&alice.actor_id)
+            .key_url(&key_url)
+            .public_key(&other_key)
+            .build();
+        assert!(repo.save_ed25519_public_key(event).await.is_err());
+        let key = repo
+            .find_ed25519_public_key_by_key_url(&key_url)
+            .await
+            .unwrap();
+        assert_eq!(key.actor_url, *BOB_URL);
+    }
 }
@@ -0,0 +1,81 @@
+use crate::{model::sent_activity::SentActivityRow, persistence::postgres::PostgresDb};
+use apub_kernel::sent_activity::{
+    model::{SentActivity, SentActivityId},
+    repository::SentActivityRepository,
+};
+
+#[async_trait::async_trait]
+impl SentActivityRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn find(&self, id: &SentActivityId) -> anyhow::Result<SentActivity> {
+        let row = sqlx::query_as!(
+            SentActivityRow,
+            r#"
+            SELECT
+                activity_id,
+                actor_url,
+                activity::TEXT AS "activity!"
+            FROM
+                sent_activities
+            WHERE
+                sent_activities.activity_id = $1
+            "#,
+            id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        row.try_into()
+    }
+
+    #[tracing::instrument(skip_all, fields(id = %activity.id))]
+    async fn create(&self, activity: &SentActivity) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO sent_activities
+                (activity_id, actor_url, activity)
+            VALUES
+                ($1, $2, $3::TEXT::JSONB)
+            ON CONFLICT (activity_id)
+            DO NOTHING
+            "#,
+            activity.id.as_ref(),
+            activity.actor_url.as_str(),
+            activity.activity.to_string()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[sqlx::test]
+    async fn test_sent_activity(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let activity = serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/activities/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69",
+            "type": "Follow",
+            "actor": "https://example.com/users/alice",
+            "object": "https://remote.example/users/bob",
+        });
+        let sent = SentActivity::builder()
+            .id("0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69".parse().unwrap())
+            .actor_url("https://example.com/users/alice".parse().unwrap())
+            .activity(activity)
+            .build();
+
+        // 同じ`Activity`を複数の`inbox`に送っても1つだけ保存される
+        repo.create(&sent).await.unwrap();
+        repo.create(&sent).await.unwrap();
+
+        let found = repo.find(&sent.id).await.unwrap();
+        assert_eq!(found, sent);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{model::share::ShareRow, persistence::postgres::PostgresDb};
+use apub_kernel::{
+    note::model::NoteId,
+    share::{model::Share, repository::ShareRepository},
+};
+use apub_shared::model::resource_url::ResourceUrl;
+
+#[async_trait::async_trait]
+impl ShareRepository for PostgresDb {
+    #[tracing::instrument(skip(self))]
+    async fn find_by_note(&self, note_id: &NoteId) -> anyhow::Result<Vec<Share>> {
+        let rows = sqlx::query_as!(
+            ShareRow,
+            r#"
+            SELECT
+                shares.note_id AS note_id,
+                actors.actor_url AS actor_url
+            FROM
+                shares
+            INNER JOIN
+                actors
+            ON
+                shares.actor_id = actors.actor_id
+            WHERE
+                shares.note_id = $1
+            ORDER BY
+                shares.created_at
+            "#,
+            note_id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        let shares = rows
+            .into_iter()
+            .filter_map(|row| Share::try_from(row).ok())
+            .collect();
+
+        Ok(shares)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn create(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO shares
+                (note_id, actor_id)
+            VALUES
+                (
+                $1,
+                (SELECT actor_id FROM actors WHERE actor_url = $2)
+            )
+            ON CONFLICT (note_id, actor_id) DO NOTHING
+            "#,
+            note_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn delete(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                shares
+            WHERE
+                shares.note_id = $1
+                AND shares.actor_id IN (SELECT actor_id FROM actors WHERE actor_url = $2)
+            "#,
+            note_id.as_ref(),
+            actor_url.as_str()
+        )
+        .execute(self.inner_ref())
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use super::*;
+    use apub_kernel::{
+        note::{model::CreateNote, repository::NoteRepository},
+        user::model::UserId,
+    };
+    use pretty_assertions::assert_eq;
+
+    static USER_ID: LazyLock<UserId> =
+        LazyLock::new(|| "ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse::<_>().unwrap());
+    static BOB_URL: LazyLock<ResourceUrl> =
+        LazyLock::new(|| "https://sub1.example.com/users/bob".parse::<_>().unwrap());
+
+    #[sqlx::test(fixtures(path = "fixtures", scripts("users", "actors")))]
+    async fn test_share_note(pool: sqlx::PgPool) {
+        let repo = PostgresDb::new(pool);
+        let note = CreateNote::new(USER_ID.clone(), "hello".to_string());
+        NoteRepository::create(&repo, &note).await.unwrap();
+
+        // 同じ`Announce`が二度届いても一つだけ
+        for _ in 0..2 {
+            ShareRepository::create(&repo, &note.note_id, &BOB_URL)
+                .await
+                .unwrap();
+        }
+
+        let shares = repo.find_by_note(&note.note_id).await.unwrap();
+        let actors = shares.into_iter().map(|v| v.actor_url).collect::<Vec<_>>();
+        assert_eq!(actors, vec![BOB_URL.clone()]);
+
+        ShareRepository::delete(&repo, &note.note_id, &BOB_URL)
+            .await
+            .unwrap();
+        let shares = repo.find_by_note(&note.note_id).await.unwrap();
+        assert!(shares.is_empty());
+
+        // `Undo`が二度届いてもエラーにしない
+        ShareRepository::delete(&repo, &note.note_id, &BOB_URL)
+            .await
+            .unwrap();
+    }
+}
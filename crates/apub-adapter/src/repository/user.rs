@@ -1,9 +1,12 @@
 use apub_kernel::user::{
-    model::{CreateUser, User, UserId},
+    model::{CreateUser, User, UserId, UserProfile},
     repository::UserRepository,
 };
 
-use crate::{model::user::UserRow, persistence::postgres::PostgresDb};
+use crate::{
+    model::user::{ProfileFieldRow, UserProfileRow, UserRow},
+    persistence::postgres::PostgresDb,
+};
 
 #[async_trait::async_trait]
 impl UserRepository for PostgresDb {
@@ -60,6 +63,101 @@ impl UserRepository for PostgresDb {
 
         Ok(user)
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn find_profile(&self, id: &UserId) -> anyhow::Result<UserProfile> {
+        let row = sqlx::query_as!(
+            UserProfileRow,
+            r#"
+            SELECT
+                display_name, summary, icon_url, manually_approves_followers
+            FROM
+                users
+            WHERE
+                users.user_id = $1"#,
+            id.as_ref()
+        )
+        .fetch_one(self.inner_ref())
+        .await?;
+
+        let fields = sqlx::query_as!(
+            ProfileFieldRow,
+            r#"
+            SELECT
+                name, value
+            FROM
+                user_profile_fields
+            WHERE
+                user_profile_fields.user_id = $1
+            ORDER BY
+                position"#,
+            id.as_ref()
+        )
+        .fetch_all(self.inner_ref())
+        .await?;
+
+        row.into_profile(fields)
+    }
+
+    #[tracing::instrument(skip(self, profile))]
+    async fn update_profile(&self, id: &UserId, profile: &UserProfile) -> anyhow::Result<()> {
+        let mut tx = self.inner_ref().begin().await?;
+
+        let count = sqlx::query!(
+            r#"
+            UPDATE users
+            SET
+                display_name = $2,
+                summary = $3,
+                icon_url = $4,
+                manually_approves_followers = $5
+            WHERE
+                users.user_id = $1
+        "#,
+            id.as_ref(),
+            profile.display_name,
+            profile.summary,
+            profile.icon.as_ref().map(|v| v.as_str()),
+            profile.manually_approves_followers
+        )
+        .execute(&mut *tx)
+        .await?;
+        if count.rows_affected() != 1 {
+            return Err(anyhow::anyhow!("No rows updated"));
+        }
+
+        sqlx::query!(
+            r#"
+            DELETE FROM user_profile_fields
+            WHERE
+                user_profile_fields.user_id = $1
+        "#,
+            id.as_ref()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (position, field) in profile.fields.iter().enumerate() {
+            sqlx::query!(
+                r#"
+                INSERT INTO user_profile_fields
+                    (user_id, position, name, value)
+                VALUES
+                    ($1, $2, $3, $4)
+            "#,
+                id.as_ref(),
+                i32::try_from(position)?,
+                field.name,
+                field.value
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -82,4 +180,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test]
+    async fn test_update_profile(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        use apub_kernel::user::model::ProfileField;
+
+        let repo = PostgresDb::new(pool);
+        let user = repo
+            .create(CreateUser {
+                name: "john".to_string(),
+            })
+            .await?;
+        assert_eq!(repo.find_profile(&user.id).await?, UserProfile::default());
+
+        let profile = UserProfile::builder()
+            .display_name("John".to_string())
+            .summary("<p>hello</p>".to_string())
+            .icon("https://example.com/john.png".parse()?)
+            .fields(vec![
+                ProfileField::builder()
+                    .name("Website".to_string())
+                    .value("https://example.com".to_string())
+                    .build(),
+                ProfileField::builder()
+                    .name("Pronouns".to_string())
+                    .value("they/them".to_string())
+                    .build(),
+            ])
+            .build();
+        repo.update_profile(&user.id, &profile).await?;
+        assert_eq!(repo.find_profile(&user.id).await?, profile);
+
+        // 項目は置き換えられる
+        let profile = UserProfile::builder()
+            .display_name("John".to_string())
+            .manually_approves_followers(true)
+            .build();
+        repo.update_profile(&user.id, &profile).await?;
+        assert_eq!(repo.find_profile(&user.id).await?, profile);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,300 @@
+use std::{sync::Arc, time::Duration};
+
+use apub_config::AppConfig;
+use apub_kernel::{
+    activitypub::activity::ActivityRepository,
+    delivery::{
+        model::{DeliveryError, DeliveryJob, RetryDecision, RetryPolicy},
+        repository::DeliveryRepository,
+    },
+    instance::repository::InstanceRepository,
+    rsa_key::repository::RsaKeyRepository,
+    user::repository::UserRepository,
+};
+use tokio::task::JoinHandle;
+
+use crate::persistence::{http_client::HttpClient, postgres::PostgresDb};
+
+/// 1回に取り出すジョブの数
+const BATCH_SIZE: i64 = 10;
+/// ジョブがないときに待つ時間
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 取り出したジョブを他のワーカーから隠しておく時間
+///
+/// ワーカーが途中で落ちても、この時間が過ぎれば他のワーカーが拾う
+const LOCK_FOR: Duration = Duration::from_secs(5 * 60);
+/// 到達できないホストを試す時刻になったか確かめる間隔
+const PROBE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 配送キューからジョブを取り出して`inbox`へ送るワーカー
+#[derive(Clone)]
+pub struct DeliveryWorker {
+    db: PostgresDb,
+    client: HttpClient,
+    config: Arc<AppConfig>,
+    policy: RetryPolicy,
+}
+
+impl DeliveryWorker {
+    pub fn new(db: PostgresDb, client: HttpClient, config: Arc<AppConfig>) -> Self {
+        Self {
+            db,
+            client,
+            config,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// `workers`個のタスクで配送し続け、別のタスクで到達できないホストを試し続ける
+    pub fn spawn(self, workers: usize) -> Vec<JoinHandle<()>> {
+        let mut handles = (0..workers)
+            .map(|worker| {
+                let this = self.clone();
+                tokio::spawn(async move { this.run(worker).await })
+            })
+            .collect::<Vec<_>>();
+        handles.push(tokio::spawn(async move { self.probe().await }));
+        handles
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn run(&self, worker: usize) {
+        loop {
+            match self.run_once().await {
+                Ok(0) => tokio::time::sleep(POLL_INTERVAL).await,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn probe(&self) {
+        loop {
+            if let Err(e) = self.probe_once().await {
+                tracing::error!(error = %e);
+            }
+            tokio::time::sleep(PROBE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// 次に試す時刻になった到達できないホストへリクエストを送り、試した数を返す
+    ///
+    /// 新しく送る`Activity`がなくても、応答があれば配送を再開する
+    pub async fn probe_once(&self) -> anyhow::Result<usize> {
+        let hosts = self.db.lock_probe_due(BATCH_SIZE, LOCK_FOR).await?;
+        let count = hosts.len();
+
+        for host in hosts {
+            let result = self.send_probe(&host).await;
+            match &result {
+                Ok(()) => tracing::info!(host = %host, "Host is reachable again"),
+                Err(e) => tracing::info!(host = %host, error = %e, "Host is still unreachable"),
+            }
+            self.record_instance(&host, &result).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// ホストが応答するか確かめる
+    ///
+    /// 4xxが返ってもホスト自体は応答しているので成功とする
+    async fn send_probe(&self, host: &str) -> anyhow::Result<()> {
+        let res = self
+            .client
+            .inner_ref()
+            .get(format!("https://{host}/.well-known/nodeinfo"))
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Network(e.to_string()))?;
+
+        if res.status().is_server_error() {
+            return Err(DeliveryError::Status(res.status().as_u16()).into());
+        }
+        Ok(())
+    }
+
+    /// 送る時刻になったジョブを1回分処理し、処理した数を返す
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        let jobs = self.db.lock_due(BATCH_SIZE, LOCK_FOR).await?;
+        let count = jobs.len();
+
+        for job in jobs {
+            // 積んだ後で到達できなくなったホストへは、次に試した後まで送らずに待つ
+            let host = job.inbox.host();
+            if !self.db.find_unreachable(&[host]).await?.is_empty() {
+                let delay = self.config.probe_interval() + PROBE_POLL_INTERVAL;
+                tracing::info!(job = %job.id, host, ?delay, "Postpone delivery to unreachable host");
+                self.db.postpone(&job.id, delay).await?;
+                continue;
+            }
+
+            let result = self.deliver(&job).await;
+            self.record_instance(host, &result).await?;
+            self.finish(&job, result).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// 送り先がEd25519の署名を受け入れるならEd25519で、それ以外はRSAで署名して送る
+    ///
+    /// `assertionMethod`のEd25519の鍵はHTTP Signatureに使えるとは限らないので、
+    /// 鍵を公開しているホストへはまず一度Ed25519で送ってみて、受け入れたかをホストごとに覚える。
+    /// 401で拒否された場合はRSAで送り直し、以後そのホストへはRSAだけで送る
+    async fn deliver(&self, job: &DeliveryJob) -> anyhow::Result<()> {
+        let user = self.db.find_by_id(&job.user_id).await?;
+        let host = job.inbox.host();
+
+        let try_ed25519 = match self.client.accepts_ed25519(host) {
+            Some(accepted) => accepted,
+            None => self.db.publishes_ed25519(&job.inbox).await?,
+        };
+        if try_ed25519 {
+            if let Ok(signing_key) = self.db.find_ed25519_private_key(&user.id).await {
+                let key_uri = self.db.find_ed25519_key_url(&user.id).await?;
+                let result = self
+                    .client
+                    .post_activity(&job.activity, &job.inbox, &signing_key, &key_uri)
+                    .await;
+                let unauthorized = result
+                    .as_ref()
+                    .is_err_and(|e| matches!(e.downcast_ref(), Some(DeliveryError::Status(401))));
+                if !unauthorized {
+                    if result.is_ok() {
+                        self.client.record_ed25519(host, true);
+                    }
+                    return result;
+                }
+                tracing::info!(inbox = %job.inbox, "Ed25519 signature rejected, retrying with RSA");
+                self.client.record_ed25519(host, false);
+            }
+        }
+
+        let signing_key = self.db.find_private_key(&user.id).await?;
+        let key_uri = self.db.find_key_url(&user.id).await?;
+
+        self.client
+            .post_activity(&job.activity, &job.inbox, &signing_key, &key_uri)
+            .await
+    }
+
+    /// 配送の結果から送り先のホストの到達状況を更新する
+    async fn record_instance(&self, host: &str, result: &anyhow::Result<()>) -> anyhow::Result<()> {
+        match result {
+            Ok(()) => self.db.record_success(host).await,
+            Err(e)
+                if e.downcast_ref::<DeliveryError>()
+                    .is_some_and(DeliveryError::is_host_failure) =>
+            {
+                self.db
+                    .record_failure(
+                        host,
+                        self.config.unreachable_after(),
+                        self.config.probe_interval(),
+                    )
+                    .await
+            }
+            Err(_) => Ok(()),
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(job = %job.id, inbox = %job.inbox, attempts = job.attempts))]
+    async fn finish(&self, job: &DeliveryJob, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        let Err(e) = result else {
+            tracing::info!("Delivered");
+            return self.db.complete(&job.id).await;
+        };
+
+        match self.policy.decide(job.attempts, &e) {
+            RetryDecision::Retry(delay) => {
+                tracing::warn!(error = %e, ?delay, "Delivery failed, retrying");
+                self.db.retry(&job.id, delay, &e.to_string()).await
+            }
+            RetryDecision::Dead => {
+                tracing::error!(error = %e, "Delivery failed, giving up");
+                self.db.dead(&job.id, &e.to_string()).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use apub_kernel::delivery::model::NewDeliveryJob;
+    use pretty_assertions::assert_eq;
+
+    /// 名前解決できないので、試すと必ず失敗する
+    const HOST: &str = "unreachable.invalid";
+
+    #[sqlx::test]
+    async fn test_probe_without_new_activity(pool: sqlx::PgPool) {
+        let db = PostgresDb::new(pool);
+        let config =
+            Arc::new(AppConfig::new("https://example.com").with_unreachable_after(Duration::ZERO));
+        let worker = DeliveryWorker::new(db.clone(), HttpClient::new(), config);
+
+        // 次に試す時刻を過ぎた到達できないホスト
+        db.record_failure(HOST, Duration::ZERO, Duration::ZERO)
+            .await
+            .unwrap();
+        assert!(db.find_unreachable(&[HOST]).await.unwrap().is_empty());
+
+        // 送るジョブがなくても試し、失敗すれば次に試す時刻まで再び止める
+        assert_eq!(worker.probe_once().await.unwrap(), 1);
+        assert_eq!(db.find_unreachable(&[HOST]).await.unwrap(), [HOST]);
+        assert_eq!(worker.probe_once().await.unwrap(), 0);
+    }
+
+    #[sqlx::test(fixtures(path = "../repository/fixtures", scripts("users")))]
+    async fn test_postpone_to_unreachable_host(pool: sqlx::PgPool) {
+        let db = PostgresDb::new(pool.clone());
+        let probe_interval = Duration::from_secs(60 * 60);
+        let config =
+            Arc::new(AppConfig::new("https://example.com").with_probe_interval(probe_interval));
+        let worker = DeliveryWorker::new(db.clone(), HttpClient::new(), config);
+
+        db.record_failure(HOST, Duration::ZERO, probe_interval)
+            .await
+            .unwrap();
+        let job = NewDeliveryJob::builder()
+            .user_id("ce68da5d-692e-4c9c-ab36-3322dd6bf214".parse().unwrap())
+            .inbox(format!("https://{HOST}/inbox").parse().unwrap())
+            .activity(serde_json::json!({ "type": "Create" }))
+            .build();
+        db.enqueue(std::slice::from_ref(&job)).await.unwrap();
+
+        // 到達できないホストへのジョブは諦めず、次に試した後まで待たせる
+        assert_eq!(worker.run_once().await.unwrap(), 1);
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                status,
+                attempts,
+                next_attempt_at > current_timestamp + make_interval(secs => $2) AS "after_probe!"
+            FROM
+                delivery_jobs
+            WHERE
+                job_id = $1
+            "#,
+            job.id.as_ref(),
+            probe_interval.as_secs_f64()
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row.status, "pending");
+        assert_eq!(row.attempts, 0);
+        assert!(row.after_probe);
+    }
+}
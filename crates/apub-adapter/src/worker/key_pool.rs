@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use apub_config::AppConfig;
+use apub_kernel::rsa_key::{model::RsaSingingKey, pool::RsaKeyPool};
+use tokio::{
+    sync::{mpsc, Mutex},
+    task::JoinHandle,
+};
+
+/// 鍵を作れなかったときに作り直すまでの最初の間隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// 作り直すまでの間隔の上限
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// RSA鍵を別のスレッドで作っておき、ユーザを作るときに渡す
+///
+/// 鍵が取り出されると[`KeyPool::spawn`]のタスクが作り足す
+#[derive(Clone)]
+pub struct KeyPool {
+    sender: mpsc::Sender<RsaSingingKey>,
+    receiver: Arc<Mutex<mpsc::Receiver<RsaSingingKey>>>,
+    bits: usize,
+}
+
+impl KeyPool {
+    pub fn new(config: &AppConfig) -> Self {
+        // 容量が0のチャネルは作れないので、少なくとも1つは作っておく
+        let (sender, receiver) = mpsc::channel(config.key_pool_size().max(1));
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            bits: config.rsa_key_bits(),
+        }
+    }
+
+    /// 空きがある間は鍵を作り足し続ける
+    pub fn spawn(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        tokio::spawn(async move { this.run().await })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn run(&self) {
+        let mut retry_interval = RETRY_INTERVAL;
+        // 空きができるまで待ってから作る
+        while let Ok(permit) = self.sender.reserve().await {
+            match generate(self.bits).await {
+                Ok(key) => {
+                    permit.send(key);
+                    retry_interval = RETRY_INTERVAL;
+                }
+                Err(e) => {
+                    // 作れなくても`take`がその場で作るので、間隔を空けて作り直す
+                    tracing::error!(error = %e, retry_in = ?retry_interval);
+                    drop(permit);
+                    tokio::time::sleep(retry_interval).await;
+                    retry_interval = (retry_interval * 2).min(MAX_RETRY_INTERVAL);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RsaKeyPool for KeyPool {
+    async fn take(&self) -> anyhow::Result<RsaSingingKey> {
+        if let Ok(key) = self.receiver.lock().await.try_recv() {
+            return Ok(key);
+        }
+
+        tracing::info!("Key pool is empty, generating a key");
+        generate(self.bits).await
+    }
+}
+
+/// 非同期のスレッドを止めないように鍵を作る
+async fn generate(bits: usize) -> anyhow::Result<RsaSingingKey> {
+    tokio::task::spawn_blocking(move || RsaSingingKey::with_bits(bits)).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BITS: usize = 1024;
+
+    #[tokio::test]
+    async fn test_take_from_empty_pool() {
+        let config = AppConfig::new("https://example.com").with_rsa_key_bits(BITS);
+        let pool = KeyPool::new(&config);
+
+        assert!(pool.take().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_keep_running_after_error() {
+        // 作れない長さを指定して失敗させる
+        let config = AppConfig::new("https://example.com").with_rsa_key_bits(0);
+        let pool = KeyPool::new(&config);
+        let task = pool.spawn();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!task.is_finished());
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_refill() {
+        let config = AppConfig::new("https://example.com")
+            .with_rsa_key_bits(BITS)
+            .with_key_pool_size(2);
+        let pool = KeyPool::new(&config);
+        let _task = pool.spawn();
+
+        // 取り出したら作り足される
+        for _ in 0..2 {
+            tokio::time::timeout(Duration::from_secs(30), async {
+                while pool.receiver.lock().await.len() < 2 {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap();
+
+            for _ in 0..2 {
+                assert!(pool.receiver.lock().await.try_recv().is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::SystemTime;
+
+use apub_activitypub::shared::activity_json::{ActivityJson, ActivityJsonRejection};
+use apub_kernel::{
+    prelude::*,
+    rsa_key::{http_signature::HttpVerifier, signature::SignatureError},
+};
+use apub_registry::{AppRegistry, AppRegistryExt};
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{
+    body::{Body, Bytes},
+    extract::{FromRef, FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts, Method, StatusCode},
+    response::IntoResponse,
+};
+use serde::de::DeserializeOwned;
+
+/// 署名者と照合するための`actor`を持つActivity
+pub trait ActivityActor {
+    fn actor_url(&self) -> &ResourceUrl;
+}
+
+/// HTTP Signatureを検証したActivity
+///
+/// 署名に使われた鍵の所有者とActivityの`actor`が一致すること、
+/// `Digest`がボディと一致すること、`Date`が許容範囲内にあることも確認する
+///
+/// draft-cavageとRFC 9421のどちらの形式の署名も受け付ける
+///
+/// See
+/// - https://docs.joinmastodon.org/spec/security/#http-verify
+/// - https://www.rfc-editor.org/rfc/rfc9421.html
+pub struct SignedActivityJson<T>(pub T);
+
+pub enum SignatureRejection {
+    Activity(ActivityJsonRejection),
+    MissingSignature,
+    InvalidSignature(SignatureError),
+    Unauthorized(anyhow::Error),
+    ActorMismatch,
+}
+
+impl From<ActivityJsonRejection> for SignatureRejection {
+    fn from(value: ActivityJsonRejection) -> Self {
+        SignatureRejection::Activity(value)
+    }
+}
+
+impl From<SignatureError> for SignatureRejection {
+    fn from(value: SignatureError) -> Self {
+        SignatureRejection::InvalidSignature(value)
+    }
+}
+
+impl IntoResponse for SignatureRejection {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            SignatureRejection::Activity(rej) => rej.into_response(),
+            SignatureRejection::MissingSignature => {
+                (StatusCode::UNAUTHORIZED, "Missing signature").into_response()
+            }
+            SignatureRejection::InvalidSignature(e) => {
+                tracing::warn!(error = %e);
+                (StatusCode::UNAUTHORIZED, e.to_string()).into_response()
+            }
+            SignatureRejection::Unauthorized(e) => {
+                tracing::warn!(error = %e);
+                (StatusCode::UNAUTHORIZED, "Signature verification failed").into_response()
+            }
+            SignatureRejection::ActorMismatch => (
+                StatusCode::UNAUTHORIZED,
+                "Signer does not match activity actor",
+            )
+                .into_response(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, S> FromRequest<S> for SignedActivityJson<T>
+where
+    T: DeserializeOwned + ActivityActor,
+    S: Send + Sync,
+    AppRegistry: FromRef<S>,
+{
+    type Rejection = SignatureRejection;
+
+    #[tracing::instrument(skip_all)]
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let registry = AppRegistry::from_ref(state);
+        let (parts, body) = req.into_parts();
+
+        let bytes = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+            .await
+            .map_err(ActivityJsonRejection::from)?;
+
+        let config = registry.config();
+        let target_uri = target_uri(&registry, &parts);
+
+        // ボディのあるリクエストはダイジェストも署名されている必要がある
+        let body = (parts.method == Method::POST).then_some(bytes.as_ref());
+        let verifier = HttpVerifier::from_request(
+            &parts.method,
+            &target_uri,
+            &parts.headers,
+            body,
+            SystemTime::now(),
+            config.clock_skew(),
+        )?
+        .ok_or(SignatureRejection::MissingSignature)?;
+
+        let signer = registry
+            .activity_service()
+            .verify_signature(&verifier)
+            .await
+            .map_err(SignatureRejection::Unauthorized)?;
+
+        let req = Request::from_parts(parts, Body::from(bytes));
+        let ActivityJson(activity) = ActivityJson::<T>::from_request(req, state).await?;
+
+        if activity.actor_url() != &signer {
+            return Err(SignatureRejection::ActorMismatch);
+        }
+
+        Ok(SignedActivityJson(activity))
+    }
+}
+
+/// 署名付きの`GET`であれば、その署名者
+///
+/// 署名がなければ`None`になり、署名があって検証に失敗した場合は拒否する
+///
+/// See https://docs.joinmastodon.org/spec/security/#http-sign
+pub struct SignedFetch(pub Option<ResourceUrl>);
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for SignedFetch
+where
+    S: Send + Sync,
+    AppRegistry: FromRef<S>,
+{
+    type Rejection = SignatureRejection;
+
+    #[tracing::instrument(skip_all)]
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let registry = AppRegistry::from_ref(state);
+
+        let config = registry.config();
+        let target_uri = target_uri(&registry, parts);
+
+        let Some(verifier) = HttpVerifier::from_request(
+            &parts.method,
+            &target_uri,
+            &parts.headers,
+            None,
+            SystemTime::now(),
+            config.clock_skew(),
+        )?
+        else {
+            return Ok(SignedFetch(None));
+        };
+
+        let signer = registry
+            .activity_service()
+            .verify_signature(&verifier)
+            .await
+            .map_err(SignatureRejection::Unauthorized)?;
+
+        Ok(SignedFetch(Some(signer)))
+    }
+}
+
+/// 管理用のトークンを`Authorization: Bearer`で渡したリクエスト
+///
+/// トークンが設定されていなければ管理用のAPIは使えない
+pub struct AdminAuth;
+
+pub enum AdminRejection {
+    Disabled,
+    Unauthorized,
+}
+
+impl IntoResponse for AdminRejection {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            AdminRejection::Disabled => {
+                (StatusCode::FORBIDDEN, "Admin token is not configured").into_response()
+            }
+            AdminRejection::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, "Invalid admin token").into_response()
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for AdminAuth
+where
+    S: Send + Sync,
+    AppRegistry: FromRef<S>,
+{
+    type Rejection = AdminRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let registry = AppRegistry::from_ref(state);
+        let config = registry.config();
+        let admin_token = config.admin_token().ok_or(AdminRejection::Disabled)?;
+
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or(AdminRejection::Unauthorized)?;
+        if !admin_token.matches(token.trim()) {
+            tracing::warn!("Invalid admin token");
+            return Err(AdminRejection::Unauthorized);
+        }
+
+        Ok(AdminAuth)
+    }
+}
+
+/// 送信者は公開しているURLに向けて署名しているので、それを復元する
+fn target_uri(registry: &AppRegistry, parts: &Parts) -> ResourceUrl {
+    let mut target_uri = registry.config().host_uri().clone();
+    target_uri.set_path(parts.uri.path());
+    if let Some(query) = parts.uri.query() {
+        target_uri.set_query(query);
+    }
+    target_uri
+}
@@ -1,3 +1,6 @@
+pub(crate) mod activity;
+pub(crate) mod follow_request;
 pub(crate) mod inbox;
+pub(crate) mod note;
 pub(crate) mod person;
 pub(crate) mod webfinger;
@@ -0,0 +1,95 @@
+use apub_activitypub::shared::activity_json::ActivityJson;
+use apub_kernel::{
+    activitypub::audience::Audience, prelude::*, sent_activity::model::SentActivityId,
+    user::model::parse_followers_uri,
+};
+use apub_registry::AppRegistryExt;
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{http::StatusCode, response::IntoResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityError {
+    #[error("Activity not found")]
+    NotFound,
+    #[error("Signature required")]
+    Unauthorized,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for ActivityError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            ActivityError::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            ActivityError::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, self.to_string()).into_response()
+            }
+            ActivityError::Internal(e) => {
+                tracing::error!(error = %e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "").into_response()
+            }
+        }
+    }
+}
+
+pub async fn activity_handler(
+    activity_id: &str,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, ActivityError> {
+    let activity_id = activity_id
+        .parse::<SentActivityId>()
+        .map_err(|_| ActivityError::NotFound)?;
+    let activity = registry
+        .sent_activity_repository()
+        .find(&activity_id)
+        .await
+        .map_err(|_| ActivityError::NotFound)?;
+
+    authorize_fetch(&activity.audience(), &activity.actor_url, signer, registry).await?;
+
+    Ok(ActivityJson(activity.activity))
+}
+
+/// 宛先に応じて`Object`を取得できるか確かめる
+///
+/// 公開されていなければ署名が必要で、署名者が作成者か宛先に含まれているか、
+/// 宛先に含まれる`followers`のフォロワーである必要がある
+///
+/// 存在を明かさないよう、権限がない場合は`NotFound`を返す
+pub(crate) async fn authorize_fetch(
+    audience: &Audience,
+    author_url: &ResourceUrl,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<(), ActivityError> {
+    if audience.is_public() {
+        return Ok(());
+    }
+    let signer = signer.ok_or(ActivityError::Unauthorized)?;
+    if signer == author_url || audience.contains(signer) {
+        return Ok(());
+    }
+
+    let config = registry.config();
+    for target in audience.iter() {
+        let Ok(url) = target.parse::<ResourceUrl>() else {
+            continue;
+        };
+        let Some(name) = parse_followers_uri(&config, &url) else {
+            continue;
+        };
+        let Ok(user) = registry.user_service().find_by_name(name).await else {
+            continue;
+        };
+        if registry
+            .follower_repository()
+            .find(&user.id, signer)
+            .await?
+        {
+            return Ok(());
+        }
+    }
+
+    Err(ActivityError::NotFound)
+}
@@ -0,0 +1,175 @@
+use apub_activitypub::model::{
+    activity::{Accept, FollowPerson, Reject},
+    person::Person,
+};
+use apub_kernel::{
+    activitypub::activity::generate_activity_uri, follow_request::model::FollowRequest, prelude::*,
+    user::model::User,
+};
+use apub_registry::AppRegistryExt;
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{http::StatusCode, response::IntoResponse, Json};
+use serde::Serialize;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FollowRequestError {
+    #[error("User not found")]
+    NotFound,
+    #[error("Follow request not found")]
+    RequestNotFound,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for FollowRequestError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            FollowRequestError::NotFound | FollowRequestError::RequestNotFound => {
+                (StatusCode::NOT_FOUND, self.to_string()).into_response()
+            }
+            FollowRequestError::Internal(e) => {
+                tracing::error!(error = %e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "").into_response()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FollowRequestResponse {
+    actor: ResourceUrl,
+    follow: ResourceUrl,
+}
+
+impl From<FollowRequest> for FollowRequestResponse {
+    fn from(value: FollowRequest) -> Self {
+        Self {
+            actor: value.actor_url,
+            follow: value.follow_url,
+        }
+    }
+}
+
+pub async fn list_follow_requests_handler(
+    username: &str,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    let user = find_user(username, registry).await?;
+
+    let requests = registry
+        .follow_request_repository()
+        .find_by_user(&user.id)
+        .await?
+        .into_iter()
+        .map(FollowRequestResponse::from)
+        .collect::<Vec<_>>();
+
+    Ok(Json(requests))
+}
+
+pub async fn accept_follow_request_handler(
+    username: &str,
+    actor_url: &ResourceUrl,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    let user = find_user(username, registry).await?;
+    let request = take_follow_request(&user, actor_url, registry).await?;
+
+    registry
+        .follower_repository()
+        .create(&user.id, &request.actor_url)
+        .await?;
+
+    let config = registry.config();
+    let accept = Accept::builder()
+        .actor(user.user_uri(&config))
+        .id(generate_activity_uri(&config).into())
+        .object(follow_activity(&user, &request, registry))
+        .context(Default::default())
+        .build();
+
+    send_to_follower(&user, &request, &accept, registry).await?;
+    tracing::info!(kind = "Accept", actor = %request.actor_url, object = user.name);
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn reject_follow_request_handler(
+    username: &str,
+    actor_url: &ResourceUrl,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    let user = find_user(username, registry).await?;
+    let request = take_follow_request(&user, actor_url, registry).await?;
+
+    let config = registry.config();
+    let reject = Reject::builder()
+        .actor(user.user_uri(&config))
+        .id(generate_activity_uri(&config).into())
+        .object(follow_activity(&user, &request, registry))
+        .context(Default::default())
+        .build();
+
+    send_to_follower(&user, &request, &reject, registry).await?;
+    tracing::info!(kind = "Reject", actor = %request.actor_url, object = user.name);
+
+    Ok(StatusCode::OK)
+}
+
+async fn find_user(
+    username: &str,
+    registry: &impl AppRegistryExt,
+) -> Result<User, FollowRequestError> {
+    registry
+        .user_service()
+        .find_by_name(username)
+        .await
+        .map_err(|_| FollowRequestError::NotFound)
+}
+
+/// 承認待ちの`Follow`を取り出して削除する
+async fn take_follow_request(
+    user: &User,
+    actor_url: &ResourceUrl,
+    registry: &impl AppRegistryExt,
+) -> Result<FollowRequest, FollowRequestError> {
+    let repo = registry.follow_request_repository();
+    let request = repo
+        .find(&user.id, actor_url)
+        .await
+        .map_err(|_| FollowRequestError::RequestNotFound)?;
+    repo.delete(&user.id, actor_url).await?;
+
+    Ok(request)
+}
+
+/// 保存していた`Follow`を`Accept`や`Reject`の`object`として組み立て直す
+fn follow_activity(
+    user: &User,
+    request: &FollowRequest,
+    registry: &impl AppRegistryExt,
+) -> FollowPerson<Person> {
+    FollowPerson::<Person>::builder()
+        .context(None)
+        .id(request.follow_url.clone().into())
+        .actor(request.actor_url.clone().into())
+        .object(user.user_uri(&registry.config()))
+        .build()
+}
+
+async fn send_to_follower<T: Serialize + Sync>(
+    user: &User,
+    request: &FollowRequest,
+    activity: &T,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<()> {
+    let follower = registry
+        .activity_service()
+        .get_actor_by_url(&request.actor_url)
+        .await?;
+
+    registry
+        .delivery_service()
+        .deliver(user, activity, &follower.inbox)
+        .await
+}
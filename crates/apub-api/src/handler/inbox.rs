@@ -1,14 +1,35 @@
-use apub_activitypub::model::{
-    activity::{Accept, Follow, UndoPersonFollow},
-    person::Person,
+use std::collections::HashSet;
+
+use apub_activitypub::{
+    core::actor::Actor as _,
+    model::{
+        activity::{
+            Accept, AcceptPersonFollow, AnnouncePersonNote, DeletePersonObject, Follow,
+            FollowPerson, LikePersonNote, RejectPersonFollow, UndoPersonAnnounce, UndoPersonFollow,
+            UndoPersonLike, UpdateAnyActor, UpdatePersonNote,
+        },
+        person::Person,
+    },
 };
 use apub_kernel::{
-    activitypub::activity::generate_activity_uri, follower::repository::FollowerRepository,
-    prelude::*, rsa_key::model::RsaVerifyingKey,
+    activitypub::{activity::generate_activity_uri, actor::ActorRepository, audience::Audience},
+    follow_request::model::FollowRequest,
+    follower::repository::FollowerRepository,
+    following::model::Following,
+    inbox::model::{InboxJob, NewInboxJob},
+    note::{
+        model::{parse_note_uri, NoteId},
+        repository::NoteRepository,
+    },
+    prelude::*,
+    user::model::{parse_followers_uri, parse_user_uri, User},
 };
 use apub_registry::AppRegistryExt;
+use apub_shared::model::resource_url::ResourceUrl;
 use axum::{http::StatusCode, response::IntoResponse};
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::extractor::ActivityActor;
 
 #[derive(Debug, thiserror::Error)]
 pub enum InboxError {
@@ -33,13 +54,110 @@ impl IntoResponse for InboxError {
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum InboxKinds {
-    Follow(Follow<Person, Person>),
-    UnFollow(UndoPersonFollow<Person>),
+    Follow(Box<Follow<Person, Person>>),
+    UnFollow(Box<UndoPersonFollow<Person>>),
+    Like(Box<LikePersonNote>),
+    UnLike(Box<UndoPersonLike>),
+    Announce(Box<AnnouncePersonNote>),
+    UnAnnounce(Box<UndoPersonAnnounce>),
+    Delete(Box<DeletePersonObject>),
+    UpdateNote(Box<UpdatePersonNote>),
+    UpdateActor(Box<UpdateAnyActor>),
+    Accept(Box<AcceptPersonFollow<Person>>),
+    Reject(Box<RejectPersonFollow<Person>>),
+}
+
+impl ActivityActor for InboxKinds {
+    fn actor_url(&self) -> &ResourceUrl {
+        match self {
+            InboxKinds::Follow(follow) => follow.actor.as_ref(),
+            InboxKinds::UnFollow(undo) => undo.actor.as_ref(),
+            InboxKinds::Like(like) => like.actor.as_ref(),
+            InboxKinds::UnLike(undo) => undo.actor.as_ref(),
+            InboxKinds::Announce(announce) => announce.actor.as_ref(),
+            InboxKinds::UnAnnounce(undo) => undo.actor.as_ref(),
+            InboxKinds::Delete(delete) => delete.actor.as_ref(),
+            InboxKinds::UpdateNote(update) => update.actor.as_ref(),
+            InboxKinds::UpdateActor(update) => update.actor.as_ref(),
+            InboxKinds::Accept(accept) => accept.actor.as_ref(),
+            InboxKinds::Reject(reject) => reject.actor.as_ref(),
+        }
+    }
+}
+
+impl InboxKinds {
+    /// 特定のローカルユーザに宛てたActivityの場合、そのユーザのURL
+    ///
+    /// `Follow`はフォローされる側、`Accept`や`Reject`は`Follow`を送った側が対象になる
+    fn target_user_url(&self) -> Option<&ResourceUrl> {
+        match self {
+            InboxKinds::Follow(follow) => Some(follow.object.as_ref()),
+            InboxKinds::UnFollow(undo) => Some(undo.object.object.as_ref()),
+            InboxKinds::Accept(accept) => Some(accept.object.actor.as_ref()),
+            InboxKinds::Reject(reject) => Some(reject.object.actor.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// 共有`inbox`に届いたActivity
+///
+/// 宛先のローカルユーザを探すため、`to`や`cc`も一緒に取り出す
+#[derive(Debug)]
+pub struct SharedInboxActivity {
+    pub kind: InboxKinds,
+    pub audience: Audience,
+}
+
+impl<'de> Deserialize<'de> for SharedInboxActivity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let audience = Audience::from_value(&value);
+        let kind = InboxKinds::deserialize(value).map_err(serde::de::Error::custom)?;
+
+        Ok(Self { kind, audience })
+    }
+}
+
+impl ActivityActor for SharedInboxActivity {
+    fn actor_url(&self) -> &ResourceUrl {
+        self.kind.actor_url()
+    }
+}
+
+/// 受け取ったままの形でキューに積むActivity
+///
+/// 受け付ける時点で`T`として読めることを確かめておく
+#[derive(Debug)]
+pub struct ReceivedActivity<T> {
+    pub activity: T,
+    pub raw: serde_json::Value,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for ReceivedActivity<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        let activity = T::deserialize(&raw).map_err(serde::de::Error::custom)?;
+
+        Ok(Self { activity, raw })
+    }
+}
+
+impl<T: ActivityActor> ActivityActor for ReceivedActivity<T> {
+    fn actor_url(&self) -> &ResourceUrl {
+        self.activity.actor_url()
+    }
 }
 
 pub async fn inbox_handler(
     username: &str,
-    kind: InboxKinds,
+    activity: ReceivedActivity<InboxKinds>,
     registry: &impl AppRegistryExt,
 ) -> Result<impl IntoResponse, InboxError> {
     let user = registry
@@ -48,14 +166,136 @@ pub async fn inbox_handler(
         .await
         .map_err(|_| InboxError::NotFound)?;
 
-    let signing_key = registry
-        .rsa_key_repository()
-        .find_private_key(&user.id)
-        .await?;
+    let job = NewInboxJob::builder()
+        .user_id(Some(user.id))
+        .activity(activity.raw)
+        .build();
+    enqueue(&job, registry).await
+}
+
+pub async fn shared_inbox_handler(
+    activity: ReceivedActivity<SharedInboxActivity>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, InboxError> {
+    let job = NewInboxJob::builder().activity(activity.raw).build();
+    enqueue(&job, registry).await
+}
+
+/// 処理はワーカーに任せ、積んだ時点で`202 Accepted`を返す
+async fn enqueue(
+    job: &NewInboxJob,
+    registry: &impl AppRegistryExt,
+) -> Result<StatusCode, InboxError> {
+    let queued = registry.inbox_repository().enqueue(job).await?;
+    if !queued {
+        tracing::info!(activity_id = job.activity_id(), "Ignore duplicate activity");
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// キューから取り出したActivityを処理する
+pub(crate) async fn process_inbox_job(
+    job: &InboxJob,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<()> {
+    let Some(user_id) = &job.user_id else {
+        let activity = SharedInboxActivity::deserialize(&job.activity)?;
+        return handle_shared_activity(activity, registry).await;
+    };
+
+    let user = registry.user_service().find_by_id(user_id).await?;
+    let kind = InboxKinds::deserialize(&job.activity)?;
+    handle_activity(&user, kind, registry).await
+}
+
+/// 共有`inbox`に届いたActivityを宛先のローカルユーザについて処理する
+async fn handle_shared_activity(
+    activity: SharedInboxActivity,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<()> {
+    let SharedInboxActivity { kind, audience } = activity;
+
+    let recipients = find_recipients(&kind, &audience, registry).await?;
+    // 各処理はActivityごとに1回だけ行えばよいので、最初の宛先として処理する
+    let Some(user) = recipients.first() else {
+        tracing::info!(actor = %kind.actor_url(), "Ignore activity without local recipients");
+        return Ok(());
+    };
+    tracing::info!(
+        actor = %kind.actor_url(),
+        recipients = ?recipients.iter().map(|v| v.name.as_str()).collect::<Vec<_>>()
+    );
+
+    handle_activity(user, kind, registry).await
+}
 
+/// 共有`inbox`に届いたActivityの宛先のローカルユーザ
+///
+/// 特定のユーザに宛てたActivityはそのユーザだけを返す。
+/// それ以外は`to`や`cc`に含まれるユーザと`followers`の持ち主に加え、
+/// リモートの宛先があれば送信者をフォローしているユーザを宛先とみなす
+async fn find_recipients(
+    kind: &InboxKinds,
+    audience: &Audience,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<Vec<User>> {
     let config = registry.config();
-    let user_key_id = user.user_key_uri::<RsaVerifyingKey>(&config);
+    let user_service = registry.user_service();
+
+    if let Some(url) = kind.target_user_url() {
+        let Some(name) = parse_user_uri(&config, url) else {
+            return Ok(vec![]);
+        };
+        return Ok(user_service
+            .find_by_name(name)
+            .await
+            .ok()
+            .into_iter()
+            .collect());
+    }
+
+    let mut recipients = Vec::<User>::new();
+    let mut remote = false;
+    for target in audience.iter() {
+        let Ok(url) = target.parse::<ResourceUrl>() else {
+            // `as:Public`のような省略形
+            remote = true;
+            continue;
+        };
+        let name = parse_user_uri(&config, &url).or_else(|| parse_followers_uri(&config, &url));
+        match name {
+            Some(name) => {
+                if let Ok(user) = user_service.find_by_name(name).await {
+                    recipients.push(user);
+                }
+            }
+            None => remote = true,
+        }
+    }
 
+    if remote {
+        let following = registry
+            .following_repository()
+            .find_by_actor(kind.actor_url())
+            .await?;
+        for f in following {
+            recipients.push(user_service.find_by_id(&f.user_id).await?);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    recipients.retain(|user| seen.insert(*user.id.as_ref()));
+
+    Ok(recipients)
+}
+
+/// ローカルユーザ宛てのActivityを処理する
+async fn handle_activity(
+    user: &User,
+    kind: InboxKinds,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<()> {
     let config = registry.config();
     let activity_service = registry.activity_service();
     match kind {
@@ -64,6 +304,23 @@ pub async fn inbox_handler(
                 .get_actor_by_url(follow.actor.as_ref())
                 .await?;
 
+            let profile = registry.user_service().find_profile(&user.id).await?;
+            if profile.manually_approves_followers {
+                // 承認されるまで`Accept`しない
+                let request = FollowRequest::builder()
+                    .user_id(user.id.clone())
+                    .actor_url(follow_person.actor_url.clone())
+                    .follow_url(follow.id().as_ref().clone())
+                    .build();
+                registry
+                    .follow_request_repository()
+                    .create(&request)
+                    .await?;
+
+                tracing::info!(kind = "Follow", actor = %follow_person.actor_url, object = user.name, "Pending approval");
+                return Ok(());
+            }
+
             let follower_repo = registry.follower_repository();
 
             follower_repo
@@ -73,27 +330,201 @@ pub async fn inbox_handler(
             let accept = Accept::builder()
                 .actor(user.user_uri(&config))
                 .id(generate_activity_uri(&config).into())
-                .object(follow)
+                .object(*follow)
                 .context(Default::default())
                 .build();
 
-            activity_service
-                .post_activity(&accept, &follow_person.inbox, &signing_key, &user_key_id)
+            registry
+                .delivery_service()
+                .deliver(user, &accept, &follow_person.inbox)
                 .await?;
             tracing::info!(kind = "Accept", actor = %follow_person.actor_url, object = user.name);
         }
         InboxKinds::UnFollow(undo) => {
-            let actor = undo.object.actor;
+            // 署名者と一致することを確認済みの`actor`が自分でしたフォローだけを取り消す
+            if undo.object.actor.as_ref() != undo.actor.as_ref() {
+                tracing::warn!(kind = "Undo", actor = %undo.actor, object = %undo.object.actor, "Ignore other actor's follow");
+                return Ok(());
+            }
+            let actor = undo.actor;
             let follow_person = activity_service.get_actor_by_url(actor.as_ref()).await?;
-            let follower_repo = registry.follower_repository();
 
-            follower_repo
+            // 承認待ちならそれを取り消す
+            let pending = registry
+                .follow_request_repository()
                 .delete(&user.id, &follow_person.actor_url)
                 .await?;
+            if !pending {
+                let follower_repo = registry.follower_repository();
+
+                follower_repo
+                    .delete(&user.id, &follow_person.actor_url)
+                    .await?;
+            }
 
             tracing::info!(kind = "Undo", actor = %follow_person.actor_url, object = user.name);
         }
+        InboxKinds::Like(like) => {
+            let Some(note_id) = find_local_note(like.object.as_ref(), registry).await? else {
+                tracing::info!(kind = "Like", object = %like.object, "Ignore unknown note");
+                return Ok(());
+            };
+            let like_person = activity_service
+                .get_actor_by_url(like.actor.as_ref())
+                .await?;
+
+            registry
+                .like_repository()
+                .create(&note_id, &like_person.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Like", actor = %like_person.actor_url, object = %like.object);
+        }
+        InboxKinds::UnLike(undo) => {
+            // 署名者と一致することを確認済みの`actor`の`Like`だけを取り消す
+            let actor = undo.actor;
+            let Some(note_id) = find_local_note(undo.object.object.as_ref(), registry).await?
+            else {
+                tracing::info!(kind = "Undo", object = %undo.object.object, "Ignore unknown note");
+                return Ok(());
+            };
+            let like_person = activity_service.get_actor_by_url(actor.as_ref()).await?;
+
+            registry
+                .like_repository()
+                .delete(&note_id, &like_person.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Undo", actor = %like_person.actor_url, object = %undo.object.object);
+        }
+        InboxKinds::Announce(announce) => {
+            let Some(note_id) = find_local_note(announce.object.as_ref(), registry).await? else {
+                tracing::info!(kind = "Announce", object = %announce.object, "Ignore unknown note");
+                return Ok(());
+            };
+            let announce_person = activity_service
+                .get_actor_by_url(announce.actor.as_ref())
+                .await?;
+
+            registry
+                .share_repository()
+                .create(&note_id, &announce_person.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Announce", actor = %announce_person.actor_url, object = %announce.object);
+        }
+        InboxKinds::UnAnnounce(undo) => {
+            // 署名者と一致することを確認済みの`actor`の`Announce`だけを取り消す
+            let actor = undo.actor;
+            let Some(note_id) = find_local_note(undo.object.object.as_ref(), registry).await?
+            else {
+                tracing::info!(kind = "Undo", object = %undo.object.object, "Ignore unknown note");
+                return Ok(());
+            };
+            let announce_person = activity_service.get_actor_by_url(actor.as_ref()).await?;
+
+            registry
+                .share_repository()
+                .delete(&note_id, &announce_person.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Undo", actor = %announce_person.actor_url, object = %undo.object.object);
+        }
+        InboxKinds::Delete(delete) => {
+            let actor_url = delete.actor.as_ref();
+            let object_url = delete.object_url();
+            if object_url == actor_url {
+                // `Actor`自身の削除。フォローと公開鍵も消える
+                if let Err(e) = registry.actor_repository().delete(actor_url).await {
+                    tracing::info!(kind = "Delete", actor = %actor_url, error = %e, "Ignore unknown actor");
+                }
+            } else if object_url.host() != actor_url.host() {
+                tracing::warn!(kind = "Delete", actor = %actor_url, object = %object_url, "Ignore other host's object");
+            } else {
+                // リモートの`Note`は`Create`を受け取っても保存していないので、削除は未対応。
+                // 保存するようになったらここで消す
+                tracing::info!(kind = "Delete", actor = %actor_url, object = %object_url, "Remote notes are not stored, nothing to delete");
+            }
+        }
+        InboxKinds::UpdateNote(update) => {
+            // リモートの`Note`は`Create`を受け取っても保存していないので、更新は未対応。
+            // 保存するようになったらここで内容を取り直す
+            tracing::info!(kind = "Update", actor = %update.actor, "Remote notes are not stored, nothing to update");
+        }
+        InboxKinds::UpdateActor(update) => {
+            // 署名者自身のプロフィールだけを受け入れる
+            if update.object.id().as_ref() != update.actor.as_ref() {
+                tracing::warn!(kind = "Update", actor = %update.actor, object = %update.object.id(), "Ignore other actor's profile");
+                return Ok(());
+            }
+            let actor = activity_service.update_actor(&update.object).await?;
+
+            tracing::info!(kind = "Update", actor = %actor.actor_url);
+        }
+        InboxKinds::Accept(accept) => {
+            let Some(following) =
+                find_following(user, accept.actor.as_ref(), &accept.object, registry).await?
+            else {
+                tracing::info!(kind = "Accept", actor = %accept.actor, object = %accept.object.id(), "Ignore unknown follow");
+                return Ok(());
+            };
+
+            registry
+                .following_repository()
+                .accept(&user.id, &following.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Accept", actor = %following.actor_url, object = user.name);
+        }
+        InboxKinds::Reject(reject) => {
+            let Some(following) =
+                find_following(user, reject.actor.as_ref(), &reject.object, registry).await?
+            else {
+                tracing::info!(kind = "Reject", actor = %reject.actor, object = %reject.object.id(), "Ignore unknown follow");
+                return Ok(());
+            };
+
+            // 承認済みでも`Reject`されたらフォローを外す
+            registry
+                .following_repository()
+                .delete(&user.id, &following.actor_url)
+                .await?;
+
+            tracing::info!(kind = "Reject", actor = %following.actor_url, object = user.name);
+        }
     };
 
-    Ok(StatusCode::ACCEPTED)
+    Ok(())
+}
+
+/// `follow`が`user`から署名者へ送った`Follow`である場合にそれを返す
+async fn find_following(
+    user: &User,
+    actor_url: &ResourceUrl,
+    follow: &FollowPerson<Person>,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<Option<Following>> {
+    if follow.actor != user.user_uri(&registry.config()) || follow.object.as_ref() != actor_url {
+        return Ok(None);
+    }
+    let following = registry
+        .following_repository()
+        .find(&user.id, actor_url)
+        .await
+        .ok();
+
+    Ok(following.filter(|v| &v.follow_url == follow.id().as_ref()))
+}
+
+/// `url`がこのサーバに保存された`Note`を指す場合に`NoteId`を返す
+async fn find_local_note(
+    url: &ResourceUrl,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<Option<NoteId>> {
+    let Some(note_id) = parse_note_uri(&registry.config(), url) else {
+        return Ok(None);
+    };
+    let note = registry.note_repository().find(&note_id).await.ok();
+
+    Ok(note.map(|note| note.id))
 }
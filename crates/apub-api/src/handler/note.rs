@@ -0,0 +1,190 @@
+use apub_activitypub::{
+    model::{
+        collection::{OrderedCollection, OrderedCollectionBase},
+        context::Context,
+        tombstone::Tombstone,
+    },
+    shared::activity_json::ActivityJson,
+};
+use apub_kernel::{
+    activitypub::audience::Audience,
+    note::{model::Note, repository::NoteRepository},
+    prelude::*,
+    user::model::User,
+};
+use apub_registry::AppRegistryExt;
+use apub_shared::model::{id::Id, resource_url::ResourceUrl};
+use axum::{http::StatusCode, response::IntoResponse};
+
+use super::activity::{authorize_fetch, ActivityError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum NoteError {
+    #[error("Note not found")]
+    NotFound,
+    #[error("Note was deleted")]
+    Gone(Box<Tombstone>),
+    #[error("Signature required")]
+    Unauthorized,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for NoteError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            NoteError::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            NoteError::Gone(tombstone) => {
+                (StatusCode::GONE, ActivityJson(*tombstone)).into_response()
+            }
+            NoteError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()).into_response(),
+            NoteError::Internal(e) => {
+                tracing::error!(error = %e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "").into_response()
+            }
+        }
+    }
+}
+
+impl From<ActivityError> for NoteError {
+    fn from(value: ActivityError) -> Self {
+        match value {
+            ActivityError::NotFound => NoteError::NotFound,
+            ActivityError::Unauthorized => NoteError::Unauthorized,
+            ActivityError::Internal(e) => NoteError::Internal(e),
+        }
+    }
+}
+
+pub async fn note_handler(
+    note_id: &str,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, NoteError> {
+    let note = find_note(note_id, registry).await?;
+    let author = registry.user_service().find_by_id(&note.user_id).await?;
+
+    let config = registry.config();
+    let note = note.to_note(&config, &author);
+
+    let audience = Audience::from_value(&serde_json::to_value(&note).map_err(anyhow::Error::from)?);
+    authorize_fetch(&audience, &author.user_uri(&config), signer, registry).await?;
+
+    Ok(ActivityJson(note))
+}
+
+/// `Note`を作成した`Create`を返す
+pub async fn create_activity_handler(
+    note_id: &str,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, NoteError> {
+    let note = find_note(note_id, registry).await?;
+    let author = registry.user_service().find_by_id(&note.user_id).await?;
+
+    let config = registry.config();
+    // `Create`の宛先は`Note`と同じ
+    authorize_note_fetch(&note, &author, signer, registry).await?;
+
+    Ok(ActivityJson(note.to_create(&config, &author)))
+}
+
+/// `Note`を`Like`した`Actor`のコレクションを返す
+///
+/// `Note`を見られる`Actor`にだけ返す
+pub async fn likes_handler(
+    note_id: &str,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, NoteError> {
+    let note = find_note(note_id, registry).await?;
+    let author = registry.user_service().find_by_id(&note.user_id).await?;
+    authorize_note_fetch(&note, &author, signer, registry).await?;
+
+    let config = registry.config();
+
+    let likes = registry.like_repository().find_by_note(&note.id).await?;
+
+    let actor_url = likes.into_iter().map(|v| v.actor_url).collect::<Vec<_>>();
+
+    let like_collection = OrderedCollectionBase::builder()
+        .total_items(actor_url.len())
+        .ordered_items(actor_url)
+        .build();
+    let like_collection = OrderedCollection::builder()
+        .context(Context::activity_context_url().clone())
+        .id(note.likes_uri(&config))
+        .base(like_collection)
+        .build();
+
+    Ok(ActivityJson(like_collection))
+}
+
+/// `Note`を`Announce`した`Actor`のコレクションを返す
+///
+/// `Note`を見られる`Actor`にだけ返す
+pub async fn shares_handler(
+    note_id: &str,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, NoteError> {
+    let note = find_note(note_id, registry).await?;
+    let author = registry.user_service().find_by_id(&note.user_id).await?;
+    authorize_note_fetch(&note, &author, signer, registry).await?;
+
+    let config = registry.config();
+
+    let shares = registry.share_repository().find_by_note(&note.id).await?;
+
+    let actor_url = shares.into_iter().map(|v| v.actor_url).collect::<Vec<_>>();
+
+    let share_collection = OrderedCollectionBase::builder()
+        .total_items(actor_url.len())
+        .ordered_items(actor_url)
+        .build();
+    let share_collection = OrderedCollection::builder()
+        .context(Context::activity_context_url().clone())
+        .id(note.shares_uri(&config))
+        .base(share_collection)
+        .build();
+
+    Ok(ActivityJson(share_collection))
+}
+
+/// `Note`の宛先に含まれる`Actor`だけが取得できるか確かめる
+async fn authorize_note_fetch(
+    note: &Note,
+    author: &User,
+    signer: Option<&ResourceUrl>,
+    registry: &impl AppRegistryExt,
+) -> Result<(), NoteError> {
+    let config = registry.config();
+    let object =
+        serde_json::to_value(note.to_note(&config, author)).map_err(anyhow::Error::from)?;
+    authorize_fetch(
+        &Audience::from_value(&object),
+        &author.user_uri(&config),
+        signer,
+        registry,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// 削除済みの場合は`Tombstone`を返す
+async fn find_note(note_id: &str, registry: &impl AppRegistryExt) -> Result<Note, NoteError> {
+    let note_id = note_id.parse::<Id<_>>().map_err(|_| NoteError::NotFound)?;
+    let note_repo = registry.note_repository();
+    if let Ok(note) = note_repo.find(&note_id).await {
+        return Ok(note);
+    }
+
+    let deleted = note_repo
+        .find_deleted(&note_id)
+        .await
+        .map_err(|_| NoteError::NotFound)?;
+    let tombstone = deleted.to_tombstone(&registry.config());
+
+    Err(NoteError::Gone(Box::new(tombstone)))
+}
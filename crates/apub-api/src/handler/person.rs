@@ -1,16 +1,26 @@
 use apub_activitypub::{
     core::actor::Actor as _,
     model::{
-        collection::{OrderedCollection, OrderedCollectionBase},
+        activity::CreatePersonNote,
+        collection::{
+            CollectionPageBase, OrderedCollection, OrderedCollectionBase, OrderedCollectionPage,
+        },
         context::Context,
-        key::PublicKeyPem,
-        person::{Person, SecurityPerson},
+        key::{Multikey, PublicKeyPem},
+        person::SecurityPerson,
     },
-    shared::activity_json::ActivityJson,
+    shared::{activity_json::ActivityJson, SingleOrMany},
+};
+use apub_kernel::{
+    note::repository::NoteRepository,
+    pagination::{Cursor, Page, PAGE_SIZE},
+    prelude::*,
+    user::model::User,
 };
-use apub_kernel::{prelude::*, rsa_key::model::RsaVerifyingKey};
 use apub_registry::AppRegistryExt;
+use apub_shared::model::resource_url::ResourceUrl;
 use axum::{http::StatusCode, response::IntoResponse};
+use serde::Deserialize;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PersonError {
@@ -38,68 +48,235 @@ pub async fn person_handler(
 ) -> Result<impl IntoResponse, PersonError> {
     let user = registry.user_service().find_by_name(username).await?;
 
-    let public_key = registry
+    let security = security_person(&user, registry).await?;
+
+    tracing::info!(message = "Return person", name = username);
+
+    Ok(ActivityJson(security))
+}
+
+/// 公開鍵とプロフィールを含めた`Person`を作る
+pub(crate) async fn security_person(
+    user: &User,
+    registry: &impl AppRegistryExt,
+) -> anyhow::Result<SecurityPerson> {
+    // 鍵を替えた後も、猶予期間の間は古い鍵のURLから取得した相手が検証できるように並べる
+    let public_keys = registry
         .rsa_key_repository()
-        .find_public_key(&user.id)
+        .find_public_keys(&user.id)
         .await?;
+    if public_keys.is_empty() {
+        return Err(anyhow::anyhow!("public key is not found"));
+    }
+    let profile = registry.user_service().find_profile(&user.id).await?;
 
     let config = registry.config();
-    let user_key_id = user.user_key_uri::<RsaVerifyingKey>(&config);
 
-    let person = Person::builder()
-        .id(user.user_uri(&config))
-        .preferred_username(user.name.clone())
-        .inbox(user.inbox_uri(&config))
-        .context(Context::activity_context_url().clone().into())
-        .followers(user.followers_uri(&config))
-        .kind(Default::default())
-        .build();
+    let person = user.to_person_with_profile(&config, &profile);
 
     let person_id = person.id().clone();
 
-    let public_key_pem = PublicKeyPem::builder()
-        .public_key_pem(public_key.to_pkcs8()?)
-        .id(user_key_id)
-        .owner(person_id)
-        .build();
+    let public_key_pems = public_keys
+        .into_iter()
+        .map(|key| {
+            Ok(PublicKeyPem::builder()
+                .public_key_pem(key.public_key.to_pkcs8()?)
+                .id(key.key_url)
+                .owner(person_id.clone())
+                .build())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // Ed25519の鍵がないユーザは`publicKey`だけを公開する
+    let assertion_method = registry
+        .rsa_key_repository()
+        .find_ed25519_public_keys(&user.id)
+        .await?
+        .into_iter()
+        .map(|key| {
+            Multikey::builder()
+                .id(key.key_url)
+                .controller(person_id.clone())
+                .public_key_multibase(key.public_key.to_multibase())
+                .build()
+        })
+        .collect();
 
     let security = SecurityPerson::builder()
         .inner(person)
-        .public_key(public_key_pem)
+        .public_key(SingleOrMany::from_vec(public_key_pems))
+        .assertion_method(assertion_method)
         .build();
 
-    tracing::info!(message = "Return person", name = username);
-
-    Ok(ActivityJson(security))
+    Ok(security)
 }
 
 pub async fn followers_handler(
     username: &str,
+    query: &CollectionQuery,
     registry: &impl AppRegistryExt,
 ) -> Result<impl IntoResponse, PersonError> {
     let user = registry.user_service().find_by_name(username).await?;
 
     let config = registry.config();
+    let followers_url = user.followers_uri(&config);
+
+    let follower_repo = registry.follower_repository();
+    let total = follower_repo.count(&user.id).await?;
+    let Some(cursor) = query.cursor() else {
+        return Ok(
+            ActivityJson(ordered_collection::<ResourceUrl>(followers_url, total)).into_response(),
+        );
+    };
+
+    let page = follower_repo.find_page(&user.id, cursor, PAGE_SIZE).await?;
+    let page = Page {
+        items: page.items.into_iter().map(|v| v.actor_url).collect(),
+        next: page.next,
+        prev: page.prev,
+    };
+
+    let page = ordered_collection_page(followers_url, total, cursor, page);
+    Ok(ActivityJson(page).into_response())
+}
+
+pub async fn following_handler(
+    username: &str,
+    query: &CollectionQuery,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, PersonError> {
+    let user = registry.user_service().find_by_name(username).await?;
+
+    let config = registry.config();
+    let following_url = user.following_uri(&config);
+
+    let following_repo = registry.following_repository();
+    let total = following_repo.count(&user.id).await?;
+    let Some(cursor) = query.cursor() else {
+        return Ok(
+            ActivityJson(ordered_collection::<ResourceUrl>(following_url, total)).into_response(),
+        );
+    };
 
-    let followers = registry
-        .follower_repository()
-        .find_followee(&user.id)
+    let page = following_repo
+        .find_page(&user.id, cursor, PAGE_SIZE)
         .await?;
+    let page = Page {
+        items: page.items.into_iter().map(|v| v.actor_url).collect(),
+        next: page.next,
+        prev: page.prev,
+    };
 
-    let follower_url = followers
-        .into_iter()
-        .map(|v| v.actor_url)
-        .collect::<Vec<_>>();
+    let page = ordered_collection_page(following_url, total, cursor, page);
+    Ok(ActivityJson(page).into_response())
+}
+
+pub async fn outbox_handler(
+    username: &str,
+    query: &CollectionQuery,
+    registry: &impl AppRegistryExt,
+) -> Result<impl IntoResponse, PersonError> {
+    let user = registry.user_service().find_by_name(username).await?;
+
+    let config = registry.config();
+    let outbox_url = user.outbox_uri(&config);
+
+    let note_repo = registry.note_repository();
+    let total = note_repo.count_user_notes(&user.id).await?;
+    let Some(cursor) = query.cursor() else {
+        let collection = ordered_collection::<CreatePersonNote>(outbox_url, total);
+        return Ok(ActivityJson(collection).into_response());
+    };
+
+    let page = note_repo
+        .list_user_notes(&user.id, cursor, PAGE_SIZE)
+        .await?;
+    let page = Page {
+        items: page
+            .items
+            .iter()
+            .map(|note| note.to_create(&config, &user))
+            .collect(),
+        next: page.next,
+        prev: page.prev,
+    };
+
+    let page = ordered_collection_page(outbox_url, total, cursor, page);
+    Ok(ActivityJson(page).into_response())
+}
+
+/// `page`か`max_id`/`min_id`があれば`OrderedCollectionPage`を返す
+#[derive(Debug, Default, Deserialize)]
+pub struct CollectionQuery {
+    #[serde(default)]
+    page: bool,
+    max_id: Option<i64>,
+    min_id: Option<i64>,
+}
+
+impl CollectionQuery {
+    fn cursor(&self) -> Option<Cursor> {
+        if !self.page && self.max_id.is_none() && self.min_id.is_none() {
+            return None;
+        }
+
+        Some(Cursor::new(self.max_id, self.min_id))
+    }
+}
 
-    let follower_collection = OrderedCollectionBase::builder()
-        .total_items(follower_url.len())
-        .ordered_items(follower_url)
+/// 件数と最初のページへのリンクだけを持つ`OrderedCollection`
+fn ordered_collection<T>(collection_url: ResourceUrl, total: i64) -> OrderedCollection<T> {
+    let first = page_url(&collection_url, Cursor::Latest);
+    let base = OrderedCollectionBase::builder()
+        .total_items(usize::try_from(total).unwrap_or_default())
+        .first(first)
         .build();
-    let follower_collection = OrderedCollection::builder()
+
+    OrderedCollection::builder()
         .context(Context::activity_context_url().clone())
-        .id(user.followers_uri(&config))
-        .base(follower_collection)
+        .id(collection_url)
+        .base(base)
+        .build()
+}
+
+fn ordered_collection_page<T>(
+    collection_url: ResourceUrl,
+    total: i64,
+    cursor: Cursor,
+    page: Page<T>,
+) -> OrderedCollectionPage<T> {
+    let base = OrderedCollectionBase::builder()
+        .total_items(usize::try_from(total).unwrap_or_default())
+        .ordered_items(page.items)
+        .build();
+
+    let links = CollectionPageBase::builder()
+        .part_of(collection_url.clone())
+        .next_opt(
+            page.next
+                .map(|id| page_url(&collection_url, Cursor::Before(id))),
+        )
+        .prev_opt(
+            page.prev
+                .map(|id| page_url(&collection_url, Cursor::After(id))),
+        )
         .build();
 
-    Ok(ActivityJson(follower_collection))
+    OrderedCollectionPage::builder()
+        .context(Context::activity_context_url().clone().into())
+        .id(page_url(&collection_url, cursor).into())
+        .base(base)
+        .page(links)
+        .build()
+}
+
+/// `cursor`の位置のページのURL
+fn page_url(collection_url: &ResourceUrl, cursor: Cursor) -> ResourceUrl {
+    let query = match cursor {
+        Cursor::Latest => "page=true".to_string(),
+        Cursor::Before(id) => format!("max_id={id}"),
+        Cursor::After(id) => format!("min_id={id}"),
+    };
+
+    collection_url.clone().set_query(&query).to_owned()
 }
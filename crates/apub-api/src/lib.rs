@@ -1,4 +1,6 @@
 pub mod app_state;
+pub mod extractor;
 
 pub(crate) mod handler;
 pub mod route;
+pub mod worker;
@@ -1,4 +1,14 @@
+pub mod activity;
+pub mod delete_note;
+pub mod follow;
+pub mod follow_request;
+pub mod note;
 pub mod person;
+pub mod rotate_key;
+pub mod send_announce;
 pub mod send_note;
+pub mod shared_inbox;
+pub mod update_note;
+pub mod update_profile;
 pub mod user_inbox;
 pub mod webfinger;
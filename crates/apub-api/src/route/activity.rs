@@ -0,0 +1,21 @@
+use crate::{
+    extractor::SignedFetch,
+    handler::activity::{activity_handler, ActivityError},
+};
+use apub_activitypub::shared::activity_json::ACTIVITY_CONTENT_TYPE;
+use apub_registry::AppRegistry;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+
+#[tracing::instrument(skip_all)]
+pub async fn activity(
+    Path(activity_id): Path<String>,
+    SignedFetch(signer): SignedFetch,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, ActivityError> {
+    let res = activity_handler(&activity_id, signer.as_ref(), &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
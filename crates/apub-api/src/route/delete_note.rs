@@ -0,0 +1,94 @@
+use apub_activitypub::{
+    model::{activity::DeletePersonObject, context::Context, note::Note},
+    shared::UrlOrObj,
+};
+use apub_kernel::activitypub::activity::generate_activity_uri;
+use apub_kernel::note::{model::NoteId, repository::NoteRepository};
+use apub_kernel::prelude::*;
+use apub_registry::{AppRegistry, AppRegistryExt};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::extractor::AdminAuth;
+
+#[derive(Deserialize)]
+pub struct DeleteNoteQuery {
+    note_id: String,
+    user: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteNoteError {
+    #[error("Note not found")]
+    NotFound,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for DeleteNoteError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+async fn delete_note_handler(
+    query: &DeleteNoteQuery,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, DeleteNoteError> {
+    let user_repo = registry.user_service();
+    let user = user_repo.find_by_name(&query.user).await?;
+
+    let note_id = query
+        .note_id
+        .parse::<NoteId>()
+        .map_err(|_| DeleteNoteError::NotFound)?;
+    let note_repo = registry.note_repository();
+    let note = note_repo
+        .find(&note_id)
+        .await
+        .map_err(|_| DeleteNoteError::NotFound)?;
+    // 他のユーザの`Note`は消せない
+    if note.user_id != user.id {
+        return Err(DeleteNoteError::NotFound);
+    }
+
+    note_repo.delete(&note.id).await?;
+    let deleted = note_repo.find_deleted(&note.id).await?;
+
+    let config = registry.config();
+    let delete = DeletePersonObject::builder()
+        .context(Context::activity_context_url().clone().into())
+        .id(generate_activity_uri(&config).into())
+        .actor(user.user_uri(&config))
+        .object(UrlOrObj::Obj(deleted.to_tombstone(&config)))
+        .to(Note::public_address().clone().into())
+        .build();
+
+    tracing::info!(delete=?delete);
+
+    registry
+        .delivery_service()
+        .deliver_to_followers(&user, &delete)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn delete_note(
+    _: AdminAuth,
+    Query(query): Query<DeleteNoteQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, DeleteNoteError> {
+    delete_note_handler(&query, registry).await
+}
@@ -0,0 +1,102 @@
+use apub_kernel::following::model::FollowTarget;
+use apub_kernel::prelude::*;
+use apub_registry::{AppRegistry, AppRegistryExt};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::extractor::AdminAuth;
+
+#[derive(Deserialize)]
+pub struct FollowQuery {
+    /// フォローする`Actor`のURLか`acct`
+    target: String,
+    user: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FollowError {
+    #[error("User not found")]
+    NotFound,
+    #[error("Invalid target")]
+    InvalidTarget,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for FollowError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            Self::InvalidTarget => (StatusCode::BAD_REQUEST, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+async fn follow_handler(
+    query: &FollowQuery,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, FollowError> {
+    let user = registry
+        .user_service()
+        .find_by_name(&query.user)
+        .await
+        .map_err(|_| FollowError::NotFound)?;
+    let target = query
+        .target
+        .parse::<FollowTarget>()
+        .map_err(|_| FollowError::InvalidTarget)?;
+
+    let following = registry.following_service().follow(&user, &target).await?;
+    tracing::info!(kind = "Follow", actor = user.name, object = %following.actor_url);
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn unfollow_handler(
+    query: &FollowQuery,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, FollowError> {
+    let user = registry
+        .user_service()
+        .find_by_name(&query.user)
+        .await
+        .map_err(|_| FollowError::NotFound)?;
+    let target = query
+        .target
+        .parse::<FollowTarget>()
+        .map_err(|_| FollowError::InvalidTarget)?;
+
+    let following = registry
+        .following_service()
+        .unfollow(&user, &target)
+        .await?;
+    tracing::info!(kind = "Undo", actor = user.name, object = %following.actor_url);
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn follow(
+    _: AdminAuth,
+    Query(query): Query<FollowQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, FollowError> {
+    follow_handler(&query, registry).await
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn unfollow(
+    _: AdminAuth,
+    Query(query): Query<FollowQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, FollowError> {
+    unfollow_handler(&query, registry).await
+}
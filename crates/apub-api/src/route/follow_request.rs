@@ -0,0 +1,49 @@
+use crate::{
+    extractor::AdminAuth,
+    handler::follow_request::{
+        accept_follow_request_handler, list_follow_requests_handler, reject_follow_request_handler,
+        FollowRequestError,
+    },
+};
+use apub_registry::AppRegistry;
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct FollowRequestQuery {
+    /// `Follow`を送ってきた`Actor`のURL
+    actor: ResourceUrl,
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn follow_requests(
+    _: AdminAuth,
+    Path(username): Path<String>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    list_follow_requests_handler(&username, &registry).await
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn accept_follow_request(
+    _: AdminAuth,
+    Path(username): Path<String>,
+    Query(query): Query<FollowRequestQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    accept_follow_request_handler(&username, &query.actor, &registry).await
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn reject_follow_request(
+    _: AdminAuth,
+    Path(username): Path<String>,
+    Query(query): Query<FollowRequestQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, FollowRequestError> {
+    reject_follow_request_handler(&username, &query.actor, &registry).await
+}
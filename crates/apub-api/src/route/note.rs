@@ -0,0 +1,56 @@
+use crate::{
+    extractor::SignedFetch,
+    handler::note::{
+        create_activity_handler, likes_handler, note_handler, shares_handler, NoteError,
+    },
+};
+use apub_activitypub::shared::activity_json::ACTIVITY_CONTENT_TYPE;
+use apub_registry::AppRegistry;
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+
+#[tracing::instrument(skip_all)]
+pub async fn note(
+    Path(note_id): Path<String>,
+    SignedFetch(signer): SignedFetch,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, NoteError> {
+    let res = note_handler(&note_id, signer.as_ref(), &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn create_activity(
+    Path(note_id): Path<String>,
+    SignedFetch(signer): SignedFetch,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, NoteError> {
+    let res = create_activity_handler(&note_id, signer.as_ref(), &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn likes(
+    Path(note_id): Path<String>,
+    SignedFetch(signer): SignedFetch,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, NoteError> {
+    let res = likes_handler(&note_id, signer.as_ref(), &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn shares(
+    Path(note_id): Path<String>,
+    SignedFetch(signer): SignedFetch,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, NoteError> {
+    let res = shares_handler(&note_id, signer.as_ref(), &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
@@ -1,8 +1,11 @@
-use crate::handler::person::{followers_handler, person_handler, PersonError};
+use crate::handler::person::{
+    followers_handler, following_handler, outbox_handler, person_handler, CollectionQuery,
+    PersonError,
+};
 use apub_activitypub::shared::activity_json::ACTIVITY_CONTENT_TYPE;
 use apub_registry::AppRegistry;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::IntoResponse,
 };
 
@@ -19,9 +22,32 @@ pub async fn person(
 #[tracing::instrument(skip_all)]
 pub async fn followers(
     Path(username): Path<String>,
+    Query(query): Query<CollectionQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, PersonError> {
+    let res = followers_handler(&username, &query, &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn following(
+    Path(username): Path<String>,
+    Query(query): Query<CollectionQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, PersonError> {
+    let res = following_handler(&username, &query, &registry).await?;
+
+    Ok(([ACTIVITY_CONTENT_TYPE], res))
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn outbox(
+    Path(username): Path<String>,
+    Query(query): Query<CollectionQuery>,
     State(registry): State<AppRegistry>,
 ) -> Result<impl IntoResponse, PersonError> {
-    let res = followers_handler(&username, &registry).await?;
+    let res = outbox_handler(&username, &query, &registry).await?;
 
     Ok(([ACTIVITY_CONTENT_TYPE], res))
 }
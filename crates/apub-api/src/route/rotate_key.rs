@@ -0,0 +1,72 @@
+use apub_activitypub::model::{activity::UpdatePerson, context::Context, note::Note};
+use apub_kernel::activitypub::activity::generate_activity_uri;
+use apub_kernel::prelude::*;
+use apub_registry::{AppRegistry, AppRegistryExt};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+use crate::{extractor::AdminAuth, handler::person::security_person};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RotateKeyError {
+    #[error("User not found")]
+    NotFound,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for RotateKeyError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+/// RSAとEd25519の鍵を置き換えて、新しい公開鍵をフォロワーに知らせる
+async fn rotate_key_handler(
+    username: &str,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, RotateKeyError> {
+    let user_service = registry.user_service();
+    let user = user_service
+        .find_by_name(username)
+        .await
+        .map_err(|_| RotateKeyError::NotFound)?;
+
+    user_service.rotate_key(&user.id).await?;
+
+    let config = registry.config();
+    let update = UpdatePerson::builder()
+        .context(Context::activity_context_url().clone().into())
+        .id(generate_activity_uri(&config).into())
+        .actor(user.user_uri(&config))
+        .object(security_person(&user, &registry).await?)
+        .to(Note::public_address().clone().into())
+        .build();
+
+    tracing::info!(update=?update);
+
+    registry
+        .delivery_service()
+        .deliver_to_followers(&user, &update)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn rotate_key(
+    _: AdminAuth,
+    Path(username): Path<String>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, RotateKeyError> {
+    rotate_key_handler(&username, registry).await
+}
@@ -0,0 +1,119 @@
+use apub_activitypub::model::{activity::AnnouncePersonNote, context::Context, note::Note};
+use apub_kernel::activitypub::{activity::generate_activity_uri, audience::Audience};
+use apub_kernel::note::{
+    model::{parse_note_uri, Visibility},
+    repository::NoteRepository,
+};
+use apub_kernel::prelude::*;
+use apub_registry::{AppRegistry, AppRegistryExt};
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::extractor::AdminAuth;
+
+#[derive(Deserialize)]
+pub struct SendAnnounceQuery {
+    /// `Announce`する`Note`のURL
+    object: ResourceUrl,
+    user: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendAnnounceError {
+    #[error("Note is not public")]
+    NotPublic,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for SendAnnounceError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotPublic => (StatusCode::FORBIDDEN, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+async fn send_announce_handler(
+    query: &SendAnnounceQuery,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, SendAnnounceError> {
+    let user_repo = registry.user_service();
+    let user = user_repo.find_by_name(&query.user).await?;
+
+    let config = registry.config();
+
+    // 公開されていない`Note`は`Announce`しない。`Note`の作者にも送り、`cc`に入れる
+    let (author_url, inboxes) = match parse_note_uri(&config, &query.object) {
+        Some(note_id) => {
+            let note = registry.note_repository().find(&note_id).await?;
+            if note.visibility == Visibility::Followers {
+                return Err(SendAnnounceError::NotPublic);
+            }
+            let author = user_repo.find_by_id(&note.user_id).await?;
+
+            // このサーバの`Note`なら`shares`に加える
+            registry
+                .share_repository()
+                .create(&note.id, user.user_uri(&config).as_ref())
+                .await?;
+            (author.user_uri(&config).as_ref().clone(), vec![])
+        }
+        None => {
+            let key_repo = registry.rsa_key_repository();
+            let signing_key = key_repo.find_private_key(&user.id).await?;
+            let key_uri = key_repo.find_key_url(&user.id).await?;
+            let activity_service = registry.activity_service();
+            let note = activity_service
+                .get_activity_with_sign::<serde_json::Value>(&query.object, &signing_key, &key_uri)
+                .await?;
+            if !Audience::from_value(&note).is_public() {
+                return Err(SendAnnounceError::NotPublic);
+            }
+            let author_url = note
+                .get("attributedTo")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("note has no attributedTo"))?
+                .parse::<ResourceUrl>()?;
+            let author = activity_service.get_actor_by_url(&author_url).await?;
+            (author.actor_url, vec![author.inbox])
+        }
+    };
+
+    let announce_uri = generate_activity_uri(&config);
+    let announce = AnnouncePersonNote::builder()
+        .context(Context::activity_context_url().clone().into())
+        .id(announce_uri.into())
+        .actor(user.user_uri(&config))
+        .object(query.object.clone().into())
+        .to(Note::public_address().clone().into())
+        .cc(vec![user.followers_uri(&config), author_url].into())
+        .build();
+
+    tracing::info!(announce=?announce);
+
+    registry
+        .delivery_service()
+        .deliver_to_followers_and(&user, &announce, &inboxes)
+        .await?;
+
+    Ok(StatusCode::CREATED)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn send_announce(
+    _: AdminAuth,
+    Query(query): Query<SendAnnounceQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, SendAnnounceError> {
+    send_announce_handler(&query, registry).await
+}
@@ -0,0 +1,14 @@
+use crate::{
+    extractor::SignedActivityJson,
+    handler::inbox::{shared_inbox_handler, InboxError, ReceivedActivity, SharedInboxActivity},
+};
+use apub_registry::AppRegistry;
+use axum::{extract::State, response::IntoResponse};
+
+#[tracing::instrument(skip_all)]
+pub async fn shared_inbox(
+    State(registry): State<AppRegistry>,
+    SignedActivityJson(activity): SignedActivityJson<ReceivedActivity<SharedInboxActivity>>,
+) -> Result<impl IntoResponse, InboxError> {
+    shared_inbox_handler(activity, &registry).await
+}
@@ -0,0 +1,87 @@
+use apub_kernel::activitypub::activity::generate_activity_uri;
+use apub_kernel::note::{model::NoteId, repository::NoteRepository};
+use apub_kernel::prelude::*;
+use apub_registry::{AppRegistry, AppRegistryExt};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+
+use crate::extractor::AdminAuth;
+
+#[derive(Deserialize)]
+pub struct UpdateNoteQuery {
+    note_id: String,
+    message: String,
+    user: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateNoteError {
+    #[error("Note not found")]
+    NotFound,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for UpdateNoteError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+async fn update_note_handler(
+    query: &UpdateNoteQuery,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, UpdateNoteError> {
+    let user_repo = registry.user_service();
+    let user = user_repo.find_by_name(&query.user).await?;
+
+    let note_id = query
+        .note_id
+        .parse::<NoteId>()
+        .map_err(|_| UpdateNoteError::NotFound)?;
+    let note_repo = registry.note_repository();
+    let note = note_repo
+        .find(&note_id)
+        .await
+        .map_err(|_| UpdateNoteError::NotFound)?;
+    // 他のユーザの`Note`は書き換えられない
+    if note.user_id != user.id {
+        return Err(UpdateNoteError::NotFound);
+    }
+
+    note_repo
+        .update(&note.id, &format!("<p>{}</p>", query.message))
+        .await?;
+    let note = note_repo.find(&note.id).await?;
+
+    let config = registry.config();
+    let update = note.to_update(&config, &user, generate_activity_uri(&config));
+
+    tracing::info!(update=?update);
+
+    registry
+        .delivery_service()
+        .deliver_to_followers(&user, &update)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn update_note(
+    _: AdminAuth,
+    Query(query): Query<UpdateNoteQuery>,
+    State(registry): State<AppRegistry>,
+) -> Result<impl IntoResponse, UpdateNoteError> {
+    update_note_handler(&query, registry).await
+}
@@ -0,0 +1,123 @@
+use apub_activitypub::model::{activity::UpdatePerson, context::Context, note::Note};
+use apub_kernel::activitypub::activity::generate_activity_uri;
+use apub_kernel::prelude::*;
+use apub_kernel::user::model::{ProfileField, UserProfile};
+use apub_registry::{AppRegistry, AppRegistryExt};
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{extractor::AdminAuth, handler::person::security_person};
+
+#[derive(Deserialize)]
+pub struct UpdateProfileField {
+    name: String,
+    value: String,
+}
+
+/// 省略した項目は保存されている値のまま
+#[derive(Deserialize)]
+pub struct UpdateProfileBody {
+    display_name: Option<String>,
+    summary: Option<String>,
+    icon: Option<ResourceUrl>,
+    fields: Option<Vec<UpdateProfileField>>,
+    manually_approves_followers: Option<bool>,
+}
+
+impl UpdateProfileBody {
+    /// 保存されているプロフィールに重ねる
+    fn merge(self, stored: UserProfile) -> UserProfile {
+        let UpdateProfileBody {
+            display_name,
+            summary,
+            icon,
+            fields,
+            manually_approves_followers,
+        } = self;
+        let fields = fields.map(|fields| {
+            fields
+                .into_iter()
+                .map(|v| ProfileField::builder().name(v.name).value(v.value).build())
+                .collect()
+        });
+
+        UserProfile {
+            display_name: display_name.or(stored.display_name),
+            summary: summary.or(stored.summary),
+            icon: icon.or(stored.icon),
+            fields: fields.unwrap_or(stored.fields),
+            manually_approves_followers: manually_approves_followers
+                .unwrap_or(stored.manually_approves_followers),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateProfileError {
+    #[error("User not found")]
+    NotFound,
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for UpdateProfileError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()).into_response(),
+            Self::Internal(e) => {
+                tracing::error!(error=%e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
+
+async fn update_profile_handler(
+    username: &str,
+    body: UpdateProfileBody,
+    registry: impl AppRegistryExt,
+) -> Result<impl IntoResponse, UpdateProfileError> {
+    let user_service = registry.user_service();
+    let user = user_service
+        .find_by_name(username)
+        .await
+        .map_err(|_| UpdateProfileError::NotFound)?;
+
+    let stored = user_service.find_profile(&user.id).await?;
+    let profile = body.merge(stored);
+    user_service.update_profile(&user.id, &profile).await?;
+
+    let config = registry.config();
+    let update = UpdatePerson::builder()
+        .context(Context::activity_context_url().clone().into())
+        .id(generate_activity_uri(&config).into())
+        .actor(user.user_uri(&config))
+        .object(security_person(&user, &registry).await?)
+        .to(Note::public_address().clone().into())
+        .build();
+
+    tracing::info!(update=?update);
+
+    registry
+        .delivery_service()
+        .deliver_to_followers(&user, &update)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn update_profile(
+    _: AdminAuth,
+    Path(username): Path<String>,
+    State(registry): State<AppRegistry>,
+    Json(body): Json<UpdateProfileBody>,
+) -> Result<impl IntoResponse, UpdateProfileError> {
+    update_profile_handler(&username, body, registry).await
+}
@@ -1,5 +1,7 @@
-use crate::handler::inbox::{inbox_handler, InboxError, InboxKinds};
-use apub_activitypub::shared::activity_json::ActivityJson;
+use crate::{
+    extractor::SignedActivityJson,
+    handler::inbox::{inbox_handler, InboxError, InboxKinds, ReceivedActivity},
+};
 use apub_registry::AppRegistry;
 use axum::{
     extract::{Path, State},
@@ -10,7 +12,7 @@ use axum::{
 pub async fn user_inbox(
     Path(username): Path<String>,
     State(registry): State<AppRegistry>,
-    ActivityJson(activity): ActivityJson<InboxKinds>,
+    SignedActivityJson(activity): SignedActivityJson<ReceivedActivity<InboxKinds>>,
 ) -> Result<impl IntoResponse, InboxError> {
     inbox_handler(&username, activity, &registry).await
 }
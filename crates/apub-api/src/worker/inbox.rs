@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use apub_kernel::{
+    delivery::model::{RetryDecision, RetryPolicy},
+    inbox::{model::InboxJob, repository::InboxRepository},
+};
+use apub_registry::{AppRegistry, AppRegistryExt};
+use tokio::task::JoinHandle;
+
+use crate::handler::inbox::process_inbox_job;
+
+/// 1回に取り出すジョブの数
+const BATCH_SIZE: i64 = 10;
+/// ジョブがないときに待つ時間
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 取り出したジョブを他のワーカーから隠しておく時間
+///
+/// ワーカーが途中で落ちても、この時間が過ぎれば他のワーカーが拾う
+const LOCK_FOR: Duration = Duration::from_secs(5 * 60);
+/// 処理し終えたジョブを重複の判定のために残しておく期間
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// 保持期間が過ぎたジョブを消す間隔
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// `inbox`で受け取ったActivityをキューから取り出して処理するワーカー
+#[derive(Clone)]
+pub struct InboxWorker {
+    registry: AppRegistry,
+    policy: RetryPolicy,
+}
+
+impl InboxWorker {
+    pub fn new(registry: AppRegistry) -> Self {
+        Self {
+            registry,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// `workers`個のタスクで処理し続け、別のタスクで保持期間が過ぎたジョブを消し続ける
+    pub fn spawn(self, workers: usize) -> Vec<JoinHandle<()>> {
+        let mut handles = (0..workers)
+            .map(|worker| {
+                let this = self.clone();
+                tokio::spawn(async move { this.run(worker).await })
+            })
+            .collect::<Vec<_>>();
+        handles.push(tokio::spawn(async move { self.purge().await }));
+        handles
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn run(&self, worker: usize) {
+        loop {
+            match self.run_once().await {
+                Ok(0) => tokio::time::sleep(POLL_INTERVAL).await,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn purge(&self) {
+        loop {
+            match self
+                .registry
+                .inbox_repository()
+                .purge_finished(RETENTION)
+                .await
+            {
+                Ok(count) => tracing::info!(count, "Purged finished inbox jobs"),
+                Err(e) => tracing::error!(error = %e),
+            }
+            tokio::time::sleep(PURGE_INTERVAL).await;
+        }
+    }
+
+    /// 処理する時刻になったジョブを1回分処理し、処理した数を返す
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        let repo = self.registry.inbox_repository();
+        let jobs = repo.lock_due(BATCH_SIZE, LOCK_FOR).await?;
+        let count = jobs.len();
+
+        for job in jobs {
+            let result = process_inbox_job(&job, &self.registry).await;
+            self.finish(&job, result).await?;
+        }
+
+        Ok(count)
+    }
+
+    #[tracing::instrument(skip_all, fields(job = %job.id, attempts = job.attempts))]
+    async fn finish(&self, job: &InboxJob, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        let repo = self.registry.inbox_repository();
+        let Err(e) = result else {
+            tracing::info!("Processed");
+            return repo.complete(&job.id).await;
+        };
+
+        match self.policy.decide(job.attempts, &e) {
+            RetryDecision::Retry(delay) => {
+                tracing::warn!(error = %e, ?delay, "Processing failed, retrying");
+                repo.retry(&job.id, delay, &e.to_string()).await
+            }
+            RetryDecision::Dead => {
+                tracing::error!(error = %e, "Processing failed, giving up");
+                repo.dead(&job.id, &e.to_string()).await
+            }
+        }
+    }
+}
@@ -1,21 +1,166 @@
+use std::time::Duration;
+
 use apub_shared::model::resource_url::ResourceUrl;
 
+pub mod settings;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AppConfig {
     host_uri: ResourceUrl,
+    clock_skew: Duration,
+    request_timeout: Duration,
+    unreachable_after: Duration,
+    probe_interval: Duration,
+    key_grace_period: Duration,
+    rsa_key_bits: usize,
+    key_pool_size: usize,
+    admin_token: Option<AdminToken>,
+}
+
+/// 管理用のAPIを呼ぶときに`Authorization: Bearer`で渡すトークン
+#[derive(Clone, PartialEq)]
+pub struct AdminToken(String);
+
+impl AdminToken {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// `token`が一致するか
+    ///
+    /// 一致する長さから推測されないように、途中で打ち切らずに比べる
+    pub fn matches(&self, token: &str) -> bool {
+        let (expected, actual) = (self.0.as_bytes(), token.as_bytes());
+        expected.len() == actual.len()
+            && expected
+                .iter()
+                .zip(actual)
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+/// トークンがログに出ないようにする
+impl std::fmt::Debug for AdminToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AdminToken(..)")
+    }
 }
 
 impl AppConfig {
+    /// 受信したリクエストの`Date`が許容される前後の幅の既定値
+    const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(60 * 60);
+    /// 応答のないサーバを待つ時間の既定値
+    const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+    /// 配送先のホストを到達できないとみなすまでの失敗期間の既定値
+    const DEFAULT_UNREACHABLE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+    /// 到達できないホストへ再び配送を試すまでの間隔の既定値
+    const DEFAULT_PROBE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+    /// 置き換えた古い鍵で署名を検証できる期間の既定値
+    const DEFAULT_KEY_GRACE_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+    /// ユーザのRSA鍵の長さの既定値
+    const DEFAULT_RSA_KEY_BITS: usize = 4096;
+    /// 作っておくRSA鍵の数の既定値
+    const DEFAULT_KEY_POOL_SIZE: usize = 4;
+
     pub fn new(host_uri: &str) -> Self {
         Self {
             host_uri: host_uri.parse().unwrap(),
+            clock_skew: Self::DEFAULT_CLOCK_SKEW,
+            request_timeout: Self::DEFAULT_REQUEST_TIMEOUT,
+            unreachable_after: Self::DEFAULT_UNREACHABLE_AFTER,
+            probe_interval: Self::DEFAULT_PROBE_INTERVAL,
+            key_grace_period: Self::DEFAULT_KEY_GRACE_PERIOD,
+            rsa_key_bits: Self::DEFAULT_RSA_KEY_BITS,
+            key_pool_size: Self::DEFAULT_KEY_POOL_SIZE,
+            admin_token: None,
         }
     }
 
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn with_unreachable_after(mut self, unreachable_after: Duration) -> Self {
+        self.unreachable_after = unreachable_after;
+        self
+    }
+
+    pub fn with_probe_interval(mut self, probe_interval: Duration) -> Self {
+        self.probe_interval = probe_interval;
+        self
+    }
+
+    pub fn with_key_grace_period(mut self, key_grace_period: Duration) -> Self {
+        self.key_grace_period = key_grace_period;
+        self
+    }
+
+    pub fn with_rsa_key_bits(mut self, rsa_key_bits: usize) -> Self {
+        self.rsa_key_bits = rsa_key_bits;
+        self
+    }
+
+    pub fn with_key_pool_size(mut self, key_pool_size: usize) -> Self {
+        self.key_pool_size = key_pool_size;
+        self
+    }
+
+    pub fn with_admin_token(mut self, admin_token: AdminToken) -> Self {
+        self.admin_token = Some(admin_token);
+        self
+    }
+
     pub fn host_uri(&self) -> &ResourceUrl {
         &self.host_uri
     }
 
+    /// 受信したリクエストの`Date`が許容される前後の幅
+    pub fn clock_skew(&self) -> Duration {
+        self.clock_skew
+    }
+
+    /// 他のサーバへのリクエストで応答を待つ時間
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
+    /// 配送先のホストへの配送がこの期間失敗し続けたら、到達できないとみなす
+    pub fn unreachable_after(&self) -> Duration {
+        self.unreachable_after
+    }
+
+    /// 到達できないホストへ再び配送を試すまでの間隔
+    pub fn probe_interval(&self) -> Duration {
+        self.probe_interval
+    }
+
+    /// 鍵を置き換えたあと、古い鍵で署名を検証できる期間
+    pub fn key_grace_period(&self) -> Duration {
+        self.key_grace_period
+    }
+
+    /// ユーザのRSA鍵の長さ
+    pub fn rsa_key_bits(&self) -> usize {
+        self.rsa_key_bits
+    }
+
+    /// ユーザを作るときのために作っておくRSA鍵の数
+    pub fn key_pool_size(&self) -> usize {
+        self.key_pool_size
+    }
+
+    /// 管理用のAPIのトークン。なければ管理用のAPIは使えない
+    pub fn admin_token(&self) -> Option<&AdminToken> {
+        self.admin_token.as_ref()
+    }
+
     /// `/inbox`
     pub fn shared_inbox(&self) -> ResourceUrl {
         self.host_uri.clone().set_path("/inbox").to_owned()
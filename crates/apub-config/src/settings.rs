@@ -0,0 +1,602 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use apub_shared::model::resource_url::ResourceUrl;
+use serde::Deserialize;
+
+use crate::{AdminToken, AppConfig};
+
+/// 読む設定ファイルを指定する環境変数
+pub const CONFIG_PATH_ENV: &str = "APUB_LITE_CONFIG";
+/// 指定がないときに読む設定ファイル
+///
+/// なければ既定値と環境変数だけを使う
+pub const DEFAULT_CONFIG_PATH: &str = "apub-lite.toml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// 環境変数の値が読めない
+    #[error("invalid value {value:?} for {name}: {reason}")]
+    Env {
+        name: &'static str,
+        value: String,
+        reason: String,
+    },
+    /// 値は読めたが使えない
+    #[error("invalid {field}: {reason}")]
+    Invalid {
+        field: &'static str,
+        reason: &'static str,
+    },
+}
+
+/// サーバ全体の設定
+///
+/// 既定値、設定ファイル、環境変数の順に上書きする
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub database: DatabaseSettings,
+    pub workers: WorkerSettings,
+    pub timeouts: TimeoutSettings,
+    pub keys: KeySettings,
+    pub log: LogSettings,
+    pub seed: SeedSettings,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    /// 待ち受けるアドレス
+    pub listen: SocketAddr,
+    /// 外から見えるこのサーバのURL
+    pub host: ResourceUrl,
+    /// 鍵の置き換えなど管理用のAPIに必要なトークン
+    pub admin_token: Option<String>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            listen: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 8080),
+            host: "http://example.com".parse().unwrap(),
+            admin_token: None,
+        }
+    }
+}
+
+/// トークンがログに出ないようにする
+impl std::fmt::Debug for ServerSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerSettings")
+            .field("listen", &self.listen)
+            .field("host", &self.host)
+            .field("admin_token", &self.admin_token.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    pub url: String,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            url: "postgresql://postgres:5432/app?user=app&password=password".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerSettings {
+    /// 配送キューを処理するタスクの数
+    pub delivery: usize,
+    /// 受け取ったActivityのキューを処理するタスクの数
+    pub inbox: usize,
+}
+
+impl Default for WorkerSettings {
+    fn default() -> Self {
+        Self {
+            delivery: 4,
+            inbox: 4,
+        }
+    }
+}
+
+/// 時間の設定で、すべて秒で書く
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeoutSettings {
+    pub request_secs: u64,
+    pub clock_skew_secs: u64,
+    pub unreachable_after_secs: u64,
+    pub probe_interval_secs: u64,
+    pub key_grace_period_secs: u64,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            request_secs: AppConfig::DEFAULT_REQUEST_TIMEOUT.as_secs(),
+            clock_skew_secs: AppConfig::DEFAULT_CLOCK_SKEW.as_secs(),
+            unreachable_after_secs: AppConfig::DEFAULT_UNREACHABLE_AFTER.as_secs(),
+            probe_interval_secs: AppConfig::DEFAULT_PROBE_INTERVAL.as_secs(),
+            key_grace_period_secs: AppConfig::DEFAULT_KEY_GRACE_PERIOD.as_secs(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeySettings {
+    /// ユーザのRSA鍵の長さ
+    pub rsa_bits: usize,
+    /// 作っておくRSA鍵の数
+    pub pool_size: usize,
+    /// 秘密鍵を暗号化するBase64のマスター鍵
+    pub master_key: Option<String>,
+    /// マスター鍵を替えるときの前のマスター鍵
+    ///
+    /// `reencrypt-keys`でこれで暗号化された秘密鍵を今のマスター鍵で暗号化し直す
+    pub previous_master_key: Option<String>,
+}
+
+impl Default for KeySettings {
+    fn default() -> Self {
+        Self {
+            rsa_bits: AppConfig::DEFAULT_RSA_KEY_BITS,
+            pool_size: AppConfig::DEFAULT_KEY_POOL_SIZE,
+            master_key: None,
+            previous_master_key: None,
+        }
+    }
+}
+
+/// マスター鍵がログに出ないようにする
+impl std::fmt::Debug for KeySettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeySettings")
+            .field("rsa_bits", &self.rsa_bits)
+            .field("pool_size", &self.pool_size)
+            .field("master_key", &self.master_key.as_ref().map(|_| ".."))
+            .field(
+                "previous_master_key",
+                &self.previous_master_key.as_ref().map(|_| ".."),
+            )
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSettings {
+    pub format: LogFormat,
+}
+
+/// ログの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// 人が読むための形式
+    Pretty,
+    /// 1行ごとのJSON
+    Json,
+}
+
+/// 開発中は読みやすい形式、リリースではJSONにする
+impl Default for LogFormat {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Pretty
+        } else {
+            Self::Json
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => Err("expected `pretty` or `json`"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SeedSettings {
+    /// 起動時に作るユーザの名前
+    pub users: Vec<String>,
+}
+
+impl Default for SeedSettings {
+    fn default() -> Self {
+        Self {
+            users: vec!["alice".to_string(), "bob".to_string()],
+        }
+    }
+}
+
+/// カンマ区切りの名前の一覧
+struct NameList(Vec<String>);
+
+impl FromStr for NameList {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names = s
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(Self(names))
+    }
+}
+
+impl Settings {
+    /// 設定ファイルと環境変数から読む
+    ///
+    /// `APUB_LITE_CONFIG`で指定したファイルはなければエラーにするが、`apub-lite.toml`はなくてもよい
+    pub fn load() -> Result<Self, ConfigError> {
+        let env = |name: &str| std::env::var(name).ok();
+        let settings = match env(CONFIG_PATH_ENV).filter(|v| !v.is_empty()) {
+            Some(path) => Self::from_file(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            None => Self::default(),
+        };
+
+        settings.with_env(env)
+    }
+
+    /// 設定ファイルを既定値に重ねる
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// 環境変数で上書きして、使える値か確かめる
+    ///
+    /// 空の環境変数は設定されていないものとして扱う
+    pub fn with_env(mut self, env: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let env = |name: &str| env(name).filter(|v| !v.is_empty());
+
+        override_with(&env, "APUB_LITE_LISTEN", &mut self.server.listen)?;
+        override_with(&env, "APUB_LITE_URL", &mut self.server.host)?;
+        if let Some(admin_token) = env("APUB_LITE_ADMIN_TOKEN") {
+            self.server.admin_token = Some(admin_token);
+        }
+        override_with(&env, "DATABASE_URL", &mut self.database.url)?;
+        override_with(
+            &env,
+            "APUB_LITE_DELIVERY_WORKERS",
+            &mut self.workers.delivery,
+        )?;
+        override_with(&env, "APUB_LITE_INBOX_WORKERS", &mut self.workers.inbox)?;
+        override_with(
+            &env,
+            "APUB_LITE_REQUEST_TIMEOUT_SECS",
+            &mut self.timeouts.request_secs,
+        )?;
+        override_with(
+            &env,
+            "APUB_LITE_CLOCK_SKEW_SECS",
+            &mut self.timeouts.clock_skew_secs,
+        )?;
+        override_with(
+            &env,
+            "APUB_LITE_UNREACHABLE_AFTER_SECS",
+            &mut self.timeouts.unreachable_after_secs,
+        )?;
+        override_with(
+            &env,
+            "APUB_LITE_PROBE_INTERVAL_SECS",
+            &mut self.timeouts.probe_interval_secs,
+        )?;
+        override_with(
+            &env,
+            "APUB_LITE_KEY_GRACE_PERIOD_SECS",
+            &mut self.timeouts.key_grace_period_secs,
+        )?;
+        override_with(&env, "APUB_LITE_RSA_KEY_BITS", &mut self.keys.rsa_bits)?;
+        override_with(&env, "APUB_LITE_KEY_POOL_SIZE", &mut self.keys.pool_size)?;
+        if let Some(master_key) = env("APUB_LITE_MASTER_KEY") {
+            self.keys.master_key = Some(master_key);
+        }
+        if let Some(previous_master_key) = env("APUB_LITE_PREVIOUS_MASTER_KEY") {
+            self.keys.previous_master_key = Some(previous_master_key);
+        }
+        override_with(&env, "APUB_LITE_LOG_FORMAT", &mut self.log.format)?;
+        if let Some(NameList(users)) = parse_env(&env, "APUB_LITE_SEED_USERS")? {
+            self.seed.users = users;
+        }
+
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    /// 値が使えるか確かめる
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |field, reason| Err(ConfigError::Invalid { field, reason });
+
+        if !self.database.url.starts_with("postgres://")
+            && !self.database.url.starts_with("postgresql://")
+        {
+            return invalid("database.url", "expected a `postgresql://` url");
+        }
+        // 総当たりで当てられないように長さを求める
+        if self
+            .server
+            .admin_token
+            .as_ref()
+            .is_some_and(|v| v.len() < 16)
+        {
+            return invalid("server.admin_token", "must be at least 16 characters");
+        }
+        if self.workers.delivery == 0 {
+            return invalid("workers.delivery", "must be at least 1");
+        }
+        if self.workers.inbox == 0 {
+            return invalid("workers.inbox", "must be at least 1");
+        }
+        if self.timeouts.request_secs == 0 {
+            return invalid("timeouts.request_secs", "must be at least 1");
+        }
+        if self.timeouts.probe_interval_secs == 0 {
+            return invalid("timeouts.probe_interval_secs", "must be at least 1");
+        }
+        // 4096ビットより長い公開鍵は`rsa`が読めない
+        if !(2048..=4096).contains(&self.keys.rsa_bits) {
+            return invalid("keys.rsa_bits", "must be between 2048 and 4096");
+        }
+        if self.seed.users.iter().any(|v| v.trim().is_empty()) {
+            return invalid("seed.users", "must not contain empty names");
+        }
+
+        Ok(())
+    }
+
+    /// アプリケーションに渡す設定
+    pub fn app_config(&self) -> AppConfig {
+        let timeouts = &self.timeouts;
+        let config = AppConfig::new(self.server.host.as_str())
+            .with_request_timeout(Duration::from_secs(timeouts.request_secs))
+            .with_clock_skew(Duration::from_secs(timeouts.clock_skew_secs))
+            .with_unreachable_after(Duration::from_secs(timeouts.unreachable_after_secs))
+            .with_probe_interval(Duration::from_secs(timeouts.probe_interval_secs))
+            .with_key_grace_period(Duration::from_secs(timeouts.key_grace_period_secs))
+            .with_rsa_key_bits(self.keys.rsa_bits)
+            .with_key_pool_size(self.keys.pool_size);
+        match self.server.admin_token {
+            Some(ref token) => config.with_admin_token(AdminToken::new(token.clone())),
+            None => config,
+        }
+    }
+}
+
+fn parse_env<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+) -> Result<Option<T>, ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    env(name)
+        .map(|value| {
+            value.parse().map_err(|e: T::Err| ConfigError::Env {
+                name,
+                reason: e.to_string(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+fn override_with<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    target: &mut T,
+) -> Result<(), ConfigError>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Some(value) = parse_env(env, name)? {
+        *target = value;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let settings = Settings::default().with_env(env(&[])).unwrap();
+        assert_eq!(settings.server.listen.to_string(), "127.0.0.1:8080");
+        assert_eq!(settings.app_config(), AppConfig::new("http://example.com"));
+    }
+
+    #[test]
+    fn test_layers() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [server]
+            listen = "0.0.0.0:3000"
+            host = "https://file.example.com"
+
+            [workers]
+            delivery = 8
+
+            [timeouts]
+            clock_skew_secs = 300
+
+            [log]
+            format = "json"
+
+            [seed]
+            users = []
+            "#,
+        )
+        .unwrap();
+        let settings = settings
+            .with_env(env(&[
+                ("APUB_LITE_URL", "https://env.example.com"),
+                ("APUB_LITE_INBOX_WORKERS", "2"),
+                ("APUB_LITE_LISTEN", ""),
+            ]))
+            .unwrap();
+
+        assert_eq!(settings.server.listen.to_string(), "0.0.0.0:3000");
+        assert_eq!(settings.server.host.as_str(), "https://env.example.com/");
+        assert_eq!(settings.workers.delivery, 8);
+        assert_eq!(settings.workers.inbox, 2);
+        assert_eq!(settings.log.format, LogFormat::Json);
+        assert!(settings.seed.users.is_empty());
+
+        let config = settings.app_config();
+        assert_eq!(config.clock_skew(), Duration::from_secs(300));
+        assert_eq!(config.host_uri().as_str(), "https://env.example.com/");
+    }
+
+    #[test]
+    fn test_example_file() {
+        let mut settings: Settings =
+            toml::from_str(include_str!("../../../apub-lite.example.toml")).unwrap();
+        settings.log = LogSettings::default();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_admin_token() {
+        let token = "0123456789abcdef";
+        let settings = Settings::default()
+            .with_env(env(&[("APUB_LITE_ADMIN_TOKEN", token)]))
+            .unwrap();
+        assert!(!format!("{settings:?}").contains(token));
+        let config = settings.app_config();
+        let admin_token = config.admin_token().unwrap();
+        assert!(admin_token.matches(token));
+        assert!(!admin_token.matches("0123456789abcdeF"));
+        assert!(!admin_token.matches("0123456789"));
+        assert!(!format!("{config:?}").contains(token));
+
+        let res = Settings::default().with_env(env(&[("APUB_LITE_ADMIN_TOKEN", "short")]));
+        assert!(matches!(
+            res,
+            Err(ConfigError::Invalid {
+                field: "server.admin_token",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_master_keys() {
+        let settings = Settings::default()
+            .with_env(env(&[
+                ("APUB_LITE_MASTER_KEY", "current-master-key"),
+                ("APUB_LITE_PREVIOUS_MASTER_KEY", "previous-master-key"),
+            ]))
+            .unwrap();
+        assert_eq!(
+            settings.keys.master_key.as_deref(),
+            Some("current-master-key")
+        );
+        assert_eq!(
+            settings.keys.previous_master_key.as_deref(),
+            Some("previous-master-key")
+        );
+        let debug = format!("{settings:?}");
+        assert!(!debug.contains("current-master-key"));
+        assert!(!debug.contains("previous-master-key"));
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let res = toml::from_str::<Settings>("[workers]\ndelivry = 8\n");
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_invalid_env() {
+        let res = Settings::default().with_env(env(&[("APUB_LITE_DELIVERY_WORKERS", "many")]));
+        assert!(matches!(
+            res,
+            Err(ConfigError::Env {
+                name: "APUB_LITE_DELIVERY_WORKERS",
+                ..
+            })
+        ));
+
+        let res = Settings::default().with_env(env(&[("APUB_LITE_URL", "ftp://example.com")]));
+        assert!(matches!(res, Err(ConfigError::Env { .. })));
+    }
+
+    #[test]
+    fn test_validate() {
+        let res = Settings::default().with_env(env(&[("APUB_LITE_INBOX_WORKERS", "0")]));
+        assert!(matches!(
+            res,
+            Err(ConfigError::Invalid {
+                field: "workers.inbox",
+                ..
+            })
+        ));
+
+        let res = Settings::default().with_env(env(&[("DATABASE_URL", "mysql://localhost")]));
+        assert!(matches!(
+            res,
+            Err(ConfigError::Invalid {
+                field: "database.url",
+                ..
+            })
+        ));
+    }
+}
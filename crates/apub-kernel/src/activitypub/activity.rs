@@ -2,7 +2,7 @@ use apub_activitypub::model::activity::CreatePersonNote;
 use apub_shared::model::resource_url::ResourceUrl;
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::rsa_key::model::RsaSingingKey;
+use crate::rsa_key::model::{HttpSigningKey, RsaSingingKey};
 use apub_config::AppConfig;
 
 pub struct SendActivity<T> {
@@ -41,7 +41,7 @@ pub trait ActivityRepository: Send + Sync {
         &self,
         activity: &T,
         inbox: &ResourceUrl,
-        signer: &RsaSingingKey,
+        signer: &dyn HttpSigningKey,
         key_uri: &ResourceUrl,
     ) -> anyhow::Result<()>;
     /// reqにGetリクエストする
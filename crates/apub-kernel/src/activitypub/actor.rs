@@ -66,10 +66,42 @@ impl<Kind: ActorKind> From<AnyActorImpl<Kind>> for CreateActorEvent {
         let actor_url = value.id().as_ref().clone();
         let inbox = value.inbox().clone();
         let name = value.username().to_owned();
+        let display_name = value.display_name().map(|v| v.to_owned());
+        let shared_inbox = value.shared_inbox().cloned();
         CreateActorEvent::builder()
             .actor_url(actor_url)
             .inbox(inbox)
-            .display_name(None)
+            .shared_inbox(shared_inbox)
+            .display_name(display_name)
+            .preferred_name(name)
+            .build()
+    }
+}
+
+/// リモートの`Actor`の更新
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[builder(field_defaults(setter(into)))]
+pub struct UpdateActorEvent {
+    pub actor_url: ResourceUrl,
+    pub preferred_name: String,
+    pub display_name: Option<String>,
+    pub inbox: ResourceUrl,
+    #[builder(default)]
+    pub shared_inbox: Option<ResourceUrl>,
+}
+
+impl<Kind: ActorKind> From<AnyActorImpl<Kind>> for UpdateActorEvent {
+    fn from(value: AnyActorImpl<Kind>) -> Self {
+        let actor_url = value.id().as_ref().clone();
+        let inbox = value.inbox().clone();
+        let name = value.username().to_owned();
+        let display_name = value.display_name().map(|v| v.to_owned());
+        let shared_inbox = value.shared_inbox().cloned();
+        UpdateActorEvent::builder()
+            .actor_url(actor_url)
+            .inbox(inbox)
+            .shared_inbox(shared_inbox)
+            .display_name(display_name)
             .preferred_name(name)
             .build()
     }
@@ -81,4 +113,8 @@ pub trait ActorRepository: Send + Sync {
     async fn find_by_acct(&self, acct: &AcctUri) -> anyhow::Result<Actor>;
     async fn find_by_url(&self, actor_url: &ResourceUrl) -> anyhow::Result<Actor>;
     async fn create(&self, event: CreateActorEvent) -> anyhow::Result<Actor>;
+    /// リモートの`Actor`のキャッシュを更新する
+    async fn update(&self, event: UpdateActorEvent) -> anyhow::Result<Actor>;
+    /// リモートの`Actor`を削除する。フォローや公開鍵も一緒に消える
+    async fn delete(&self, actor_url: &ResourceUrl) -> anyhow::Result<()>;
 }
@@ -0,0 +1,73 @@
+use apub_activitypub::model::note::Note;
+use apub_shared::model::resource_url::ResourceUrl;
+
+/// `Object`の宛先
+///
+/// `to`、`cc`、`bto`、`bcc`、`audience`に含まれるURLを集めたもの
+///
+/// See https://www.w3.org/TR/activitypub/#audience-targeting
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Audience(Vec<String>);
+
+const AUDIENCE_FIELDS: [&str; 5] = ["to", "cc", "bto", "bcc", "audience"];
+
+impl Audience {
+    pub fn from_value(value: &serde_json::Value) -> Self {
+        let targets = AUDIENCE_FIELDS
+            .iter()
+            .filter_map(|field| value.get(field))
+            .flat_map(|v| match v {
+                serde_json::Value::Array(values) => values.iter().collect(),
+                v => vec![v],
+            })
+            .filter_map(|v| v.as_str().map(ToString::to_string))
+            .collect();
+
+        Self(targets)
+    }
+
+    /// 誰でも取得できるか
+    ///
+    /// `as:Public`や`Public`と省略された表記も受け付ける
+    pub fn is_public(&self) -> bool {
+        let public = Note::public_address().as_str();
+        self.0
+            .iter()
+            .any(|v| v == public || v == "as:Public" || v == "Public")
+    }
+
+    pub fn contains(&self, url: &ResourceUrl) -> bool {
+        self.0.iter().any(|v| v == url.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_audience() {
+        let value = serde_json::json!({
+            "to": "https://www.w3.org/ns/activitystreams#Public",
+            "cc": ["https://example.com/users/alice/followers"],
+        });
+        let audience = Audience::from_value(&value);
+        assert!(audience.is_public());
+        assert!(audience.contains(&"https://example.com/users/alice/followers".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_followers_only_audience() {
+        let value = serde_json::json!({
+            "to": ["https://example.com/users/alice/followers"],
+            "bcc": "https://remote.example/users/bob",
+        });
+        let audience = Audience::from_value(&value);
+        assert!(!audience.is_public());
+        assert_eq!(audience.iter().count(), 2);
+    }
+}
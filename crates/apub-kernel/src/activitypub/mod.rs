@@ -1,3 +1,4 @@
 pub mod activity;
 pub mod actor;
+pub mod audience;
 pub mod service;
@@ -1,52 +1,93 @@
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 
-use apub_activitypub::{model::person::AnyActor, webfinger::AcctUri};
+use apub_activitypub::{
+    core::actor::Actor as _,
+    model::person::SecurityAnyActor,
+    webfinger::{AcctUri, WebFingerResolver},
+};
+use apub_config::AppConfig;
 use apub_shared::model::resource_url::ResourceUrl;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    prelude::{ActivityRepository, RsaKeyRepository},
-    rsa_key::model::RsaSingingKey,
+    prelude::{ActivityRepository, RsaKeyRepository, SentActivityRepository},
+    rsa_key::{
+        http_signature::HttpVerifier,
+        model::{
+            Ed25519VerifyingKey, HttpSigningKey, RsaSingingKey, RsaVerifyingKey,
+            SaveEd25519PublicKeyEvent, SavePublicKeyEvent,
+        },
+    },
+    sent_activity::model::SentActivity,
 };
 
-use super::actor::{Actor, ActorRepository};
+use super::actor::{Actor, ActorRepository, UpdateActorEvent};
 
 pub trait ActivityService: ActivityRepository {
     fn get_actor_by_url(&self, url: &ResourceUrl) -> impl Future<Output = anyhow::Result<Actor>>;
     fn get_actor_by_acct(&self, acct: &AcctUri) -> impl Future<Output = anyhow::Result<Actor>>;
+    /// `keyId`の公開鍵で署名を検証し、鍵の所有者である`Actor`のURLを返す
+    fn verify_signature(
+        &self,
+        verifier: &HttpVerifier,
+    ) -> impl Future<Output = anyhow::Result<ResourceUrl>>;
+    /// `Update`で届いたリモートの`Actor`でキャッシュを更新する
+    fn update_actor(
+        &self,
+        remote: &SecurityAnyActor,
+    ) -> impl Future<Output = anyhow::Result<Actor>>;
 }
 
-pub struct ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo> {
+pub struct ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo, SentRepo> {
     activity: ActivityRepo,
     actor: ActorRepo,
-    _rsa_key: KeyRepo,
+    rsa_key: KeyRepo,
+    sent: SentRepo,
+    config: Arc<AppConfig>,
 }
 
-impl<ActivityRepo, ActorRepo, KeyRepo> ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo> {
-    pub fn new(activity: ActivityRepo, actor: ActorRepo, rsa_key: KeyRepo) -> Self {
+impl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
+    ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
+{
+    pub fn new(
+        activity: ActivityRepo,
+        actor: ActorRepo,
+        rsa_key: KeyRepo,
+        sent: SentRepo,
+        config: Arc<AppConfig>,
+    ) -> Self {
         Self {
             activity,
             actor,
-            _rsa_key: rsa_key,
+            rsa_key,
+            sent,
+            config,
         }
     }
 }
 
 #[async_trait::async_trait]
-impl<ActivityRepo, ActorRepo, KeyRepo> ActivityRepository
-    for ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo>
+impl<ActivityRepo, ActorRepo, KeyRepo, SentRepo> ActivityRepository
+    for ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
 where
     ActivityRepo: ActivityRepository,
     ActorRepo: Send + Sync,
     KeyRepo: Send + Sync,
+    SentRepo: SentActivityRepository,
 {
+    /// 送る`Activity`は`/activities/{id}`で取得できるよう保存しておく
     async fn post_activity<T: Serialize + Sync>(
         &self,
         activity: &T,
         inbox: &ResourceUrl,
-        signer: &RsaSingingKey,
+        signer: &dyn HttpSigningKey,
         key_uri: &ResourceUrl,
     ) -> anyhow::Result<()> {
+        let value = serde_json::to_value(activity)?;
+        if let Some(sent) = SentActivity::from_value(&self.config, value) {
+            self.sent.create(&sent).await?;
+        }
+
         let bind = self
             .activity
             .post_activity(activity, inbox, signer, key_uri)
@@ -71,12 +112,13 @@ where
     }
 }
 
-impl<ActivityRepo, ActorRepo, KeyRepo> ActivityService
-    for ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo>
+impl<ActivityRepo, ActorRepo, KeyRepo, SentRepo> ActivityService
+    for ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
 where
-    ActivityRepo: ActivityRepository,
+    ActivityRepo: ActivityRepository + WebFingerResolver,
     ActorRepo: ActorRepository,
     KeyRepo: RsaKeyRepository,
+    SentRepo: SentActivityRepository,
 {
     async fn get_actor_by_url(&self, url: &ResourceUrl) -> anyhow::Result<Actor> {
         let res = self.actor.find_by_url(url).await;
@@ -84,17 +126,157 @@ where
             return Ok(actor);
         }
 
-        // 理想的にはここでアクターの公開鍵も取得してDBへ格納する
-        let res = self.activity.get_activity::<AnyActor>(url).await?;
-        let actor = self.actor.create(res.into()).await?;
+        let res = self.activity.get_activity::<SecurityAnyActor>(url).await?;
+        self.save_remote_actor(&res).await
+    }
 
-        Ok(actor)
+    async fn get_actor_by_acct(&self, acct: &AcctUri) -> anyhow::Result<Actor> {
+        let res = self.actor.find_by_acct(acct).await;
+        if let Ok(actor) = res {
+            return Ok(actor);
+        }
+
+        let webfinger = self
+            .activity
+            .resolve_webfinger(acct)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to resolve {acct}: {e}"))?;
+        let Some(url) = webfinger.me() else {
+            return Err(anyhow::anyhow!("actor link not found: {acct}"));
+        };
+
+        self.get_actor_by_url(url).await
     }
 
-    async fn get_actor_by_acct(&self, _acct: &AcctUri) -> anyhow::Result<Actor> {
-        // resolve webfinger
+    #[tracing::instrument(skip(self, verifier), fields(key_id = %verifier.key_id()))]
+    async fn verify_signature(&self, verifier: &HttpVerifier) -> anyhow::Result<ResourceUrl> {
+        match self.verify_with_saved_key(verifier).await {
+            Ok(actor_url) => return Ok(actor_url),
+            Err(e) => tracing::info!(error = %e, "key is unknown or outdated, refetching"),
+        }
+
+        // 鍵が未知か更新されている可能性があるので取得し直す
+        let actor = self
+            .activity
+            .get_activity::<SecurityAnyActor>(verifier.key_id())
+            .await?;
+        let published = actor.find_public_key(verifier.key_id()).is_some()
+            || actor
+                .assertion_method()
+                .iter()
+                .any(|v| v.id() == verifier.key_id());
+        if !published {
+            return Err(anyhow::anyhow!("key id does not match"));
+        }
 
-        // then, send get request
-        unimplemented!()
+        self.save_remote_actor(&actor).await?;
+        self.verify_with_saved_key(verifier).await
+    }
+
+    async fn update_actor(&self, remote: &SecurityAnyActor) -> anyhow::Result<Actor> {
+        self.save_remote_actor(remote).await
+    }
+}
+
+impl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
+    ActivityServiceImpl<ActivityRepo, ActorRepo, KeyRepo, SentRepo>
+where
+    ActorRepo: ActorRepository,
+    KeyRepo: RsaKeyRepository,
+{
+    /// 保存している`keyId`の公開鍵で署名を検証し、鍵の所有者である`Actor`のURLを返す
+    ///
+    /// RSAの鍵は`publicKey`、Ed25519の鍵は`assertionMethod`で公開されたもの
+    async fn verify_with_saved_key(&self, verifier: &HttpVerifier) -> anyhow::Result<ResourceUrl> {
+        let key_id = verifier.key_id();
+        if let Ok(saved) = self.rsa_key.find_public_key_by_key_url(key_id).await {
+            verifier.verify(&saved.public_key)?;
+            return Ok(saved.actor_url);
+        }
+
+        let saved = self
+            .rsa_key
+            .find_ed25519_public_key_by_key_url(key_id)
+            .await?;
+        verifier.verify(&saved.public_key)?;
+        Ok(saved.actor_url)
+    }
+
+    /// リモートの`Actor`とその公開鍵をDBへ格納する。すでにある場合は更新する
+    ///
+    /// 鍵を替えた直後の`Actor`は古い鍵も並べているので、すべて保存する。
+    /// 載っていない鍵は漏れて外した可能性もあるので消す
+    async fn save_remote_actor(&self, remote: &SecurityAnyActor) -> anyhow::Result<Actor> {
+        if remote.public_keys().is_empty() {
+            return Err(anyhow::anyhow!("public key is not found"));
+        }
+        let mut verifying_keys = Vec::with_capacity(remote.public_keys().len());
+        for public_key in remote.public_keys() {
+            if public_key.owner().as_ref() != remote.id().as_ref() {
+                return Err(anyhow::anyhow!("key owner does not match"));
+            }
+            // 他のサーバの鍵のURLを名乗らせない
+            if public_key.id().host() != remote.id().host() {
+                return Err(anyhow::anyhow!("key host does not match actor host"));
+            }
+            let verifying_key = RsaVerifyingKey::from_pem(public_key.public_key_pem())?;
+            verifying_keys.push((public_key.id(), verifying_key));
+        }
+
+        let actor = match self.actor.find_by_url(remote.id().as_ref()).await {
+            Ok(actor) if actor.local_id.is_some() => {
+                return Err(anyhow::anyhow!("actor is not remote"));
+            }
+            Ok(_) => {
+                let any_actor = (**remote).clone();
+                self.actor.update(UpdateActorEvent::from(any_actor)).await?
+            }
+            Err(_) => {
+                let any_actor = (**remote).clone();
+                self.actor.create(any_actor.into()).await?
+            }
+        };
+
+        let mut key_urls = Vec::with_capacity(verifying_keys.len());
+        for (key_url, verifying_key) in verifying_keys {
+            let event = SavePublicKeyEvent::builder()
+                .public_key(&verifying_key)
+                .actor_id(&actor.actor_id)
+                .key_url(key_url)
+                .build();
+            self.rsa_key.save_public_key(event).await?;
+            key_urls.push(key_url.clone());
+        }
+
+        self.rsa_key
+            .delete_other_public_keys(&actor.actor_id, &key_urls)
+            .await?;
+
+        // Ed25519の鍵は、その鍵で署名されたリクエストの検証に使う
+        let mut ed25519_key_urls = Vec::new();
+        for multikey in remote.assertion_method() {
+            if multikey.controller().as_ref() != remote.id().as_ref()
+                || multikey.id().host() != remote.id().host()
+            {
+                continue;
+            }
+            let Ok(public_key) =
+                Ed25519VerifyingKey::from_multibase(multikey.public_key_multibase())
+            else {
+                continue;
+            };
+            let event = SaveEd25519PublicKeyEvent::builder()
+                .public_key(&public_key)
+                .actor_id(&actor.actor_id)
+                .key_url(multikey.id())
+                .build();
+            self.rsa_key.save_ed25519_public_key(event).await?;
+            ed25519_key_urls.push(multikey.id().clone());
+        }
+        self.rsa_key
+            .delete_other_ed25519_public_keys(&actor.actor_id, &ed25519_key_urls)
+            .await?;
+
+        Ok(actor)
     }
 }
@@ -0,0 +1,237 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use apub_shared::model::{id::Id, resource_url::ResourceUrl};
+use typed_builder::TypedBuilder;
+
+use crate::{follower::model::Follower, user::model::UserId};
+
+pub type DeliveryJobId = Id<DeliveryJob>;
+
+/// `inbox`へ`Activity`を送るジョブ
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct DeliveryJob {
+    pub id: DeliveryJobId,
+    /// 署名に使う鍵の持ち主
+    pub user_id: UserId,
+    pub inbox: ResourceUrl,
+    pub activity: serde_json::Value,
+    /// これまでに送ろうとした回数。取り出した時点で1増える
+    pub attempts: i32,
+}
+
+/// 積むジョブ
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct NewDeliveryJob {
+    #[builder(default = DeliveryJobId::new())]
+    pub id: DeliveryJobId,
+    pub user_id: UserId,
+    pub inbox: ResourceUrl,
+    pub activity: serde_json::Value,
+}
+
+/// フォロワーへ届けるために送る先の`inbox`
+///
+/// ホストごとにまとめ、共有`inbox`を公開しているホストにはそこへ1回だけ送る。
+/// 共有`inbox`のないフォロワーには個別の`inbox`へ送る
+pub fn follower_inboxes(followers: &[Follower]) -> Vec<ResourceUrl> {
+    let mut by_host = BTreeMap::<&str, Vec<&Follower>>::new();
+    for f in followers {
+        by_host.entry(f.actor_url.host()).or_default().push(f);
+    }
+
+    // 同じ`inbox`へ二度送らないよう、URLで重複を除く
+    let mut inboxes = BTreeMap::<&str, &ResourceUrl>::new();
+    for followers in by_host.into_values() {
+        let shared = followers.iter().find_map(|f| f.shared_inbox.as_ref());
+        for f in followers {
+            let inbox = match (shared, &f.shared_inbox) {
+                (Some(shared), Some(_)) => shared,
+                _ => &f.inbox,
+            };
+            inboxes.insert(inbox.as_str(), inbox);
+        }
+    }
+
+    inboxes.into_values().cloned().collect()
+}
+
+/// 配送の失敗
+#[derive(Debug, thiserror::Error)]
+pub enum DeliveryError {
+    #[error("inbox responded with {0}")]
+    Status(u16),
+    /// 接続できなかった、またはタイムアウトした
+    #[error("{0}")]
+    Network(String),
+}
+
+impl DeliveryError {
+    /// 送り直しても受け入れられない失敗か
+    ///
+    /// 4xxは再送しても変わらないが、`408`と`429`は時間をおけば受け入れられる
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            DeliveryError::Status(status) => {
+                (400..500).contains(status) && *status != 408 && *status != 429
+            }
+            DeliveryError::Network(_) => false,
+        }
+    }
+
+    /// 配送先のホストに届かなかったことを示す失敗か
+    ///
+    /// 4xxはホスト自体は応答しているので含めない
+    pub fn is_host_failure(&self) -> bool {
+        match self {
+            DeliveryError::Status(status) => *status >= 500,
+            DeliveryError::Network(_) => true,
+        }
+    }
+}
+
+/// 失敗したジョブをどうするか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// 指定した時間が経ってから送り直す
+    Retry(Duration),
+    /// これ以上送らない
+    Dead,
+}
+
+/// 再送の間隔と回数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder)]
+pub struct RetryPolicy {
+    #[builder(default = 10)]
+    pub max_attempts: i32,
+    #[builder(default = Duration::from_secs(30))]
+    pub base_delay: Duration,
+    #[builder(default = Duration::from_secs(6 * 60 * 60))]
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl RetryPolicy {
+    /// `attempts`回目の失敗の後に待つ時間
+    ///
+    /// `base_delay`から倍々に伸ばし、`max_delay`で頭打ちにする
+    pub fn backoff(&self, attempts: i32) -> Duration {
+        let exp = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default();
+        let factor = 2u32.checked_pow(exp).unwrap_or(u32::MAX);
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// `attempts`回目に`error`で失敗したジョブをどうするか
+    ///
+    /// `DeliveryError`でない失敗は一時的なものとして扱う
+    pub fn decide(&self, attempts: i32, error: &anyhow::Error) -> RetryDecision {
+        let permanent = error
+            .downcast_ref::<DeliveryError>()
+            .is_some_and(DeliveryError::is_permanent);
+        if permanent || attempts >= self.max_attempts {
+            return RetryDecision::Dead;
+        }
+
+        RetryDecision::Retry(self.backoff(attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn follower(actor_url: &str, shared_inbox: Option<&str>) -> Follower {
+        let actor_url = actor_url.parse::<ResourceUrl>().unwrap();
+        Follower::builder()
+            .user_id(UserId::new())
+            .acct(format!("acct:foo@{}", actor_url.host()).parse().unwrap())
+            .inbox(format!("{actor_url}/inbox").parse().unwrap())
+            .shared_inbox(shared_inbox.map(|v| v.parse().unwrap()))
+            .actor_url(actor_url)
+            .build()
+    }
+
+    #[test]
+    fn test_follower_inboxes() {
+        let followers = [
+            follower(
+                "https://sub1.example.com/users/a",
+                Some("https://sub1.example.com/inbox"),
+            ),
+            follower(
+                "https://sub1.example.com/users/b",
+                Some("https://sub1.example.com/inbox"),
+            ),
+            follower("https://sub2.example.com/users/c", None),
+            follower("https://sub2.example.com/users/d", None),
+        ];
+
+        let inboxes = follower_inboxes(&followers)
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inboxes,
+            vec![
+                "https://sub1.example.com/inbox",
+                "https://sub2.example.com/users/c/inbox",
+                "https://sub2.example.com/users/d/inbox",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::builder()
+            .base_delay(Duration::from_secs(10))
+            .max_delay(Duration::from_secs(100))
+            .build();
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2), Duration::from_secs(20));
+        assert_eq!(policy.backoff(4), Duration::from_secs(80));
+        assert_eq!(policy.backoff(5), Duration::from_secs(100));
+        assert_eq!(policy.backoff(100), Duration::from_secs(100));
+    }
+
+    #[rstest]
+    #[case(DeliveryError::Status(404), 1, RetryDecision::Dead)]
+    #[case(DeliveryError::Status(410), 1, RetryDecision::Dead)]
+    #[case(
+        DeliveryError::Status(429),
+        1,
+        RetryDecision::Retry(Duration::from_secs(30))
+    )]
+    #[case(
+        DeliveryError::Status(503),
+        2,
+        RetryDecision::Retry(Duration::from_secs(60))
+    )]
+    #[case(DeliveryError::Network("timed out".to_string()), 1, RetryDecision::Retry(Duration::from_secs(30)))]
+    #[case(DeliveryError::Status(503), 10, RetryDecision::Dead)]
+    fn test_decide(
+        #[case] error: DeliveryError,
+        #[case] attempts: i32,
+        #[case] expected: RetryDecision,
+    ) {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.decide(attempts, &error.into()), expected);
+    }
+
+    #[rstest]
+    #[case(DeliveryError::Status(404), false)]
+    #[case(DeliveryError::Status(429), false)]
+    #[case(DeliveryError::Status(502), true)]
+    #[case(DeliveryError::Network("timed out".to_string()), true)]
+    fn test_is_host_failure(#[case] error: DeliveryError, #[case] expected: bool) {
+        assert_eq!(error.is_host_failure(), expected);
+    }
+}
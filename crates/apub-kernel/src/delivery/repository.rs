@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use super::model::{DeliveryJob, DeliveryJobId, NewDeliveryJob};
+
+#[async_trait::async_trait]
+pub trait DeliveryRepository: Send + Sync {
+    async fn enqueue(&self, jobs: &[NewDeliveryJob]) -> anyhow::Result<()>;
+    /// 送る時刻になったジョブを`limit`件取り出す
+    ///
+    /// 取り出したジョブは`lock_for`の間は他のワーカーから見えなくなり、`attempts`が1増える
+    async fn lock_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<DeliveryJob>>;
+    /// 送り終えたジョブを消す
+    async fn complete(&self, id: &DeliveryJobId) -> anyhow::Result<()>;
+    /// `delay`が経ってから送り直す
+    async fn retry(&self, id: &DeliveryJobId, delay: Duration, error: &str) -> anyhow::Result<()>;
+    /// 送らずに`delay`が経ってから送り直す
+    ///
+    /// 送っていないので、取り出したときに増やした`attempts`を戻す
+    async fn postpone(&self, id: &DeliveryJobId, delay: Duration) -> anyhow::Result<()>;
+    /// これ以上送らないジョブとして残す
+    async fn dead(&self, id: &DeliveryJobId, error: &str) -> anyhow::Result<()>;
+}
@@ -0,0 +1,158 @@
+use std::{future::Future, sync::Arc};
+
+use apub_config::AppConfig;
+use apub_shared::model::resource_url::ResourceUrl;
+use serde::Serialize;
+
+use crate::{
+    follower::repository::FollowerRepository,
+    instance::repository::InstanceRepository,
+    sent_activity::{model::SentActivity, repository::SentActivityRepository},
+    user::model::User,
+};
+
+use super::{
+    model::{follower_inboxes, NewDeliveryJob},
+    repository::DeliveryRepository,
+};
+
+pub trait DeliveryService: Send + Sync {
+    /// `activity`を`inbox`へ送るジョブを積む
+    fn deliver<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+        inbox: &ResourceUrl,
+    ) -> impl Future<Output = anyhow::Result<()>>;
+    /// `activity`を`user`のフォロワー全員へ送るジョブを積む
+    fn deliver_to_followers<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+    ) -> impl Future<Output = anyhow::Result<()>>;
+    /// `activity`を`user`のフォロワー全員と`inboxes`へ送るジョブを積む
+    ///
+    /// フォロワーと同じ`inbox`へは1度だけ送る
+    fn deliver_to_followers_and<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+        inboxes: &[ResourceUrl],
+    ) -> impl Future<Output = anyhow::Result<()>>;
+}
+
+pub struct DeliveryServiceImpl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo> {
+    delivery: DeliveryRepo,
+    follower: FollowerRepo,
+    sent: SentRepo,
+    instance: InstanceRepo,
+    config: Arc<AppConfig>,
+}
+
+impl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo>
+    DeliveryServiceImpl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo>
+{
+    pub fn new(
+        delivery: DeliveryRepo,
+        follower: FollowerRepo,
+        sent: SentRepo,
+        instance: InstanceRepo,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            delivery,
+            follower,
+            sent,
+            instance,
+            config,
+        }
+    }
+}
+
+impl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo> DeliveryService
+    for DeliveryServiceImpl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo>
+where
+    DeliveryRepo: DeliveryRepository,
+    FollowerRepo: FollowerRepository,
+    SentRepo: SentActivityRepository,
+    InstanceRepo: InstanceRepository,
+{
+    #[tracing::instrument(skip(self, user, activity), fields(user = user.name))]
+    async fn deliver<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+        inbox: &ResourceUrl,
+    ) -> anyhow::Result<()> {
+        self.enqueue(user, activity, vec![inbox.clone()]).await
+    }
+
+    #[tracing::instrument(skip(self, user, activity), fields(user = user.name))]
+    async fn deliver_to_followers<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+    ) -> anyhow::Result<()> {
+        self.deliver_to_followers_and(user, activity, &[]).await
+    }
+
+    #[tracing::instrument(skip(self, user, activity), fields(user = user.name))]
+    async fn deliver_to_followers_and<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+        inboxes: &[ResourceUrl],
+    ) -> anyhow::Result<()> {
+        let followers = self.follower.find_followee(&user.id).await?;
+        let mut targets = follower_inboxes(&followers);
+        targets.extend_from_slice(inboxes);
+
+        self.enqueue(user, activity, targets).await
+    }
+}
+
+impl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo>
+    DeliveryServiceImpl<DeliveryRepo, FollowerRepo, SentRepo, InstanceRepo>
+where
+    DeliveryRepo: DeliveryRepository,
+    SentRepo: SentActivityRepository,
+    InstanceRepo: InstanceRepository,
+{
+    async fn enqueue<T: Serialize + Sync>(
+        &self,
+        user: &User,
+        activity: &T,
+        mut inboxes: Vec<ResourceUrl>,
+    ) -> anyhow::Result<()> {
+        let activity = serde_json::to_value(activity)?;
+        // 送る`Activity`は`/activities/{id}`で取得できるよう保存しておく
+        if let Some(sent) = SentActivity::from_value(&self.config, activity.clone()) {
+            self.sent.create(&sent).await?;
+        }
+
+        inboxes.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        inboxes.dedup();
+
+        // 到達できないホストへは送らない
+        let hosts = inboxes.iter().map(|v| v.host()).collect::<Vec<_>>();
+        let unreachable = self.instance.find_unreachable(&hosts).await?;
+        if !unreachable.is_empty() {
+            tracing::info!(?unreachable, "Skip unreachable hosts");
+            inboxes.retain(|v| !unreachable.iter().any(|host| host == v.host()));
+        }
+
+        let jobs = inboxes
+            .into_iter()
+            .map(|inbox| {
+                NewDeliveryJob::builder()
+                    .user_id(user.id.clone())
+                    .inbox(inbox)
+                    .activity(activity.clone())
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        tracing::info!(jobs = jobs.len(), "Enqueue delivery");
+
+        self.delivery.enqueue(&jobs).await
+    }
+}
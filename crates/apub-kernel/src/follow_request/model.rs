@@ -0,0 +1,13 @@
+use apub_shared::model::resource_url::ResourceUrl;
+use typed_builder::TypedBuilder;
+
+use crate::user::model::UserId;
+
+/// 承認待ちの`Follow`
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct FollowRequest {
+    pub user_id: UserId,
+    pub actor_url: ResourceUrl,
+    /// 届いた`Follow`の`id`
+    pub follow_url: ResourceUrl,
+}
@@ -0,0 +1,20 @@
+use apub_shared::model::resource_url::ResourceUrl;
+
+use crate::user::model::UserId;
+
+use super::model::FollowRequest;
+
+#[async_trait::async_trait]
+pub trait FollowRequestRepository: Send + Sync {
+    async fn find(
+        &self,
+        user_id: &UserId,
+        actor_url: &ResourceUrl,
+    ) -> anyhow::Result<FollowRequest>;
+    /// 承認待ちの`Follow`を古い順に探す
+    async fn find_by_user(&self, user_id: &UserId) -> anyhow::Result<Vec<FollowRequest>>;
+    /// `Follow`を保存する。すでにある場合は`follow_url`を置き換える
+    async fn create(&self, request: &FollowRequest) -> anyhow::Result<()>;
+    /// 承認待ちの`Follow`を削除する。削除したかどうかを返す
+    async fn delete(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<bool>;
+}
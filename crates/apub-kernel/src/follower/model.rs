@@ -10,4 +10,6 @@ pub struct Follower {
     pub acct: AcctUri,
     pub actor_url: ResourceUrl,
     pub inbox: ResourceUrl,
+    #[builder(default)]
+    pub shared_inbox: Option<ResourceUrl>,
 }
@@ -1,6 +1,9 @@
 use apub_shared::model::resource_url::ResourceUrl;
 
-use crate::user::model::UserId;
+use crate::{
+    pagination::{Cursor, Page},
+    user::model::UserId,
+};
 
 use super::model::Follower;
 
@@ -8,6 +11,14 @@ use super::model::Follower;
 pub trait FollowerRepository: Send + Sync {
     async fn find(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<bool>;
     async fn find_followee(&self, user_id: &UserId) -> anyhow::Result<Vec<Follower>>;
+    async fn count(&self, user_id: &UserId) -> anyhow::Result<i64>;
+    /// フォロワーを新しい順に`cursor`から`limit`件探す
+    async fn find_page(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Follower>>;
     async fn create(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
     async fn delete(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
 }
@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use apub_activitypub::webfinger::AcctUri;
+use apub_shared::model::resource_url::ResourceUrl;
+use typed_builder::TypedBuilder;
+
+use crate::user::model::UserId;
+
+/// ローカルのユーザーからリモートの`Actor`への`Follow`
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct Following {
+    pub user_id: UserId,
+    pub actor_url: ResourceUrl,
+    /// 送った`Follow`の`id`
+    pub follow_url: ResourceUrl,
+    /// `Accept`が届いたか
+    #[builder(default)]
+    pub accepted: bool,
+}
+
+/// [`FollowingRepository::create`](super::repository::FollowingRepository::create)の結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreatedFollowing {
+    /// 新しく承認待ちとして保存した
+    Created,
+    /// 承認待ちのものの`follow_url`を置き換えた
+    Replaced,
+    /// すでに承認されていたので書き換えなかった
+    Accepted(Following),
+}
+
+/// フォローする相手の指定
+#[derive(Debug, Clone, PartialEq)]
+pub enum FollowTarget {
+    Url(ResourceUrl),
+    Acct(AcctUri),
+}
+
+impl FromStr for FollowTarget {
+    type Err = anyhow::Error;
+
+    /// `https://example.com/users/bob`、`acct:bob@example.com`、`@bob@example.com`を受け付ける
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("://") {
+            return Ok(Self::Url(s.parse()?));
+        }
+        let acct = s.trim_start_matches('@');
+        let acct = if acct.starts_with("acct:") {
+            acct.parse()?
+        } else {
+            format!("acct:{acct}").parse()?
+        };
+
+        Ok(Self::Acct(acct))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("acct:bob@example.com")]
+    #[case("bob@example.com")]
+    #[case("@bob@example.com")]
+    fn test_parse_acct_target(#[case] s: &str) {
+        let expected = FollowTarget::Acct(AcctUri::new("example.com", "bob").unwrap());
+        assert_eq!(expected, s.parse::<FollowTarget>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_url_target() {
+        let target = "https://example.com/users/bob"
+            .parse::<FollowTarget>()
+            .unwrap();
+        assert!(matches!(target, FollowTarget::Url(_)));
+    }
+}
@@ -0,0 +1,33 @@
+use apub_shared::model::resource_url::ResourceUrl;
+
+use crate::{
+    pagination::{Cursor, Page},
+    user::model::UserId,
+};
+
+use super::model::{CreatedFollowing, Following};
+
+#[async_trait::async_trait]
+pub trait FollowingRepository: Send + Sync {
+    async fn find(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<Following>;
+    /// `user_id`の`Follow`を古い順に探す。承認待ちのものも含む
+    async fn find_by_user(&self, user_id: &UserId) -> anyhow::Result<Vec<Following>>;
+    /// `actor_url`を承認済みでフォローしているローカルユーザの`Follow`を探す
+    async fn find_by_actor(&self, actor_url: &ResourceUrl) -> anyhow::Result<Vec<Following>>;
+    /// 承認済みの`Follow`の数
+    async fn count(&self, user_id: &UserId) -> anyhow::Result<i64>;
+    /// 承認済みの`Follow`を新しい順に`cursor`から`limit`件探す
+    async fn find_page(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Following>>;
+    /// 承認待ちとして保存する。承認待ちのものがすでにある場合は`follow_url`を置き換える
+    ///
+    /// 承認済みのものは書き換えない
+    async fn create(&self, following: &Following) -> anyhow::Result<CreatedFollowing>;
+    /// `Accept`が届いた`Follow`を承認済みにする
+    async fn accept(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+    async fn delete(&self, user_id: &UserId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+}
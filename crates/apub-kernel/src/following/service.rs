@@ -0,0 +1,163 @@
+use std::{future::Future, sync::Arc};
+
+use apub_activitypub::model::{
+    activity::{FollowPerson, UndoPersonFollow},
+    context::Context,
+    person::Person,
+};
+use apub_config::AppConfig;
+
+use crate::{
+    activitypub::{activity::generate_activity_uri, actor::Actor, service::ActivityService},
+    rsa_key::repository::RsaKeyRepository,
+    user::model::User,
+};
+
+use super::{
+    model::{CreatedFollowing, FollowTarget, Following},
+    repository::FollowingRepository,
+};
+
+pub trait FollowingService: Send + Sync {
+    /// `target`へ`Follow`を送り、`Accept`が届くまで承認待ちとして記録する
+    fn follow(
+        &self,
+        user: &User,
+        target: &FollowTarget,
+    ) -> impl Future<Output = anyhow::Result<Following>>;
+    /// `target`へ`Undo`を送り、`Follow`を取り消す
+    fn unfollow(
+        &self,
+        user: &User,
+        target: &FollowTarget,
+    ) -> impl Future<Output = anyhow::Result<Following>>;
+}
+
+pub struct FollowingServiceImpl<ActivityServ, KeyRepo, FollowingRepo> {
+    activity: ActivityServ,
+    rsa_key: KeyRepo,
+    following: FollowingRepo,
+    config: Arc<AppConfig>,
+}
+
+impl<ActivityServ, KeyRepo, FollowingRepo>
+    FollowingServiceImpl<ActivityServ, KeyRepo, FollowingRepo>
+{
+    pub fn new(
+        activity: ActivityServ,
+        rsa_key: KeyRepo,
+        following: FollowingRepo,
+        config: Arc<AppConfig>,
+    ) -> Self {
+        Self {
+            activity,
+            rsa_key,
+            following,
+            config,
+        }
+    }
+}
+
+impl<ActivityServ, KeyRepo, FollowingRepo> FollowingService
+    for FollowingServiceImpl<ActivityServ, KeyRepo, FollowingRepo>
+where
+    ActivityServ: ActivityService,
+    KeyRepo: RsaKeyRepository,
+    FollowingRepo: FollowingRepository,
+{
+    #[tracing::instrument(skip(self, user), fields(user = user.name))]
+    async fn follow(&self, user: &User, target: &FollowTarget) -> anyhow::Result<Following> {
+        let actor = self.resolve(target).await?;
+
+        let following = Following::builder()
+            .user_id(user.id.clone())
+            .actor_url(actor.actor_url.clone())
+            .follow_url(generate_activity_uri(&self.config))
+            .build();
+        // `Accept`が先に届いても見つけられるよう、送る前に記録する
+        // すでに承認されていれば送り直さない
+        let created = match self.following.create(&following).await? {
+            CreatedFollowing::Accepted(accepted) => return Ok(accepted),
+            CreatedFollowing::Created => true,
+            CreatedFollowing::Replaced => false,
+        };
+
+        let context = Context::activity_context_url().clone().into();
+        let follow = self.follow_activity(user, &following, Some(context));
+        if let Err(e) = self.send(user, &actor, &follow).await {
+            // 前から承認待ちだったものは残す
+            if created {
+                self.following.delete(&user.id, &actor.actor_url).await?;
+            }
+            return Err(e);
+        }
+
+        Ok(following)
+    }
+
+    #[tracing::instrument(skip(self, user), fields(user = user.name))]
+    async fn unfollow(&self, user: &User, target: &FollowTarget) -> anyhow::Result<Following> {
+        let actor = self.resolve(target).await?;
+        let following = self.following.find(&user.id, &actor.actor_url).await?;
+
+        let undo = UndoPersonFollow::<Person>::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id(generate_activity_uri(&self.config).into())
+            .actor(user.user_uri(&self.config))
+            .object(self.follow_activity(user, &following, None))
+            .build();
+        self.send(user, &actor, &undo).await?;
+
+        self.following.delete(&user.id, &actor.actor_url).await?;
+
+        Ok(following)
+    }
+}
+
+impl<ActivityServ, KeyRepo, FollowingRepo>
+    FollowingServiceImpl<ActivityServ, KeyRepo, FollowingRepo>
+where
+    ActivityServ: ActivityService,
+    KeyRepo: RsaKeyRepository,
+{
+    async fn resolve(&self, target: &FollowTarget) -> anyhow::Result<Actor> {
+        let actor = match target {
+            FollowTarget::Url(url) => self.activity.get_actor_by_url(url).await?,
+            FollowTarget::Acct(acct) => self.activity.get_actor_by_acct(acct).await?,
+        };
+        if actor.local_id.is_some() {
+            return Err(anyhow::anyhow!("actor is not remote"));
+        }
+
+        Ok(actor)
+    }
+
+    /// 記録していた`Follow`を組み立て直す。`Undo`に埋め込む場合は`context`を省く
+    fn follow_activity(
+        &self,
+        user: &User,
+        following: &Following,
+        context: Option<Context>,
+    ) -> FollowPerson<Person> {
+        FollowPerson::<Person>::builder()
+            .context(context)
+            .id(following.follow_url.clone().into())
+            .actor(user.user_uri(&self.config))
+            .object(following.actor_url.clone().into())
+            .build()
+    }
+
+    async fn send<T: serde::Serialize + Sync>(
+        &self,
+        user: &User,
+        actor: &Actor,
+        activity: &T,
+    ) -> anyhow::Result<()> {
+        let signing_key = self.rsa_key.find_private_key(&user.id).await?;
+        let key_uri = self.rsa_key.find_key_url(&user.id).await?;
+
+        self.activity
+            .post_activity(activity, &actor.inbox, &signing_key, &key_uri)
+            .await
+    }
+}
@@ -0,0 +1,34 @@
+use apub_shared::model::id::Id;
+use typed_builder::TypedBuilder;
+
+use crate::user::model::UserId;
+
+pub type InboxJobId = Id<InboxJob>;
+
+/// `inbox`で受け取ったActivityを処理するジョブ
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct InboxJob {
+    pub id: InboxJobId,
+    /// 個人の`inbox`で受け取った場合はその持ち主。共有`inbox`の場合は`None`
+    pub user_id: Option<UserId>,
+    pub activity: serde_json::Value,
+    /// これまでに処理しようとした回数。取り出した時点で1増える
+    pub attempts: i32,
+}
+
+/// 積むジョブ
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct NewInboxJob {
+    #[builder(default = InboxJobId::new())]
+    pub id: InboxJobId,
+    #[builder(default)]
+    pub user_id: Option<UserId>,
+    pub activity: serde_json::Value,
+}
+
+impl NewInboxJob {
+    /// 重複して受け取ったかを判定するためのActivityの`id`
+    pub fn activity_id(&self) -> Option<&str> {
+        self.activity.get("id").and_then(serde_json::Value::as_str)
+    }
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use super::model::{InboxJob, InboxJobId, NewInboxJob};
+
+#[async_trait::async_trait]
+pub trait InboxRepository: Send + Sync {
+    /// ジョブを積む
+    ///
+    /// 同じ`id`のActivityをすでに受け取っていれば積まずに`false`を返す
+    async fn enqueue(&self, job: &NewInboxJob) -> anyhow::Result<bool>;
+    /// 処理する時刻になったジョブを`limit`件取り出す
+    ///
+    /// 取り出したジョブは`lock_for`の間は他のワーカーから見えなくなり、`attempts`が1増える
+    async fn lock_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<InboxJob>>;
+    /// 処理し終えたジョブとして残す
+    ///
+    /// 同じActivityを再び受け取ったときに重複を判定できるよう、[`InboxRepository::purge_finished`]で消すまで残す
+    async fn complete(&self, id: &InboxJobId) -> anyhow::Result<()>;
+    /// `delay`が経ってから処理し直す
+    async fn retry(&self, id: &InboxJobId, delay: Duration, error: &str) -> anyhow::Result<()>;
+    /// これ以上処理しないジョブとして残す
+    async fn dead(&self, id: &InboxJobId, error: &str) -> anyhow::Result<()>;
+    /// 処理し終えてから`older_than`が経ったジョブを消し、消した数を返す
+    ///
+    /// 消したジョブと同じActivityは再び受け取ると積まれる
+    async fn purge_finished(&self, older_than: Duration) -> anyhow::Result<u64>;
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+/// 配送先のホストごとの到達状況
+#[async_trait::async_trait]
+pub trait InstanceRepository: Send + Sync {
+    /// `host`への配送が成功したことを記録し、到達できない状態を解除する
+    async fn record_success(&self, host: &str) -> anyhow::Result<()>;
+    /// `host`への配送が失敗したことを記録する
+    ///
+    /// 最初の失敗から`unreachable_after`経っても成功していなければ到達できないホストとし、
+    /// `probe_interval`が経つまで配送を止める
+    async fn record_failure(
+        &self,
+        host: &str,
+        unreachable_after: Duration,
+        probe_interval: Duration,
+    ) -> anyhow::Result<()>;
+    /// `hosts`のうち、到達できずに配送を止めているホスト
+    async fn find_unreachable(&self, hosts: &[&str]) -> anyhow::Result<Vec<String>>;
+    /// 次に試す時刻になった到達できないホストを`limit`件取り出す
+    ///
+    /// 取り出したホストは`lock_for`の間は他のワーカーから見えず、配送も止めたままにする
+    async fn lock_probe_due(&self, limit: i64, lock_for: Duration) -> anyhow::Result<Vec<String>>;
+}
@@ -0,0 +1,11 @@
+use apub_shared::model::resource_url::ResourceUrl;
+use typed_builder::TypedBuilder;
+
+use crate::note::model::NoteId;
+
+/// `Note`への`Like`
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct Like {
+    pub note_id: NoteId,
+    pub actor_url: ResourceUrl,
+}
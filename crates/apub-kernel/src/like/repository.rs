@@ -0,0 +1,15 @@
+use apub_shared::model::resource_url::ResourceUrl;
+
+use crate::note::model::NoteId;
+
+use super::model::Like;
+
+#[async_trait::async_trait]
+pub trait LikeRepository: Send + Sync {
+    /// `Note`への`Like`を古い順に探す
+    async fn find_by_note(&self, note_id: &NoteId) -> anyhow::Result<Vec<Like>>;
+    /// `Like`を保存する。すでにある場合は何もしない
+    async fn create(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+    /// `Like`を削除する。ない場合は何もしない
+    async fn delete(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+}
@@ -1,7 +1,19 @@
+use apub_activitypub::model::{
+    activity::{CreatePersonNote, UpdatePersonNote},
+    context::Context,
+    note::Note as NoteObject,
+    tombstone::Tombstone,
+};
+use std::str::FromStr;
+
 use apub_config::AppConfig;
-use apub_shared::model::id::{Id, UrlId};
+use apub_shared::model::{
+    id::{Id, UrlId},
+    resource_url::ResourceUrl,
+};
+use typed_builder::TypedBuilder;
 
-use crate::user::model::UserId;
+use crate::user::model::{User, UserId};
 
 pub type NoteId = Id<Note>;
 pub type NoteUrl = UrlId<Note>;
@@ -11,17 +23,196 @@ pub struct Note {
     pub id: NoteId,
     pub user_id: UserId,
     pub content: String,
+    /// 作成された日時 (RFC 3339)
+    pub published: String,
+    /// 編集された日時 (RFC 3339)
+    pub updated: Option<String>,
+    pub visibility: Visibility,
 }
 
 impl Note {
+    /// `/notes/{id}`
     pub fn note_uri(&self, config: &AppConfig) -> NoteUrl {
-        let note_uri = config
-            .host_uri()
-            .clone()
-            .set_path(&format!("/notes/{}", self.id))
-            .to_owned();
-        note_uri.into()
+        create_note_uri(config, &self.id)
+    }
+
+    /// `/notes/{id}/likes`
+    pub fn likes_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_likes_uri(config, &self.id)
+    }
+
+    /// `/notes/{id}/shares`
+    pub fn shares_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_shares_uri(config, &self.id)
+    }
+
+    /// `/notes/{id}/activity`
+    pub fn create_activity_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_activity_uri(config, &self.id)
+    }
+
+    /// Create Note object
+    pub fn to_note(&self, config: &AppConfig, author: &User) -> NoteObject {
+        let (to, cc) = self.visibility.addressing(config, author);
+        NoteObject::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id(self.note_uri(config).as_ref().clone().into())
+            .content(self.content.clone())
+            .published(self.published.clone())
+            .updated(self.updated.clone())
+            .attributed_to(author.user_uri(config).into())
+            .to(to.into())
+            .cc(cc.into())
+            .likes(self.likes_uri(config))
+            .shares(self.shares_uri(config))
+            .build()
+    }
+
+    /// `Note`を作成した`Create`を作る
+    pub fn to_create(&self, config: &AppConfig, author: &User) -> CreatePersonNote {
+        CreatePersonNote::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id(self.create_activity_uri(config).into())
+            .actor(author.user_uri(config))
+            .object(self.to_note(config, author))
+            .build()
+    }
+
+    /// `Note`を編集した`Update`を作る
+    ///
+    /// `to`と`cc`は`Note`と同じ公開範囲にする
+    pub fn to_update(
+        &self,
+        config: &AppConfig,
+        author: &User,
+        id: ResourceUrl,
+    ) -> UpdatePersonNote {
+        let (to, cc) = self.visibility.addressing(config, author);
+        UpdatePersonNote::builder()
+            .context(Context::activity_context_url().clone().into())
+            .id(id.into())
+            .actor(author.user_uri(config))
+            .object(self.to_note(config, author))
+            .to(to.into())
+            .cc(cc.into())
+            .build()
+    }
+}
+
+/// `Note`の公開範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Visibility {
+    /// 誰でも見られ、公開タイムラインにも載る
+    #[default]
+    Public,
+    /// 誰でも見られるが、公開タイムラインには載らない
+    Unlisted,
+    /// フォロワーだけが見られる
+    Followers,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Unlisted => "unlisted",
+            Visibility::Followers => "followers",
+        }
     }
+
+    /// 公開範囲に応じた`to`と`cc`
+    ///
+    /// See https://docs.joinmastodon.org/spec/activitypub/#to-cc
+    pub fn addressing(
+        &self,
+        config: &AppConfig,
+        author: &User,
+    ) -> (Vec<ResourceUrl>, Vec<ResourceUrl>) {
+        let public = NoteObject::public_address().clone();
+        let followers = author.followers_uri(config);
+        match self {
+            Visibility::Public => (vec![public], vec![followers]),
+            Visibility::Unlisted => (vec![followers], vec![public]),
+            Visibility::Followers => (vec![followers], vec![]),
+        }
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "unlisted" => Ok(Visibility::Unlisted),
+            "followers" => Ok(Visibility::Followers),
+            _ => Err(anyhow::anyhow!("unknown visibility: {s}")),
+        }
+    }
+}
+
+/// 削除された`Note`
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct DeletedNote {
+    pub id: NoteId,
+    pub user_id: UserId,
+    /// 削除された日時 (RFC 3339)
+    pub deleted_at: String,
+}
+
+impl DeletedNote {
+    /// Create Tombstone object
+    pub fn to_tombstone(&self, config: &AppConfig) -> Tombstone {
+        let note_uri = create_note_uri(config, &self.id);
+        Tombstone::builder()
+            .id(note_uri.as_ref().clone().into())
+            .former_type("Note".to_string())
+            .deleted(self.deleted_at.clone())
+            .build()
+    }
+}
+
+/// このサーバの`Note`のURLから`NoteId`を取り出す
+///
+/// 別のサーバのURLや`/notes/{id}`の形でない場合は`None`を返す
+pub fn parse_note_uri(config: &AppConfig, url: &ResourceUrl) -> Option<NoteId> {
+    let host_uri = config.host_uri();
+    if url.host() != host_uri.host() || url.port() != host_uri.port() {
+        return None;
+    }
+    url.path().strip_prefix("/notes/")?.parse().ok()
+}
+
+pub(crate) fn create_note_uri(config: &AppConfig, note_id: &NoteId) -> NoteUrl {
+    let note_uri = config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/notes/{}", note_id))
+        .to_owned();
+    note_uri.into()
+}
+
+pub(crate) fn create_likes_uri(config: &AppConfig, note_id: &NoteId) -> ResourceUrl {
+    config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/notes/{}/likes", note_id))
+        .to_owned()
+}
+
+pub(crate) fn create_shares_uri(config: &AppConfig, note_id: &NoteId) -> ResourceUrl {
+    config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/notes/{}/shares", note_id))
+        .to_owned()
+}
+
+pub(crate) fn create_activity_uri(config: &AppConfig, note_id: &NoteId) -> ResourceUrl {
+    config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/notes/{}/activity", note_id))
+        .to_owned()
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -29,6 +220,7 @@ pub struct CreateNote {
     pub note_id: NoteId,
     pub user_id: UserId,
     pub content: String,
+    pub visibility: Visibility,
 }
 
 impl CreateNote {
@@ -38,6 +230,138 @@ impl CreateNote {
             note_id,
             user_id,
             content,
+            visibility: Visibility::default(),
         }
     }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// `/notes/{id}`
+    pub fn note_uri(&self, config: &AppConfig) -> NoteUrl {
+        create_note_uri(config, &self.note_id)
+    }
+
+    /// `/notes/{id}/likes`
+    pub fn likes_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_likes_uri(config, &self.note_id)
+    }
+
+    /// `/notes/{id}/shares`
+    pub fn shares_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_shares_uri(config, &self.note_id)
+    }
+
+    /// `/notes/{id}/activity`
+    pub fn create_activity_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_activity_uri(config, &self.note_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activitypub::{activity::generate_activity_uri, audience::Audience};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_note_uri() {
+        let config = AppConfig::new("https://example.com");
+        let note = CreateNote::new(UserId::new(), "hello".to_string());
+
+        let note_uri = note.note_uri(&config);
+        assert_eq!(parse_note_uri(&config, &note_uri), Some(note.note_id));
+
+        let remote = "https://remote.example/notes/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69"
+            .parse()
+            .unwrap();
+        assert_eq!(parse_note_uri(&config, &remote), None);
+
+        let not_note = "https://example.com/users/alice".parse().unwrap();
+        assert_eq!(parse_note_uri(&config, &not_note), None);
+    }
+
+    #[test]
+    fn test_addressing() {
+        let config = AppConfig::new("https://example.com");
+        let author = User {
+            id: UserId::new(),
+            name: "alice".to_string(),
+        };
+        let public = NoteObject::public_address().clone();
+        let followers = author.followers_uri(&config);
+
+        let (to, cc) = Visibility::Public.addressing(&config, &author);
+        assert_eq!((to, cc), (vec![public.clone()], vec![followers.clone()]));
+
+        let (to, cc) = Visibility::Followers.addressing(&config, &author);
+        assert_eq!((to, cc), (vec![followers], vec![]));
+
+        assert_eq!(
+            "unlisted".parse::<Visibility>().unwrap(),
+            Visibility::Unlisted
+        );
+        assert!("direct".parse::<Visibility>().is_err());
+    }
+
+    #[test]
+    fn test_update_addressing() {
+        let config = AppConfig::new("https://example.com");
+        let author = User {
+            id: UserId::new(),
+            name: "alice".to_string(),
+        };
+        let note = Note {
+            id: NoteId::new(),
+            user_id: author.id.clone(),
+            content: "<p>hello</p>".to_string(),
+            published: "2024-12-01T00:00:00Z".to_string(),
+            updated: None,
+            visibility: Visibility::Followers,
+        };
+        let id = generate_activity_uri(&config);
+
+        // フォロワー限定の`Note`の`Update`は誰でも取得できるようにしない
+        let update = serde_json::to_value(note.to_update(&config, &author, id.clone())).unwrap();
+        let audience = Audience::from_value(&update);
+        assert!(!audience.is_public());
+        assert!(audience.contains(&author.followers_uri(&config)));
+
+        let note = Note {
+            visibility: Visibility::Public,
+            ..note
+        };
+        let update = serde_json::to_value(note.to_update(&config, &author, id)).unwrap();
+        assert!(Audience::from_value(&update).is_public());
+    }
+
+    #[test]
+    fn test_update_carries_updated() {
+        let config = AppConfig::new("https://example.com");
+        let author = User {
+            id: UserId::new(),
+            name: "alice".to_string(),
+        };
+        let note = Note {
+            id: NoteId::new(),
+            user_id: author.id.clone(),
+            content: "<p>hello</p>".to_string(),
+            published: "2024-12-01T00:00:00Z".to_string(),
+            updated: None,
+            visibility: Visibility::Public,
+        };
+        let created = serde_json::to_value(note.to_note(&config, &author)).unwrap();
+        assert!(created.get("updated").is_none());
+
+        // 編集した`Note`には`updated`を付ける
+        let note = Note {
+            updated: Some("2024-12-02T00:00:00Z".to_string()),
+            ..note
+        };
+        let update = note.to_update(&config, &author, generate_activity_uri(&config));
+        let update = serde_json::to_value(update).unwrap();
+        assert_eq!(update["object"]["updated"], "2024-12-02T00:00:00Z");
+    }
 }
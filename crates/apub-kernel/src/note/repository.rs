@@ -1,11 +1,29 @@
-use crate::user::model::UserId;
+use crate::{
+    pagination::{Cursor, Page},
+    user::model::UserId,
+};
 
-use super::model::{CreateNote, Note, NoteId};
+use super::model::{CreateNote, DeletedNote, Note, NoteId};
 
 #[async_trait::async_trait]
 pub trait NoteRepository: Send + Sync {
     async fn find(&self, note_id: &NoteId) -> anyhow::Result<Note>;
-    async fn list_user_notes(&self, user_id: &UserId) -> anyhow::Result<Vec<Note>>;
+    /// `user_id`の誰でも見られる`Note`を新しい順に`cursor`から`limit`件探す
+    ///
+    /// フォロワー限定の`Note`は`outbox`に載せないので含めない
+    async fn list_user_notes(
+        &self,
+        user_id: &UserId,
+        cursor: Cursor,
+        limit: i64,
+    ) -> anyhow::Result<Page<Note>>;
+    /// `list_user_notes`で探せる`Note`の数
+    async fn count_user_notes(&self, user_id: &UserId) -> anyhow::Result<i64>;
     async fn create(&self, event: &CreateNote) -> anyhow::Result<()>;
+    /// `Note`の本文を書き換える
+    async fn update(&self, note_id: &NoteId, content: &str) -> anyhow::Result<()>;
+    /// 削除された`Note`を探す
+    async fn find_deleted(&self, note_id: &NoteId) -> anyhow::Result<DeletedNote>;
+    /// `Note`を削除し、`Tombstone`を残す
     async fn delete(&self, note_id: &NoteId) -> anyhow::Result<()>;
 }
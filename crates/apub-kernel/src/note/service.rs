@@ -0,0 +1,62 @@
+use std::{future::Future, sync::Arc};
+
+use apub_config::AppConfig;
+
+use crate::{delivery::service::DeliveryService, user::model::User};
+
+use super::{
+    model::{CreateNote, Note, Visibility},
+    repository::NoteRepository,
+};
+
+pub trait NoteService: Send + Sync {
+    /// `Note`を保存し、フォロワーへ`Create`を送る
+    fn create(
+        &self,
+        user: &User,
+        content: String,
+        visibility: Visibility,
+    ) -> impl Future<Output = anyhow::Result<Note>>;
+}
+
+pub struct NoteServiceImpl<DeliveryServ, NoteRepo> {
+    delivery: DeliveryServ,
+    note: NoteRepo,
+    config: Arc<AppConfig>,
+}
+
+impl<DeliveryServ, NoteRepo> NoteServiceImpl<DeliveryServ, NoteRepo> {
+    pub fn new(delivery: DeliveryServ, note: NoteRepo, config: Arc<AppConfig>) -> Self {
+        Self {
+            delivery,
+            note,
+            config,
+        }
+    }
+}
+
+impl<DeliveryServ, NoteRepo> NoteService for NoteServiceImpl<DeliveryServ, NoteRepo>
+where
+    DeliveryServ: DeliveryService,
+    NoteRepo: NoteRepository,
+{
+    #[tracing::instrument(skip(self, user, content), fields(user = user.name))]
+    async fn create(
+        &self,
+        user: &User,
+        content: String,
+        visibility: Visibility,
+    ) -> anyhow::Result<Note> {
+        let create_note = CreateNote::new(user.id.clone(), content).with_visibility(visibility);
+        self.note.create(&create_note).await?;
+        // `published`はDBで決まるので読み直す
+        let note = self.note.find(&create_note.note_id).await?;
+
+        let create = note.to_create(&self.config, user);
+        tracing::info!(create = ?create);
+
+        self.delivery.deliver_to_followers(user, &create).await?;
+
+        Ok(note)
+    }
+}
@@ -0,0 +1,110 @@
+/// 1ページに含める件数
+pub const PAGE_SIZE: i64 = 20;
+
+/// キーセットページネーションの位置
+///
+/// 新しいものから順に並べたときの位置をIDで表す
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cursor {
+    /// 最新から
+    #[default]
+    Latest,
+    /// このIDより古いもの
+    Before(i64),
+    /// このIDより新しいもの
+    After(i64),
+}
+
+impl Cursor {
+    /// `max_id`と`min_id`から作る。両方ある場合は`max_id`を優先する
+    pub fn new(max_id: Option<i64>, min_id: Option<i64>) -> Self {
+        match (max_id, min_id) {
+            (Some(id), _) => Cursor::Before(id),
+            (None, Some(id)) => Cursor::After(id),
+            (None, None) => Cursor::Latest,
+        }
+    }
+
+    pub fn max_id(&self) -> Option<i64> {
+        match self {
+            Cursor::Before(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn min_id(&self) -> Option<i64> {
+        match self {
+            Cursor::After(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// 新しいものから順に並べた1ページ分の項目
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// より古い項目を取得するときの`max_id`
+    pub next: Option<i64>,
+    /// より新しい項目を取得するときの`min_id`
+    pub prev: Option<i64>,
+}
+
+impl<T> Page<T> {
+    /// `cursor`の向きに並んだ最大`limit + 1`件の`(ID, 項目)`からページを作る
+    ///
+    /// `Cursor::After`の場合は古いものから、それ以外は新しいものから並んでいること
+    pub fn from_rows(mut rows: Vec<(i64, T)>, cursor: Cursor, limit: i64) -> Self {
+        let limit = usize::try_from(limit).unwrap_or_default();
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        if let Cursor::After(_) = cursor {
+            rows.reverse();
+        }
+
+        let newest = rows.first().map(|(id, _)| *id);
+        let oldest = rows.last().map(|(id, _)| *id);
+        let (next, prev) = match cursor {
+            Cursor::Latest => (oldest.filter(|_| has_more), None),
+            Cursor::Before(_) => (oldest.filter(|_| has_more), newest),
+            Cursor::After(_) => (oldest, newest.filter(|_| has_more)),
+        };
+
+        Self {
+            items: rows.into_iter().map(|(_, item)| item).collect(),
+            next,
+            prev,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_latest_page() {
+        let page = Page::from_rows(vec![(5, "e"), (4, "d"), (3, "c")], Cursor::Latest, 2);
+        assert_eq!(page.items, vec!["e", "d"]);
+        assert_eq!(page.next, Some(4));
+        assert_eq!(page.prev, None);
+    }
+
+    #[test]
+    fn test_last_page() {
+        let page = Page::from_rows(vec![(2, "b"), (1, "a")], Cursor::Before(3), 2);
+        assert_eq!(page.items, vec!["b", "a"]);
+        assert_eq!(page.next, None);
+        assert_eq!(page.prev, Some(2));
+    }
+
+    #[test]
+    fn test_prev_page() {
+        // `min_id`より新しいものは古い順に返ってくる
+        let page = Page::from_rows(vec![(3, "c"), (4, "d"), (5, "e")], Cursor::After(2), 2);
+        assert_eq!(page.items, vec!["d", "c"]);
+        assert_eq!(page.next, Some(3));
+        assert_eq!(page.prev, Some(4));
+    }
+}
@@ -1,5 +1,14 @@
 pub use crate::activitypub::{activity::ActivityRepository, service::ActivityService};
 
+pub use crate::delivery::{repository::DeliveryRepository, service::DeliveryService};
+pub use crate::follow_request::repository::FollowRequestRepository;
 pub use crate::follower::repository::FollowerRepository;
-pub use crate::rsa_key::repository::RsaKeyRepository;
+pub use crate::following::{repository::FollowingRepository, service::FollowingService};
+pub use crate::inbox::repository::InboxRepository;
+pub use crate::instance::repository::InstanceRepository;
+pub use crate::like::repository::LikeRepository;
+pub use crate::note::service::NoteService;
+pub use crate::rsa_key::{pool::RsaKeyPool, repository::RsaKeyRepository};
+pub use crate::sent_activity::repository::SentActivityRepository;
+pub use crate::share::repository::ShareRepository;
 pub use crate::user::service::UserService;
@@ -0,0 +1,173 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use sha2::{Digest, Sha256, Sha512};
+
+/// `Digest`ヘッダの検証で起きるエラー
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DigestError {
+    /// `Digest`ヘッダの書式が不正
+    #[error("malformed digest header")]
+    Malformed,
+    /// 対応しているアルゴリズムのダイジェストがない
+    #[error("no supported digest algorithm")]
+    Unsupported,
+    /// ボディのダイジェストと一致しない
+    #[error("digest does not match the body")]
+    Mismatch,
+}
+
+/// ボディから`Digest`ヘッダの値を作る
+pub fn digest_header(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64_STANDARD.encode(Sha256::digest(body)))
+}
+
+/// ボディから`Content-Digest`ヘッダの値を作る
+///
+/// See https://www.rfc-editor.org/rfc/rfc9530.html
+pub fn content_digest_header(body: &[u8]) -> String {
+    format!("sha-256=:{}:", BASE64_STANDARD.encode(Sha256::digest(body)))
+}
+
+/// `Digest`ヘッダがボディと一致するか検証する
+///
+/// `SHA-256`と`SHA-512`に対応し、対応しているものはすべて一致する必要がある
+///
+/// See
+/// - https://datatracker.ietf.org/doc/html/rfc3230#section-4.3.2
+/// - https://docs.joinmastodon.org/spec/security/#digest
+pub fn verify_digest(digest_header: &str, body: &[u8]) -> Result<(), DigestError> {
+    let mut verified = false;
+
+    for instance in digest_header.split(',') {
+        let (algorithm, value) = instance
+            .trim()
+            .split_once('=')
+            .ok_or(DigestError::Malformed)?;
+
+        let expected = match algorithm.to_ascii_uppercase().as_str() {
+            "SHA-256" => Sha256::digest(body).to_vec(),
+            "SHA-512" => Sha512::digest(body).to_vec(),
+            _ => continue,
+        };
+
+        let actual = BASE64_STANDARD
+            .decode(value)
+            .map_err(|_| DigestError::Malformed)?;
+        if actual != expected {
+            return Err(DigestError::Mismatch);
+        }
+        verified = true;
+    }
+
+    if verified {
+        Ok(())
+    } else {
+        Err(DigestError::Unsupported)
+    }
+}
+
+/// `Content-Digest`ヘッダがボディと一致するか検証する
+///
+/// `sha-256`と`sha-512`に対応し、対応しているものはすべて一致する必要がある
+///
+/// See https://www.rfc-editor.org/rfc/rfc9530.html#section-2
+pub fn verify_content_digest(content_digest: &str, body: &[u8]) -> Result<(), DigestError> {
+    let mut verified = false;
+
+    for member in content_digest.split(',') {
+        let (algorithm, value) = member
+            .trim()
+            .split_once('=')
+            .ok_or(DigestError::Malformed)?;
+
+        let expected = match algorithm {
+            "sha-256" => Sha256::digest(body).to_vec(),
+            "sha-512" => Sha512::digest(body).to_vec(),
+            _ => continue,
+        };
+
+        let value = value
+            .strip_prefix(':')
+            .and_then(|v| v.strip_suffix(':'))
+            .ok_or(DigestError::Malformed)?;
+        let actual = BASE64_STANDARD
+            .decode(value)
+            .map_err(|_| DigestError::Malformed)?;
+        if actual != expected {
+            return Err(DigestError::Mismatch);
+        }
+        verified = true;
+    }
+
+    if verified {
+        Ok(())
+    } else {
+        Err(DigestError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    const BODY: &[u8] = br#"{"hello": "world"}"#;
+
+    #[rstest]
+    #[case("SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=")]
+    #[case("sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=")]
+    #[case("SHA-512=WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==")]
+    #[case("MD5=abc, SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=")]
+    fn test_valid_digest(#[case] header: &str) {
+        assert_eq!(verify_digest(header, BODY), Ok(()))
+    }
+
+    #[rstest]
+    #[case(
+        "SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=",
+        b"{}",
+        DigestError::Mismatch
+    )]
+    #[case("MD5=Sd/dVLAcvNLSq16eXua5uQ==", BODY, DigestError::Unsupported)]
+    #[case("SHA-256", BODY, DigestError::Malformed)]
+    fn test_invalid_digest(#[case] header: &str, #[case] body: &[u8], #[case] err: DigestError) {
+        assert_eq!(verify_digest(header, body), Err(err))
+    }
+
+    #[rstest]
+    #[case("sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:")]
+    #[case("sha-512=:WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:")]
+    #[case("unixsum=:MTIzNA==:, sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:")]
+    fn test_valid_content_digest(#[case] header: &str) {
+        assert_eq!(verify_content_digest(header, BODY), Ok(()))
+    }
+
+    #[rstest]
+    #[case(
+        "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:",
+        b"{}",
+        DigestError::Mismatch
+    )]
+    #[case(
+        "sha-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=",
+        BODY,
+        DigestError::Malformed
+    )]
+    #[case("md5=:Sd/dVLAcvNLSq16eXua5uQ==:", BODY, DigestError::Unsupported)]
+    fn test_invalid_content_digest(
+        #[case] header: &str,
+        #[case] body: &[u8],
+        #[case] err: DigestError,
+    ) {
+        assert_eq!(verify_content_digest(header, body), Err(err))
+    }
+
+    #[test]
+    fn test_make_digest() {
+        assert_eq!(verify_digest(&digest_header(BODY), BODY), Ok(()));
+        assert_eq!(
+            verify_content_digest(&content_digest_header(BODY), BODY),
+            Ok(())
+        );
+    }
+}
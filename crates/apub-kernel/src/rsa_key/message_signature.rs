@@ -0,0 +1,401 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::http::{HeaderMap, Method};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+use super::signature::SignatureError;
+
+/// RFC 9421の`Signature-Input`と`Signature`の組
+///
+/// See https://www.rfc-editor.org/rfc/rfc9421.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSignature {
+    pub label: String,
+    /// 署名されたコンポーネントの一覧(小文字)
+    pub components: Vec<String>,
+    pub key_id: ResourceUrl,
+    pub algorithm: Option<String>,
+    pub created: Option<u64>,
+    pub expires: Option<u64>,
+    pub signature: Vec<u8>,
+    /// 署名ベースの`@signature-params`に使う値
+    params: String,
+}
+
+impl MessageSignature {
+    /// 署名前の`MessageSignature`を作る
+    pub fn new(
+        label: &str,
+        components: Vec<String>,
+        key_id: ResourceUrl,
+        algorithm: Option<String>,
+        created: Option<u64>,
+    ) -> Self {
+        let mut params = format!(
+            "({})",
+            components
+                .iter()
+                .map(|v| format!(r#""{}""#, v))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        if let Some(created) = created {
+            params.push_str(&format!(";created={}", created));
+        }
+        params.push_str(&format!(r#";keyid="{}""#, key_id));
+        if let Some(algorithm) = &algorithm {
+            params.push_str(&format!(r#";alg="{}""#, algorithm));
+        }
+
+        Self {
+            label: label.to_string(),
+            components,
+            key_id,
+            algorithm,
+            created,
+            expires: None,
+            signature: Vec::new(),
+            params,
+        }
+    }
+
+    /// `Signature-Input`と`Signature`から最初に揃っている署名を取り出す
+    pub fn from_headers(signature_input: &str, signature: &str) -> Result<Self, SignatureError> {
+        let signatures = split_members(signature)?
+            .into_iter()
+            .map(|(label, value)| {
+                let value = value
+                    .strip_prefix(':')
+                    .and_then(|v| v.strip_suffix(':'))
+                    .ok_or(SignatureError::Malformed)?;
+                let value = BASE64_STANDARD
+                    .decode(value)
+                    .map_err(|_| SignatureError::InvalidSignature)?;
+                Ok((label, value))
+            })
+            .collect::<Result<Vec<_>, SignatureError>>()?;
+
+        for (label, input) in split_members(signature_input)? {
+            let Some((_, value)) = signatures.iter().find(|(l, _)| *l == label) else {
+                continue;
+            };
+            return Self::parse_input(label, input, value.clone());
+        }
+
+        Err(SignatureError::MissingParameter("signature"))
+    }
+
+    fn parse_input(label: &str, input: &str, signature: Vec<u8>) -> Result<Self, SignatureError> {
+        let list = input.strip_prefix('(').ok_or(SignatureError::Malformed)?;
+        let end = list.find(')').ok_or(SignatureError::Malformed)?;
+        let (items, params) = (&list[..end], &list[end + 1..]);
+
+        let components = items
+            .split_ascii_whitespace()
+            .map(|item| {
+                item.strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .map(|v| v.to_lowercase())
+                    .ok_or(SignatureError::Malformed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut key_id = None;
+        let mut algorithm = None;
+        let mut created = None;
+        let mut expires = None;
+        for param in params.split(';').filter(|v| !v.is_empty()) {
+            let (key, value) = param.split_once('=').ok_or(SignatureError::Malformed)?;
+            let string = || {
+                value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .ok_or(SignatureError::Malformed)
+            };
+            let integer = || value.parse::<u64>().map_err(|_| SignatureError::Malformed);
+            match key {
+                "keyid" => key_id = Some(string()?),
+                "alg" => algorithm = Some(string()?.to_string()),
+                "created" => created = Some(integer()?),
+                "expires" => expires = Some(integer()?),
+                // `nonce`や`tag`は使わない
+                _ => {}
+            }
+        }
+
+        let key_id = key_id
+            .ok_or(SignatureError::MissingParameter("keyid"))?
+            .parse::<ResourceUrl>()
+            .map_err(|_| SignatureError::InvalidKeyId)?;
+
+        Ok(Self {
+            label: label.to_string(),
+            components,
+            key_id,
+            algorithm,
+            created,
+            expires,
+            signature,
+            params: input.to_string(),
+        })
+    }
+
+    /// `Signature-Input`ヘッダの値
+    pub fn signature_input(&self) -> String {
+        format!("{}={}", self.label, self.params)
+    }
+
+    /// `Signature`ヘッダの値
+    pub fn signature_header(&self) -> String {
+        format!(
+            "{}=:{}:",
+            self.label,
+            BASE64_STANDARD.encode(&self.signature)
+        )
+    }
+
+    /// `name`が署名対象に含まれているか
+    pub fn is_signed(&self, name: &str) -> bool {
+        self.components.iter().any(|v| v == name)
+    }
+
+    /// `created`が`now`の前後`skew`以内にあるか検証する
+    pub fn verify_time(&self, now: SystemTime, skew: Duration) -> Result<(), SignatureError> {
+        if let Some(expires) = self.expires {
+            if UNIX_EPOCH + Duration::from_secs(expires) < now {
+                return Err(SignatureError::Expired);
+            }
+        }
+
+        let created = UNIX_EPOCH
+            + Duration::from_secs(
+                self.created
+                    .ok_or(SignatureError::MissingParameter("created"))?,
+            );
+        let within_skew = match created.duration_since(now) {
+            Ok(ahead) => ahead <= skew,
+            Err(behind) => behind.duration() <= skew,
+        };
+        within_skew.then_some(()).ok_or(SignatureError::OutOfWindow)
+    }
+
+    /// リクエストから署名ベースを組み立てる
+    ///
+    /// See https://www.rfc-editor.org/rfc/rfc9421.html#section-2.5
+    pub fn signature_base(
+        &self,
+        method: &Method,
+        target_uri: &ResourceUrl,
+        headers: &HeaderMap,
+    ) -> Result<String, SignatureError> {
+        let mut lines = self
+            .components
+            .iter()
+            .map(|name| {
+                let value = match name.as_str() {
+                    "@method" => method.as_str().to_string(),
+                    "@target-uri" => target_uri.as_str().to_string(),
+                    "@authority" => authority(target_uri),
+                    "@scheme" => target_uri.scheme().to_string(),
+                    "@path" => target_uri.path().to_string(),
+                    "@query" => format!("?{}", target_uri.query().unwrap_or_default()),
+                    "@request-target" => match target_uri.query() {
+                        Some(query) => format!("{}?{}", target_uri.path(), query),
+                        None => target_uri.path().to_string(),
+                    },
+                    _ if name.starts_with('@') => {
+                        return Err(SignatureError::MissingHeader(name.clone()))
+                    }
+                    _ => {
+                        let values = headers
+                            .get_all(name.as_str())
+                            .iter()
+                            .map(|v| v.to_str().map(str::trim))
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|_| SignatureError::MissingHeader(name.clone()))?;
+                        if values.is_empty() {
+                            return Err(SignatureError::MissingHeader(name.clone()));
+                        }
+                        values.join(", ")
+                    }
+                };
+                Ok(format!(r#""{}": {}"#, name, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        lines.push(format!(r#""@signature-params": {}"#, self.params));
+        Ok(lines.join("\n"))
+    }
+}
+
+/// `Host`ヘッダと同じ形式の`host[:port]`
+pub(crate) fn authority(url: &ResourceUrl) -> String {
+    match url.port() {
+        Some(port) => format!("{}:{}", url.host(), port),
+        None => url.host().to_string(),
+    }
+}
+
+/// 構造化フィールドのディクショナリを`(key, value)`に分割する
+///
+/// See https://www.rfc-editor.org/rfc/rfc8941.html#section-3.2
+fn split_members(s: &str) -> Result<Vec<(&str, &str)>, SignatureError> {
+    let mut members = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut depth = 0usize;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth = depth.checked_sub(1).ok_or(SignatureError::Malformed)?,
+            ',' if !in_string && depth == 0 => {
+                members.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if in_string || depth != 0 {
+        return Err(SignatureError::Malformed);
+    }
+    members.push(&s[start..]);
+
+    members
+        .into_iter()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|member| {
+            let (key, value) = member.split_once('=').ok_or(SignatureError::Malformed)?;
+            Ok((key.trim(), value.trim()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header, HeaderValue};
+    use pretty_assertions::assert_eq;
+
+    // https://www.rfc-editor.org/rfc/rfc9421.html#section-2.5
+    const SIGNATURE_INPUT: &str = r#"sig1=("@method" "@authority" "@path" "content-digest" "content-length" "content-type");created=1618884473;keyid="https://example.com/users/alice#main-key""#;
+
+    fn request_headers() -> HeaderMap {
+        HeaderMap::from_iter([
+            (header::HOST, HeaderValue::from_static("example.com")),
+            (
+                header::DATE,
+                HeaderValue::from_static("Tue, 20 Apr 2021 02:07:55 GMT"),
+            ),
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (
+                header::HeaderName::from_static("content-digest"),
+                HeaderValue::from_static("sha-512=:WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:"),
+            ),
+            (header::CONTENT_LENGTH, HeaderValue::from_static("18")),
+        ])
+    }
+
+    #[test]
+    fn test_parse_message_signature() {
+        let signature =
+            MessageSignature::from_headers(SIGNATURE_INPUT, "sig1=:Y2FiYWIxNGRiZDk4ZA==:").unwrap();
+
+        assert_eq!(signature.label, "sig1");
+        assert_eq!(
+            signature.components,
+            vec![
+                "@method",
+                "@authority",
+                "@path",
+                "content-digest",
+                "content-length",
+                "content-type"
+            ]
+        );
+        assert_eq!(
+            signature.key_id.as_str(),
+            "https://example.com/users/alice#main-key"
+        );
+        assert_eq!(signature.created, Some(1618884473));
+        assert_eq!(signature.signature_input(), SIGNATURE_INPUT);
+    }
+
+    #[test]
+    fn test_parse_multiple_signatures() {
+        let input = format!(
+            r#"proxy=("@method");created=1618884480;keyid="https://proxy.example/key", {}"#,
+            SIGNATURE_INPUT
+        );
+        let signature =
+            MessageSignature::from_headers(&input, "sig1=:Y2FiYWIxNGRiZDk4ZA==:").unwrap();
+
+        assert_eq!(signature.label, "sig1");
+
+        let missing = MessageSignature::from_headers(&input, "sig2=:Y2FiYWIxNGRiZDk4ZA==:");
+        assert_eq!(missing, Err(SignatureError::MissingParameter("signature")));
+    }
+
+    #[test]
+    fn test_signature_base() {
+        let signature =
+            MessageSignature::from_headers(SIGNATURE_INPUT, "sig1=:Y2FiYWIxNGRiZDk4ZA==:").unwrap();
+        let target = "https://example.com/foo?param=Value&Pet=dog"
+            .parse::<ResourceUrl>()
+            .unwrap();
+
+        let base = signature
+            .signature_base(&Method::POST, &target, &request_headers())
+            .unwrap();
+
+        assert_eq!(
+            base,
+            r#""@method": POST
+"@authority": example.com
+"@path": /foo
+"content-digest": sha-512=:WZDPaVn/7XgHaAy8pmojAkGWoRx2UFChF41A2svX+TaPm+AbwAgBWnrIiYllu7BNNyealdVLvRwEmTHWXvJwew==:
+"content-length": 18
+"content-type": application/json
+"@signature-params": ("@method" "@authority" "@path" "content-digest" "content-length" "content-type");created=1618884473;keyid="https://example.com/users/alice#main-key""#
+        );
+    }
+
+    #[test]
+    fn test_new_message_signature() {
+        let signature = MessageSignature::new(
+            "sig1",
+            vec!["@method".to_string(), "@target-uri".to_string()],
+            "https://example.com/users/alice#main-key".parse().unwrap(),
+            Some("rsa-v1_5-sha256".to_string()),
+            Some(1618884473),
+        );
+
+        assert_eq!(
+            signature.signature_input(),
+            r#"sig1=("@method" "@target-uri");created=1618884473;keyid="https://example.com/users/alice#main-key";alg="rsa-v1_5-sha256""#
+        );
+        let parsed =
+            MessageSignature::from_headers(&signature.signature_input(), "sig1=::").unwrap();
+        assert_eq!(parsed, signature);
+    }
+
+    #[test]
+    fn test_verify_created() {
+        let signature =
+            MessageSignature::from_headers(SIGNATURE_INPUT, "sig1=:Y2FiYWIxNGRiZDk4ZA==:").unwrap();
+        let created = UNIX_EPOCH + Duration::from_secs(1618884473);
+        let skew = Duration::from_secs(300);
+
+        assert_eq!(signature.verify_time(created, skew), Ok(()));
+        assert_eq!(
+            signature.verify_time(created + Duration::from_secs(301), skew),
+            Err(SignatureError::OutOfWindow)
+        );
+    }
+}
@@ -1,2 +1,7 @@
+pub mod digest;
+pub mod http_signature;
+pub mod message_signature;
 pub mod model;
+pub mod pool;
 pub mod repository;
+pub mod signature;
@@ -17,6 +17,20 @@ pub trait KeyType {
     fn key_type() -> &'static str;
 }
 
+/// HTTP Signatureの署名に使える秘密鍵
+pub trait HttpSigningKey: std::fmt::Debug + Send + Sync {
+    fn sign(&self, msg: &[u8]) -> Box<[u8]>;
+    /// draft-cavageの`algorithm`
+    fn cavage_algorithm(&self) -> &'static str;
+    /// RFC 9421の`alg`
+    fn rfc9421_algorithm(&self) -> &'static str;
+}
+
+/// HTTP Signatureの検証に使える公開鍵
+pub trait HttpVerifyingKey: std::fmt::Debug + Send + Sync {
+    fn verify(&self, msg: &[u8], signature: &[u8]) -> anyhow::Result<()>;
+}
+
 #[derive(Debug, Clone)]
 pub struct RsaVerifyingKey {
     verifying_key: VerifyingKey<Sha256>,
@@ -67,6 +81,12 @@ impl RsaVerifyingKey {
     }
 }
 
+impl HttpVerifyingKey for RsaVerifyingKey {
+    fn verify(&self, msg: &[u8], signature: &[u8]) -> anyhow::Result<()> {
+        RsaVerifyingKey::verify(self, msg, signature)
+    }
+}
+
 impl FromStr for RsaVerifyingKey {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -90,11 +110,21 @@ pub struct RsaSingingKey {
 }
 
 impl RsaSingingKey {
+    /// 鍵の長さの既定値
+    pub const DEFAULT_KEY_BITS: usize = 4096;
+
     #[tracing::instrument]
     pub fn new() -> anyhow::Result<Self> {
-        const KEY_BITS: usize = 4096;
+        Self::with_bits(Self::DEFAULT_KEY_BITS)
+    }
+
+    /// `bits`の長さの鍵を作る
+    ///
+    /// 数秒かかることがあるので、非同期の処理からは`spawn_blocking`などで呼ぶ
+    #[tracing::instrument]
+    pub fn with_bits(bits: usize) -> anyhow::Result<Self> {
         let mut rng = rand::thread_rng();
-        let private_key = RsaPrivateKey::new(&mut rng, KEY_BITS)?;
+        let private_key = RsaPrivateKey::new(&mut rng, bits)?;
         let signing_key = SigningKey::<Sha256>::new(private_key);
         Ok(Self { signing_key })
     }
@@ -151,6 +181,171 @@ impl FromStr for RsaSingingKey {
     }
 }
 
+impl HttpSigningKey for RsaSingingKey {
+    fn sign(&self, msg: &[u8]) -> Box<[u8]> {
+        RsaSingingKey::sign(self, msg)
+    }
+
+    fn cavage_algorithm(&self) -> &'static str {
+        "rsa-sha256"
+    }
+
+    fn rfc9421_algorithm(&self) -> &'static str {
+        "rsa-v1_5-sha256"
+    }
+}
+
+/// Multikeyで公開するEd25519の公開鍵
+///
+/// See https://codeberg.org/fediverse/fep/src/branch/main/fep/521a/fep-521a.md
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ed25519VerifyingKey {
+    verifying_key: ed25519_dalek::VerifyingKey,
+}
+
+impl KeyType for Ed25519VerifyingKey {
+    fn key_type() -> &'static str {
+        "ed25519-key"
+    }
+}
+
+impl Ed25519VerifyingKey {
+    /// multicodecの`ed25519-pub`
+    const MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+    #[tracing::instrument(skip(self))]
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> anyhow::Result<()> {
+        let signature = ed25519_dalek::Signature::from_slice(signature)?;
+        self.verifying_key.verify_strict(msg, &signature)?;
+
+        Ok(())
+    }
+
+    /// `publicKeyMultibase`の値から読み込む
+    ///
+    /// base58btcの`z`で始まり、multicodecの`ed25519-pub`が前に付いている必要がある
+    pub fn from_multibase(multibase: &str) -> anyhow::Result<Self> {
+        let encoded = multibase
+            .strip_prefix('z')
+            .ok_or_else(|| anyhow::anyhow!("multibase is not base58btc"))?;
+        let decoded = bs58::decode(encoded).into_vec()?;
+        let key = decoded
+            .strip_prefix(&Self::MULTICODEC)
+            .ok_or_else(|| anyhow::anyhow!("multikey is not ed25519-pub"))?;
+        let verifying_key = ed25519_dalek::VerifyingKey::try_from(key)?;
+        Ok(Self { verifying_key })
+    }
+
+    /// `publicKeyMultibase`の値
+    pub fn to_multibase(&self) -> String {
+        let mut bytes = Self::MULTICODEC.to_vec();
+        bytes.extend_from_slice(self.verifying_key.as_bytes());
+        format!("z{}", bs58::encode(bytes).into_string())
+    }
+}
+
+impl HttpVerifyingKey for Ed25519VerifyingKey {
+    fn verify(&self, msg: &[u8], signature: &[u8]) -> anyhow::Result<()> {
+        Ed25519VerifyingKey::verify(self, msg, signature)
+    }
+}
+
+impl FromStr for Ed25519VerifyingKey {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_multibase(s)
+    }
+}
+
+impl std::fmt::Display for Ed25519VerifyingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_multibase())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ed25519SigningKey {
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519SigningKey {
+    #[tracing::instrument]
+    pub fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let signing_key = ed25519_dalek::SigningKey::generate(&mut rng);
+        Self { signing_key }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn sign(&self, msg: &[u8]) -> Box<[u8]> {
+        use ed25519_dalek::Signer as _;
+        let signature = self.signing_key.sign(msg);
+        signature.to_bytes().into()
+    }
+
+    pub fn from_pkcs8(pem: &str) -> anyhow::Result<Self> {
+        use ed25519_dalek::pkcs8::DecodePrivateKey as _;
+        let signing_key = ed25519_dalek::SigningKey::from_pkcs8_pem(pem)?;
+        Ok(Self { signing_key })
+    }
+
+    pub fn to_pkcs8(&self) -> anyhow::Result<String> {
+        use ed25519_dalek::pkcs8::EncodePrivateKey as _;
+        self.signing_key
+            .to_pkcs8_pem(rsa::pkcs8::LineEnding::LF)
+            .map_err(|e| e.into())
+            .map(|pem| pem.to_string())
+    }
+
+    pub fn to_public_key(&self) -> Ed25519VerifyingKey {
+        let verifying_key = self.signing_key.verifying_key();
+        Ed25519VerifyingKey { verifying_key }
+    }
+}
+
+impl Default for Ed25519SigningKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromStr for Ed25519SigningKey {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_pkcs8(s)
+    }
+}
+
+impl HttpSigningKey for Ed25519SigningKey {
+    fn sign(&self, msg: &[u8]) -> Box<[u8]> {
+        Ed25519SigningKey::sign(self, msg)
+    }
+
+    fn cavage_algorithm(&self) -> &'static str {
+        "hs2019"
+    }
+
+    fn rfc9421_algorithm(&self) -> &'static str {
+        "ed25519"
+    }
+}
+
+/// `Actor`が持つ公開鍵
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct ActorPublicKey {
+    pub actor_url: ResourceUrl,
+    pub key_url: ResourceUrl,
+    pub public_key: RsaVerifyingKey,
+}
+
+/// `Actor`が持つEd25519の公開鍵
+#[derive(Debug, Clone, TypedBuilder)]
+pub struct ActorEd25519PublicKey {
+    pub actor_url: ResourceUrl,
+    pub key_url: ResourceUrl,
+    pub public_key: Ed25519VerifyingKey,
+}
+
 #[derive(Debug, TypedBuilder)]
 pub struct SavePublicKeyEvent<'a> {
     pub public_key: &'a RsaVerifyingKey,
@@ -165,3 +360,49 @@ pub struct SaveKeyPairEvent<'a> {
     pub actor_id: &'a ActorId,
     pub key_url: &'a ResourceUrl,
 }
+
+#[derive(Debug, TypedBuilder)]
+pub struct SaveEd25519PublicKeyEvent<'a> {
+    pub public_key: &'a Ed25519VerifyingKey,
+    pub actor_id: &'a ActorId,
+    pub key_url: &'a ResourceUrl,
+}
+
+#[derive(Debug, TypedBuilder)]
+pub struct SaveEd25519KeyPairEvent<'a> {
+    pub public_key: &'a Ed25519VerifyingKey,
+    pub private_key: &'a Ed25519SigningKey,
+    pub actor_id: &'a ActorId,
+    pub key_url: &'a ResourceUrl,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ed25519_multibase() {
+        // https://codeberg.org/fediverse/fep/src/branch/main/fep/521a/fep-521a.md#example
+        let multibase = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+        let key = Ed25519VerifyingKey::from_multibase(multibase).unwrap();
+        assert_eq!(key.to_multibase(), multibase);
+
+        assert!(Ed25519VerifyingKey::from_multibase(
+            "6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_ed25519_sign() {
+        let skey = Ed25519SigningKey::new();
+        let pem = skey.to_pkcs8().unwrap();
+        let skey = Ed25519SigningKey::from_pkcs8(&pem).unwrap();
+
+        let pkey = skey.to_public_key();
+        let signature = skey.sign(b"hello");
+        pkey.verify(b"hello", &signature).unwrap();
+        assert!(pkey.verify(b"world", &signature).is_err());
+    }
+}
@@ -0,0 +1,12 @@
+use super::model::RsaSingingKey;
+
+/// ユーザを作るときに使うRSA鍵を作っておく場所
+///
+/// 鍵を作るのには時間がかかるので、ユーザを作るたびに作らないようにする
+#[async_trait::async_trait]
+pub trait RsaKeyPool: Send + Sync {
+    /// 作っておいた鍵を1つ取り出す
+    ///
+    /// 空のときはその場で作る
+    async fn take(&self) -> anyhow::Result<RsaSingingKey>;
+}
@@ -1,15 +1,104 @@
-use crate::user::model::UserId;
+use std::time::Duration;
 
-use super::model::{RsaSingingKey, RsaVerifyingKey, SaveKeyPairEvent, SavePublicKeyEvent};
+use apub_shared::model::resource_url::ResourceUrl;
+
+use crate::{activitypub::actor::ActorId, user::model::UserId};
+
+use super::model::{
+    ActorEd25519PublicKey, ActorPublicKey, Ed25519SigningKey, Ed25519VerifyingKey, RsaSingingKey,
+    RsaVerifyingKey, SaveEd25519KeyPairEvent, SaveEd25519PublicKeyEvent, SaveKeyPairEvent,
+    SavePublicKeyEvent,
+};
 
 #[async_trait::async_trait]
 pub trait RsaKeyRepository: Send + Sync {
     /// 公開鍵をDBから探す
     async fn find_public_key(&self, user_id: &UserId) -> anyhow::Result<RsaVerifyingKey>;
+    /// ユーザが公開する公開鍵をDBから探す
+    ///
+    /// 使っている鍵を先頭に、猶予期間が残っている古い鍵を新しい順に続ける
+    async fn find_public_keys(&self, user_id: &UserId) -> anyhow::Result<Vec<ActorPublicKey>>;
+    /// 鍵のURLから公開鍵をDBから探す
+    async fn find_public_key_by_key_url(
+        &self,
+        key_url: &ResourceUrl,
+    ) -> anyhow::Result<ActorPublicKey>;
     /// 秘密鍵をDBから探す
     async fn find_private_key(&self, user_id: &UserId) -> anyhow::Result<RsaSingingKey>;
     /// 公開鍵をDBに保存する
+    ///
+    /// 同じ鍵がすでにある場合は上書きする
     async fn save_public_key(&self, event: SavePublicKeyEvent<'_>) -> anyhow::Result<()>;
+    /// `Actor`が公開しなくなった公開鍵をDBから消す
+    ///
+    /// `key_urls`にない鍵はもう署名の検証に使わない
+    async fn delete_other_public_keys(
+        &self,
+        actor_id: &ActorId,
+        key_urls: &[ResourceUrl],
+    ) -> anyhow::Result<()>;
     /// ユーザのキーペアをDBに保存する
     async fn save_key_pair(&self, event: SaveKeyPairEvent<'_>) -> anyhow::Result<()>;
+    /// ユーザが使っている鍵のURLをDBから探す
+    async fn find_key_url(&self, user_id: &UserId) -> anyhow::Result<ResourceUrl>;
+    /// ユーザの鍵を新しいキーペアに置き換える
+    ///
+    /// 古い鍵の秘密鍵は消し、公開鍵は`grace_period`の間だけ署名の検証に使える
+    async fn rotate_key_pair(
+        &self,
+        event: SaveKeyPairEvent<'_>,
+        grace_period: Duration,
+    ) -> anyhow::Result<()>;
+    /// ユーザのEd25519の公開鍵をDBから探す
+    async fn find_ed25519_public_key(
+        &self,
+        user_id: &UserId,
+    ) -> anyhow::Result<Ed25519VerifyingKey>;
+    /// ユーザが公開するEd25519の公開鍵をDBから探す
+    ///
+    /// 使っている鍵を先頭に、猶予期間が残っている古い鍵を新しい順に続ける
+    async fn find_ed25519_public_keys(
+        &self,
+        user_id: &UserId,
+    ) -> anyhow::Result<Vec<ActorEd25519PublicKey>>;
+    /// 鍵のURLから`Actor`が公開しているEd25519の公開鍵をDBから探す
+    async fn find_ed25519_public_key_by_key_url(
+        &self,
+        key_url: &ResourceUrl,
+    ) -> anyhow::Result<ActorEd25519PublicKey>;
+    /// ユーザのEd25519の秘密鍵をDBから探す
+    async fn find_ed25519_private_key(&self, user_id: &UserId)
+        -> anyhow::Result<Ed25519SigningKey>;
+    /// `Actor`が公開しているEd25519の公開鍵をDBに保存する
+    ///
+    /// 同じ鍵がすでにある場合は上書きする。他の`Actor`の鍵のURLは上書きしない
+    async fn save_ed25519_public_key(
+        &self,
+        event: SaveEd25519PublicKeyEvent<'_>,
+    ) -> anyhow::Result<()>;
+    /// `Actor`が公開しなくなったEd25519の公開鍵をDBから消す
+    async fn delete_other_ed25519_public_keys(
+        &self,
+        actor_id: &ActorId,
+        key_urls: &[ResourceUrl],
+    ) -> anyhow::Result<()>;
+    /// ユーザのEd25519のキーペアをDBに保存する
+    async fn save_ed25519_key_pair(&self, event: SaveEd25519KeyPairEvent<'_>)
+        -> anyhow::Result<()>;
+    /// ユーザが使っているEd25519の鍵のURLをDBから探す
+    async fn find_ed25519_key_url(&self, user_id: &UserId) -> anyhow::Result<ResourceUrl>;
+    /// ユーザのEd25519の鍵を新しいキーペアに置き換える
+    ///
+    /// 古い鍵の秘密鍵は消し、公開鍵は`grace_period`の間だけ公開しておく
+    async fn rotate_ed25519_key_pair(
+        &self,
+        event: SaveEd25519KeyPairEvent<'_>,
+        grace_period: Duration,
+    ) -> anyhow::Result<()>;
+    /// `inbox`の持ち主が`assertionMethod`でEd25519の鍵を公開しているか
+    ///
+    /// 共有`inbox`の場合は、それを使う`Actor`のいずれかが公開していればよい。
+    /// FEP-521aの鍵はオブジェクトの証明に使うもので、公開していてもEd25519の
+    /// HTTP Signatureを受け付けるとは限らない
+    async fn publishes_ed25519(&self, inbox: &ResourceUrl) -> anyhow::Result<bool>;
 }
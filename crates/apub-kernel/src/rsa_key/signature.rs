@@ -0,0 +1,397 @@
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use apub_shared::model::resource_url::ResourceUrl;
+use axum::http::{header, HeaderMap, Method};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+use super::digest::DigestError;
+
+/// `Signature`ヘッダの解析や署名文字列の組み立てで起きるエラー
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SignatureError {
+    /// `Signature`ヘッダの書式が不正
+    #[error("malformed signature header")]
+    Malformed,
+    /// 必須のパラメータがない
+    #[error("missing `{0}` parameter")]
+    MissingParameter(&'static str),
+    /// `keyId`がURLではない
+    #[error("`keyId` is not a valid url")]
+    InvalidKeyId,
+    /// `signature`がbase64ではない
+    #[error("`signature` is not a valid base64")]
+    InvalidSignature,
+    /// 署名対象のヘッダがリクエストにない
+    #[error("signed header `{0}` is missing")]
+    MissingHeader(String),
+    /// 署名されているべきヘッダが署名されていない
+    #[error("`{0}` must be signed")]
+    UnsignedHeader(&'static str),
+    /// `Date`ヘッダが不正
+    #[error("invalid date")]
+    InvalidDate,
+    /// 署名された日時が許容範囲外
+    #[error("request date is outside of the allowed window")]
+    OutOfWindow,
+    /// 署名の有効期限が切れている
+    #[error("signature has expired")]
+    Expired,
+    /// ボディのダイジェストが不正
+    #[error(transparent)]
+    Digest(#[from] DigestError),
+}
+
+/// `Signature`ヘッダ
+///
+/// See https://datatracker.ietf.org/doc/html/draft-cavage-http-signatures-12#section-4.1
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureHeader {
+    pub key_id: ResourceUrl,
+    pub algorithm: Option<String>,
+    /// 署名されたヘッダの一覧(小文字)
+    pub headers: Vec<String>,
+    pub signature: Vec<u8>,
+    pub created: Option<u64>,
+    pub expires: Option<u64>,
+}
+
+impl SignatureHeader {
+    /// `name`が署名対象に含まれているか
+    pub fn is_signed(&self, name: &str) -> bool {
+        self.headers.iter().any(|v| v == name)
+    }
+
+    /// 署名された日時が`now`の前後`skew`以内にあるか検証する
+    ///
+    /// リプレイ攻撃を防ぐため、`date`か`(created)`のどちらかが署名されている必要がある
+    pub fn verify_time(
+        &self,
+        headers: &HeaderMap,
+        now: SystemTime,
+        skew: Duration,
+    ) -> Result<(), SignatureError> {
+        let within_skew = |time: SystemTime| match time.duration_since(now) {
+            Ok(ahead) => ahead <= skew,
+            Err(behind) => behind.duration() <= skew,
+        };
+
+        if let Some(expires) = self.expires {
+            if UNIX_EPOCH + Duration::from_secs(expires) < now {
+                return Err(SignatureError::Expired);
+            }
+        }
+
+        if self.is_signed("(created)") {
+            let created = self
+                .created
+                .ok_or(SignatureError::MissingParameter("created"))?;
+            return within_skew(UNIX_EPOCH + Duration::from_secs(created))
+                .then_some(())
+                .ok_or(SignatureError::OutOfWindow);
+        }
+
+        if self.is_signed("date") {
+            let date = headers
+                .get(header::DATE)
+                .ok_or_else(|| SignatureError::MissingHeader("date".to_string()))?
+                .to_str()
+                .map_err(|_| SignatureError::InvalidDate)?;
+            let date = httpdate::parse_http_date(date).map_err(|_| SignatureError::InvalidDate)?;
+            return within_skew(date)
+                .then_some(())
+                .ok_or(SignatureError::OutOfWindow);
+        }
+
+        Err(SignatureError::UnsignedHeader("date"))
+    }
+
+    /// リクエストから検証に使う署名文字列を組み立てる
+    ///
+    /// See https://datatracker.ietf.org/doc/html/draft-cavage-http-signatures-12#section-2.3
+    pub fn signing_string(
+        &self,
+        method: &Method,
+        path_and_query: &str,
+        headers: &HeaderMap,
+    ) -> Result<String, SignatureError> {
+        let lines = self
+            .headers
+            .iter()
+            .map(|name| match name.as_str() {
+                "(request-target)" => Ok(format!(
+                    "(request-target): {} {}",
+                    method.as_str().to_lowercase(),
+                    path_and_query
+                )),
+                "(created)" => self
+                    .created
+                    .map(|v| format!("(created): {}", v))
+                    .ok_or(SignatureError::MissingParameter("created")),
+                "(expires)" => self
+                    .expires
+                    .map(|v| format!("(expires): {}", v))
+                    .ok_or(SignatureError::MissingParameter("expires")),
+                _ => {
+                    let values = headers
+                        .get_all(name.as_str())
+                        .iter()
+                        .map(|v| v.to_str().map(str::trim))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| SignatureError::MissingHeader(name.clone()))?;
+                    if values.is_empty() {
+                        return Err(SignatureError::MissingHeader(name.clone()));
+                    }
+                    Ok(format!("{}: {}", name, values.join(", ")))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lines.join("\n"))
+    }
+}
+
+impl FromStr for SignatureHeader {
+    type Err = SignatureError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut key_id = None;
+        let mut algorithm = None;
+        let mut headers = None;
+        let mut signature = None;
+        let mut created = None;
+        let mut expires = None;
+
+        for (key, value) in split_params(s)? {
+            match key {
+                "keyId" => key_id = Some(value),
+                "algorithm" => algorithm = Some(value),
+                "headers" => headers = Some(value),
+                "signature" => signature = Some(value),
+                "created" => created = Some(value),
+                "expires" => expires = Some(value),
+                // 未知のパラメータは無視する
+                _ => {}
+            }
+        }
+
+        let key_id = key_id
+            .ok_or(SignatureError::MissingParameter("keyId"))?
+            .parse::<ResourceUrl>()
+            .map_err(|_| SignatureError::InvalidKeyId)?;
+        let signature = signature.ok_or(SignatureError::MissingParameter("signature"))?;
+        let signature = BASE64_STANDARD
+            .decode(signature)
+            .map_err(|_| SignatureError::InvalidSignature)?;
+
+        // `headers`がない場合は`date`のみが署名されているとみなす(Mastodonと同じ)
+        let headers = headers
+            .unwrap_or("date")
+            .split_ascii_whitespace()
+            .map(|v| v.to_lowercase())
+            .collect::<Vec<_>>();
+
+        let parse_time = |v: &str| v.parse::<u64>().map_err(|_| SignatureError::Malformed);
+        let created = created.map(parse_time).transpose()?;
+        let expires = expires.map(parse_time).transpose()?;
+
+        Ok(Self {
+            key_id,
+            algorithm: algorithm.map(|v| v.to_string()),
+            headers,
+            signature,
+            created,
+            expires,
+        })
+    }
+}
+
+impl std::fmt::Display for SignatureHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, r#"keyId="{}""#, self.key_id)?;
+        if let Some(algorithm) = &self.algorithm {
+            write!(f, r#",algorithm="{}""#, algorithm)?;
+        }
+        if let Some(created) = self.created {
+            write!(f, ",created={}", created)?;
+        }
+        if let Some(expires) = self.expires {
+            write!(f, ",expires={}", expires)?;
+        }
+        write!(
+            f,
+            r#",headers="{}",signature="{}""#,
+            self.headers.join(" "),
+            BASE64_STANDARD.encode(&self.signature)
+        )
+    }
+}
+
+/// `key="value",key2=value2`を組に分割する
+fn split_params(s: &str) -> Result<Vec<(&str, &str)>, SignatureError> {
+    let mut params = Vec::new();
+    let mut rest = s.trim();
+
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=').ok_or(SignatureError::Malformed)?;
+        let key = key.trim();
+
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').ok_or(SignatureError::Malformed)?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => {
+                let end = after_key.find(',').unwrap_or(after_key.len());
+                (after_key[..end].trim(), &after_key[end..])
+            }
+        };
+        params.push((key, value));
+
+        let after_value = after_value.trim_start();
+        rest = match after_value.strip_prefix(',') {
+            Some(next) => next.trim_start(),
+            None if after_value.is_empty() => after_value,
+            None => return Err(SignatureError::Malformed),
+        };
+    }
+
+    Ok(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_signature_header() {
+        let s = r#"keyId="https://my-example.com/actor#main-key",headers="(request-target) host date",signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        let expected = SignatureHeader {
+            key_id: "https://my-example.com/actor#main-key".parse().unwrap(),
+            algorithm: None,
+            headers: vec![
+                "(request-target)".to_string(),
+                "host".to_string(),
+                "date".to_string(),
+            ],
+            signature: BASE64_STANDARD.decode("Y2FiYWIxNGRiZDk4ZA==").unwrap(),
+            created: None,
+            expires: None,
+        };
+
+        assert_eq!(expected, header)
+    }
+
+    #[test]
+    fn test_parse_signature_header_with_unquoted_params() {
+        let s = r#"keyId="https://example.com/users/alice#main-key", algorithm="hs2019", created=1402170695, expires=1402170995, headers="(request-target) (created) (expires)", signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        assert_eq!(header.algorithm.as_deref(), Some("hs2019"));
+        assert_eq!(header.created, Some(1402170695));
+        assert_eq!(header.expires, Some(1402170995));
+    }
+
+    #[test]
+    fn test_display_signature_header() {
+        let s = r#"keyId="https://my-example.com/actor#main-key",algorithm="rsa-sha256",headers="(request-target) host date",signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        assert_eq!(header.to_string(), s);
+        assert_eq!(header.to_string().parse::<SignatureHeader>(), Ok(header));
+    }
+
+    #[test]
+    fn test_parse_invalid_signature_header() {
+        let s = r#"keyId="https://example.com/users/alice#main-key",headers="date""#;
+        assert_eq!(
+            s.parse::<SignatureHeader>(),
+            Err(SignatureError::MissingParameter("signature"))
+        );
+
+        let s = r#"keyId="https://example.com/users/alice#main-key",signature="abc"#;
+        assert_eq!(s.parse::<SignatureHeader>(), Err(SignatureError::Malformed));
+    }
+
+    #[test]
+    fn test_verify_date() {
+        let s = r#"keyId="https://example.com/users/alice#main-key",headers="(request-target) host date",signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        let headers = HeaderMap::from_iter([(
+            header::DATE,
+            HeaderValue::from_static("Wed, 18 Dec 2019 10:08:46 GMT"),
+        )]);
+        let date = httpdate::parse_http_date("Wed, 18 Dec 2019 10:08:46 GMT").unwrap();
+        let skew = Duration::from_secs(300);
+
+        assert_eq!(header.verify_time(&headers, date, skew), Ok(()));
+        assert_eq!(
+            header.verify_time(&headers, date + Duration::from_secs(299), skew),
+            Ok(())
+        );
+        assert_eq!(
+            header.verify_time(&headers, date - Duration::from_secs(299), skew),
+            Ok(())
+        );
+        assert_eq!(
+            header.verify_time(&headers, date + Duration::from_secs(301), skew),
+            Err(SignatureError::OutOfWindow)
+        );
+        assert_eq!(
+            header.verify_time(&headers, date - Duration::from_secs(301), skew),
+            Err(SignatureError::OutOfWindow)
+        );
+    }
+
+    #[test]
+    fn test_verify_date_unsigned() {
+        let s = r#"keyId="https://example.com/users/alice#main-key",headers="(request-target) host",signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        let headers = HeaderMap::from_iter([(
+            header::DATE,
+            HeaderValue::from_static("Wed, 18 Dec 2019 10:08:46 GMT"),
+        )]);
+        let date = httpdate::parse_http_date("Wed, 18 Dec 2019 10:08:46 GMT").unwrap();
+
+        assert_eq!(
+            header.verify_time(&headers, date, Duration::from_secs(300)),
+            Err(SignatureError::UnsignedHeader("date"))
+        );
+    }
+
+    #[test]
+    fn test_signing_string() {
+        let s = r#"keyId="https://my-example.com/actor#main-key",headers="(request-target) host date",signature="Y2FiYWIxNGRiZDk4ZA==""#;
+        let header = s.parse::<SignatureHeader>().unwrap();
+
+        let headers = HeaderMap::from_iter([
+            (header::HOST, HeaderValue::from_static("mastodon.example")),
+            (
+                header::DATE,
+                HeaderValue::from_static("Wed, 18 Dec 2019 10:08:46 GMT"),
+            ),
+        ]);
+
+        let signing_string = header
+            .signing_string(&Method::GET, "/users/username/outbox", &headers)
+            .unwrap();
+
+        assert_eq!(
+            signing_string,
+            "(request-target): get /users/username/outbox\nhost: mastodon.example\ndate: Wed, 18 Dec 2019 10:08:46 GMT"
+        );
+
+        let missing = header.signing_string(&Method::GET, "/", &HeaderMap::new());
+        assert_eq!(
+            missing,
+            Err(SignatureError::MissingHeader("host".to_string()))
+        );
+    }
+}
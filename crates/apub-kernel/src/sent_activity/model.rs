@@ -0,0 +1,78 @@
+use apub_config::AppConfig;
+use apub_shared::model::{id::Id, resource_url::ResourceUrl};
+use typed_builder::TypedBuilder;
+
+use crate::activitypub::audience::Audience;
+
+pub type SentActivityId = Id<SentActivity>;
+
+/// このサーバから送った`Activity`
+///
+/// `/activities/{id}`で取得できるよう、送ったJSONをそのまま保存する
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct SentActivity {
+    pub id: SentActivityId,
+    pub actor_url: ResourceUrl,
+    pub activity: serde_json::Value,
+}
+
+impl SentActivity {
+    /// `id`が`/activities/{id}`の`Activity`から作る。それ以外は`None`を返す
+    pub fn from_value(config: &AppConfig, activity: serde_json::Value) -> Option<Self> {
+        let id = activity.get("id")?.as_str()?.parse().ok()?;
+        let id = parse_activity_uri(config, &id)?;
+        let actor_url = activity.get("actor")?.as_str()?.parse().ok()?;
+
+        Some(Self {
+            id,
+            actor_url,
+            activity,
+        })
+    }
+
+    pub fn audience(&self) -> Audience {
+        Audience::from_value(&self.activity)
+    }
+}
+
+/// このサーバの`Activity`のURLから`SentActivityId`を取り出す
+///
+/// 別のサーバのURLや`/activities/{id}`の形でない場合は`None`を返す
+pub fn parse_activity_uri(config: &AppConfig, url: &ResourceUrl) -> Option<SentActivityId> {
+    let host_uri = config.host_uri();
+    if url.host() != host_uri.host() || url.port() != host_uri.port() {
+        return None;
+    }
+    url.path().strip_prefix("/activities/")?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activitypub::activity::generate_activity_uri;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_from_value() {
+        let config = AppConfig::new("https://example.com");
+        let id = generate_activity_uri(&config);
+        let activity = serde_json::json!({
+            "id": id.as_str(),
+            "type": "Follow",
+            "actor": "https://example.com/users/alice",
+            "object": "https://remote.example/users/bob",
+        });
+
+        let sent = SentActivity::from_value(&config, activity).unwrap();
+        assert_eq!(parse_activity_uri(&config, &id), Some(sent.id));
+        assert_eq!(sent.actor_url.as_str(), "https://example.com/users/alice");
+
+        // 別のサーバの`Activity`は保存しない
+        let remote = serde_json::json!({
+            "id": "https://remote.example/activities/0193c9a6-8e3a-7c4b-9a1e-5f2d3c4b5a69",
+            "type": "Follow",
+            "actor": "https://remote.example/users/bob",
+        });
+        assert!(SentActivity::from_value(&config, remote).is_none());
+    }
+}
@@ -0,0 +1,8 @@
+use super::model::{SentActivity, SentActivityId};
+
+#[async_trait::async_trait]
+pub trait SentActivityRepository: Send + Sync {
+    async fn find(&self, id: &SentActivityId) -> anyhow::Result<SentActivity>;
+    /// 送った`Activity`を保存する。すでにある場合は何もしない
+    async fn create(&self, activity: &SentActivity) -> anyhow::Result<()>;
+}
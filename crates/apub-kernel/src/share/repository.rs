@@ -0,0 +1,15 @@
+use apub_shared::model::resource_url::ResourceUrl;
+
+use crate::note::model::NoteId;
+
+use super::model::Share;
+
+#[async_trait::async_trait]
+pub trait ShareRepository: Send + Sync {
+    /// `Note`の`Announce`を古い順に探す
+    async fn find_by_note(&self, note_id: &NoteId) -> anyhow::Result<Vec<Share>>;
+    /// `Announce`を保存する。すでにある場合は何もしない
+    async fn create(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+    /// `Announce`を削除する。ない場合は何もしない
+    async fn delete(&self, note_id: &NoteId, actor_url: &ResourceUrl) -> anyhow::Result<()>;
+}
@@ -1,6 +1,8 @@
 use apub_activitypub::model::{
     context::Context,
-    person::{Person, PersonUrl},
+    image::Image,
+    person::{Endpoints, Person, PersonUrl},
+    property_value::PropertyValue,
 };
 use apub_config::AppConfig;
 use apub_shared::model::{id::Id, resource_url::ResourceUrl};
@@ -30,11 +32,21 @@ impl User {
         create_user_inbox(config, &self.name)
     }
 
+    /// `/users/{username}/outbox`
+    pub fn outbox_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_user_outbox(config, &self.name)
+    }
+
     /// `/users/{username}/followers`
     pub fn followers_uri(&self, config: &AppConfig) -> ResourceUrl {
         create_followers_url(config, &self.name)
     }
 
+    /// `/users/{username}/following`
+    pub fn following_uri(&self, config: &AppConfig) -> ResourceUrl {
+        create_following_url(config, &self.name)
+    }
+
     /// `/users/{username}#keyname`
     pub fn user_key_uri<T>(&self, config: &AppConfig) -> ResourceUrl
     where
@@ -43,16 +55,95 @@ impl User {
         create_user_key_url::<T>(config, &self.name)
     }
 
+    /// 鍵を置き換えるときの新しい鍵のURL
+    ///
+    /// `/users/{username}#keyname-{uuid}`
+    pub fn rotated_user_key_uri<T>(&self, config: &AppConfig) -> ResourceUrl
+    where
+        T: KeyType,
+    {
+        let mut key_uri = self.user_key_uri::<T>(config);
+        let fragment = format!("{}-{}", T::key_type(), uuid::Uuid::now_v7().simple());
+        key_uri.set_fragment(&fragment);
+        key_uri
+    }
+
+    /// サーバ全体の共有`inbox`を知らせる
+    fn endpoints(&self, config: &AppConfig) -> Endpoints {
+        Endpoints::builder()
+            .shared_inbox(config.shared_inbox())
+            .build()
+    }
+
     /// Create Person actor
     pub fn to_person(&self, config: &AppConfig) -> Person {
         Person::builder()
             .id(self.user_uri(config))
             .preferred_username(self.name.clone())
             .inbox(self.inbox_uri(config))
+            .endpoints(self.endpoints(config))
             .context(Context::activity_context_url().clone().into())
             .kind(Default::default())
             .build()
     }
+
+    /// Create Person actor with profile
+    pub fn to_person_with_profile(&self, config: &AppConfig, profile: &UserProfile) -> Person {
+        let icon = profile
+            .icon
+            .as_ref()
+            .map(|url| Image::builder().url(url.clone()).build());
+        let attachment = profile
+            .fields
+            .iter()
+            .map(|field| {
+                PropertyValue::builder()
+                    .name(field.name.clone())
+                    .value(field.value.clone())
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        Person::builder()
+            .id(self.user_uri(config))
+            .preferred_username(self.name.clone())
+            .inbox(self.inbox_uri(config))
+            .outbox(self.outbox_uri(config))
+            .context(Context::activity_context_url().clone().into())
+            .endpoints(self.endpoints(config))
+            .followers(self.followers_uri(config))
+            .following(self.following_uri(config))
+            .kind(Default::default())
+            .name(profile.display_name.clone())
+            .summary(profile.summary.clone())
+            .icon(icon)
+            .attachment((!attachment.is_empty()).then_some(attachment))
+            .manually_approves_followers(Some(profile.manually_approves_followers))
+            .build()
+    }
+}
+
+/// ユーザが編集できるプロフィール
+#[derive(Debug, Clone, PartialEq, Default, TypedBuilder)]
+pub struct UserProfile {
+    #[builder(default, setter(strip_option))]
+    pub display_name: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub summary: Option<String>,
+    #[builder(default, setter(strip_option))]
+    pub icon: Option<ResourceUrl>,
+    #[builder(default)]
+    pub fields: Vec<ProfileField>,
+    /// フォローに承認が必要か
+    #[builder(default)]
+    pub manually_approves_followers: bool,
+}
+
+/// プロフィールの項目
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct ProfileField {
+    pub name: String,
+    pub value: String,
 }
 
 pub(crate) fn create_user_uri(config: &AppConfig, name: &str) -> PersonUrl {
@@ -73,6 +164,15 @@ pub(crate) fn create_user_inbox(config: &AppConfig, name: &str) -> ResourceUrl {
     inbox_uri
 }
 
+pub(crate) fn create_user_outbox(config: &AppConfig, name: &str) -> ResourceUrl {
+    let outbox_uri = config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/users/{}/outbox", name))
+        .to_owned();
+    outbox_uri
+}
+
 pub(crate) fn create_followers_url(config: &AppConfig, name: &str) -> ResourceUrl {
     let followers_uri = config
         .host_uri()
@@ -82,6 +182,42 @@ pub(crate) fn create_followers_url(config: &AppConfig, name: &str) -> ResourceUr
     followers_uri
 }
 
+pub(crate) fn create_following_url(config: &AppConfig, name: &str) -> ResourceUrl {
+    let following_uri = config
+        .host_uri()
+        .clone()
+        .set_path(&format!("/users/{}/following", name))
+        .to_owned();
+    following_uri
+}
+
+/// このサーバのユーザのURLからユーザ名を取り出す
+///
+/// 別のサーバのURLや`/users/{username}`の形でない場合は`None`を返す
+pub fn parse_user_uri<'a>(config: &AppConfig, url: &'a ResourceUrl) -> Option<&'a str> {
+    let host_uri = config.host_uri();
+    if url.host() != host_uri.host() || url.port() != host_uri.port() {
+        return None;
+    }
+    url.path()
+        .strip_prefix("/users/")
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// このサーバのユーザの`followers`のURLからユーザ名を取り出す
+///
+/// 別のサーバのURLや`/users/{username}/followers`の形でない場合は`None`を返す
+pub fn parse_followers_uri<'a>(config: &AppConfig, url: &'a ResourceUrl) -> Option<&'a str> {
+    let host_uri = config.host_uri();
+    if url.host() != host_uri.host() || url.port() != host_uri.port() {
+        return None;
+    }
+    url.path()
+        .strip_prefix("/users/")?
+        .strip_suffix("/followers")
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
 pub(crate) fn create_user_key_url<T>(config: &AppConfig, name: &str) -> ResourceUrl
 where
     T: KeyType,
@@ -139,4 +275,30 @@ mod tests {
             "https://example.com/users/foo/inbox".parse().unwrap()
         )
     }
+
+    #[test]
+    fn test_parse_user_uri() {
+        let config = test_config();
+        let user = test_user().user_uri(&config);
+        assert_eq!(parse_user_uri(&config, user.as_ref()), Some("foo"));
+
+        let remote = "https://remote.example/users/foo".parse().unwrap();
+        assert_eq!(parse_user_uri(&config, &remote), None);
+        let inbox = test_user().inbox_uri(&config);
+        assert_eq!(parse_user_uri(&config, &inbox), None);
+    }
+
+    #[test]
+    fn test_parse_followers_uri() {
+        let config = test_config();
+        let followers = test_user().followers_uri(&config);
+        assert_eq!(parse_followers_uri(&config, &followers), Some("foo"));
+
+        let remote = "https://remote.example/users/foo/followers"
+            .parse()
+            .unwrap();
+        assert_eq!(parse_followers_uri(&config, &remote), None);
+        let following = test_user().following_uri(&config);
+        assert_eq!(parse_followers_uri(&config, &following), None);
+    }
 }
@@ -1,8 +1,11 @@
-use super::model::{CreateUser, User, UserId};
+use super::model::{CreateUser, User, UserId, UserProfile};
 
 #[async_trait::async_trait]
 pub trait UserRepository: Send + Sync {
     async fn find_by_name(&self, name: &str) -> anyhow::Result<User>;
     async fn find_by_id(&self, id: &UserId) -> anyhow::Result<User>;
     async fn create(&self, event: CreateUser) -> anyhow::Result<User>;
+    async fn find_profile(&self, id: &UserId) -> anyhow::Result<UserProfile>;
+    /// プロフィールを置き換える
+    async fn update_profile(&self, id: &UserId, profile: &UserProfile) -> anyhow::Result<()>;
 }
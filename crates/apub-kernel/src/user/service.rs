@@ -5,13 +5,17 @@ use apub_config::AppConfig;
 use crate::{
     activitypub::actor::{ActorRepository, CreateActorEvent},
     rsa_key::{
-        model::{RsaSingingKey, RsaVerifyingKey, SaveKeyPairEvent},
+        model::{
+            Ed25519SigningKey, Ed25519VerifyingKey, RsaSingingKey, RsaVerifyingKey,
+            SaveEd25519KeyPairEvent, SaveKeyPairEvent,
+        },
+        pool::RsaKeyPool,
         repository::RsaKeyRepository,
     },
 };
 
 use super::{
-    model::{CreateUser, User, UserId},
+    model::{CreateUser, User, UserId, UserProfile},
     repository::UserRepository,
 };
 
@@ -19,31 +23,62 @@ pub trait UserService: Send + Sync {
     fn find_by_name(&self, name: &str) -> impl Future<Output = anyhow::Result<User>>;
     fn find_by_id(&self, id: &UserId) -> impl Future<Output = anyhow::Result<User>>;
     fn create(&self, event: CreateUser) -> impl Future<Output = anyhow::Result<User>>;
+    fn find_profile(&self, id: &UserId) -> impl Future<Output = anyhow::Result<UserProfile>>;
+    fn update_profile(
+        &self,
+        id: &UserId,
+        profile: &UserProfile,
+    ) -> impl Future<Output = anyhow::Result<()>>;
+    /// ユーザのRSAとEd25519の鍵を新しいURLのキーペアに置き換える
+    ///
+    /// 古い鍵は設定された猶予期間だけ公開し、署名の検証に使える
+    fn rotate_key(&self, id: &UserId) -> impl Future<Output = anyhow::Result<()>>;
 }
 
-pub struct UserServiceImpl<UserRepo, ActorRepo, KeyRepo> {
+pub struct UserServiceImpl<UserRepo, ActorRepo, KeyRepo, KeyPool> {
     user: UserRepo,
     actor: ActorRepo,
     rsa_key: KeyRepo,
+    key_pool: KeyPool,
     config: Arc<AppConfig>,
 }
 
-impl<UserRepo, ActorRepo, KeyRepo> UserServiceImpl<UserRepo, ActorRepo, KeyRepo> {
-    pub fn new(user: UserRepo, actor: ActorRepo, rsa_key: KeyRepo, config: Arc<AppConfig>) -> Self {
+impl<UserRepo, ActorRepo, KeyRepo, KeyPool> UserServiceImpl<UserRepo, ActorRepo, KeyRepo, KeyPool> {
+    pub fn new(
+        user: UserRepo,
+        actor: ActorRepo,
+        rsa_key: KeyRepo,
+        key_pool: KeyPool,
+        config: Arc<AppConfig>,
+    ) -> Self {
         Self {
             user,
             actor,
             rsa_key,
+            key_pool,
             config,
         }
     }
+
+    /// 作っておいた鍵からキーペアを取り出す
+    async fn generate_key_pair(&self) -> anyhow::Result<(RsaVerifyingKey, RsaSingingKey)>
+    where
+        KeyPool: RsaKeyPool,
+    {
+        let skey = self.key_pool.take().await?;
+        let pkey = skey.to_public_key();
+
+        Ok((pkey, skey))
+    }
 }
 
-impl<UserRepo, ActorRepo, KeyRepo> UserService for UserServiceImpl<UserRepo, ActorRepo, KeyRepo>
+impl<UserRepo, ActorRepo, KeyRepo, KeyPool> UserService
+    for UserServiceImpl<UserRepo, ActorRepo, KeyRepo, KeyPool>
 where
     UserRepo: UserRepository,
     ActorRepo: ActorRepository,
     KeyRepo: RsaKeyRepository,
+    KeyPool: RsaKeyPool,
 {
     async fn find_by_name(&self, name: &str) -> anyhow::Result<User> {
         let bind = self.user.find_by_name(name).await?;
@@ -55,6 +90,14 @@ where
         Ok(bind)
     }
 
+    async fn find_profile(&self, id: &UserId) -> anyhow::Result<UserProfile> {
+        self.user.find_profile(id).await
+    }
+
+    async fn update_profile(&self, id: &UserId, profile: &UserProfile) -> anyhow::Result<()> {
+        self.user.update_profile(id, profile).await
+    }
+
     #[tracing::instrument(skip(self))]
     async fn create(&self, event: CreateUser) -> anyhow::Result<User> {
         let user = self.user.create(event).await?;
@@ -67,7 +110,7 @@ where
             .build();
 
         let actor = self.actor.create(create_actor).await?;
-        let (pkey, skey) = generate_key_pair()?;
+        let (pkey, skey) = self.generate_key_pair().await?;
 
         let key_url = user.user_key_uri::<RsaVerifyingKey>(&self.config);
 
@@ -80,13 +123,53 @@ where
 
         self.rsa_key.save_key_pair(key_pair).await?;
 
+        // 対応しているサーバにはEd25519で署名する
+        let ed25519_skey = Ed25519SigningKey::new();
+        let ed25519_pkey = ed25519_skey.to_public_key();
+        let ed25519_key_url = user.user_key_uri::<Ed25519VerifyingKey>(&self.config);
+        let ed25519_key_pair = SaveEd25519KeyPairEvent::builder()
+            .actor_id(&actor.actor_id)
+            .key_url(&ed25519_key_url)
+            .public_key(&ed25519_pkey)
+            .private_key(&ed25519_skey)
+            .build();
+
+        self.rsa_key.save_ed25519_key_pair(ed25519_key_pair).await?;
+
         Ok(user)
     }
-}
 
-fn generate_key_pair() -> anyhow::Result<(RsaVerifyingKey, RsaSingingKey)> {
-    let skey = RsaSingingKey::new()?;
-    let pkey = skey.to_public_key();
+    #[tracing::instrument(skip(self))]
+    async fn rotate_key(&self, id: &UserId) -> anyhow::Result<()> {
+        let user = self.user.find_by_id(id).await?;
+        let actor = self.actor.find_by_url(&user.user_uri(&self.config)).await?;
+        let (pkey, skey) = self.generate_key_pair().await?;
+
+        let key_url = user.rotated_user_key_uri::<RsaVerifyingKey>(&self.config);
 
-    Ok((pkey, skey))
+        let key_pair = SaveKeyPairEvent::builder()
+            .actor_id(&actor.actor_id)
+            .key_url(&key_url)
+            .public_key(&pkey)
+            .private_key(&skey)
+            .build();
+
+        self.rsa_key
+            .rotate_key_pair(key_pair, self.config.key_grace_period())
+            .await?;
+
+        let ed25519_skey = Ed25519SigningKey::new();
+        let ed25519_pkey = ed25519_skey.to_public_key();
+        let ed25519_key_url = user.rotated_user_key_uri::<Ed25519VerifyingKey>(&self.config);
+        let ed25519_key_pair = SaveEd25519KeyPairEvent::builder()
+            .actor_id(&actor.actor_id)
+            .key_url(&ed25519_key_url)
+            .public_key(&ed25519_pkey)
+            .private_key(&ed25519_skey)
+            .build();
+
+        self.rsa_key
+            .rotate_ed25519_key_pair(ed25519_key_pair, self.config.key_grace_period())
+            .await
+    }
 }
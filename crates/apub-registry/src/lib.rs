@@ -1,11 +1,17 @@
 use std::sync::Arc;
 
-use apub_adapter::persistence::{http_client::HttpClient, postgres::PostgresDb};
+use apub_activitypub::webfinger::WebFingerResolver;
+use apub_adapter::{
+    persistence::{http_client::HttpClient, postgres::PostgresDb},
+    worker::{delivery::DeliveryWorker, key_pool::KeyPool},
+};
 use apub_config::AppConfig;
 use apub_kernel::{
     activitypub::{actor::ActorRepository, service::ActivityServiceImpl},
+    delivery::service::DeliveryServiceImpl,
     follower::repository::FollowerRepository,
-    note::repository::NoteRepository,
+    following::service::FollowingServiceImpl,
+    note::{repository::NoteRepository, service::NoteServiceImpl},
     prelude::*,
     user::{repository::UserRepository, service::UserServiceImpl},
 };
@@ -14,6 +20,7 @@ use apub_kernel::{
 pub struct AppRegistry {
     postgres: PostgresDb,
     http_client: HttpClient,
+    key_pool: KeyPool,
     config: Arc<AppConfig>,
 }
 
@@ -21,24 +28,51 @@ impl AppRegistry {
     pub fn new_postgres(pool: PostgresDb, config: AppConfig) -> Self {
         AppRegistry {
             postgres: pool,
-            http_client: HttpClient::new(),
+            http_client: HttpClient::with_timeout(config.request_timeout()),
+            key_pool: KeyPool::new(&config),
             config: Arc::new(config),
         }
     }
+
+    /// ユーザを作るときのためにRSA鍵を作っておく場所
+    ///
+    /// [`KeyPool::spawn`]するまでは鍵を作り足さない
+    pub fn key_pool(&self) -> KeyPool {
+        self.key_pool.clone()
+    }
+
+    /// 配送キューを処理するワーカー
+    pub fn delivery_worker(&self) -> DeliveryWorker {
+        DeliveryWorker::new(
+            self.postgres.clone(),
+            self.http_client.clone(),
+            self.config(),
+        )
+    }
 }
 
 impl AppRegistryExt for AppRegistry {
     type UserRepo = PostgresDb;
     type RsaRepo = PostgresDb;
     type FollowerRepo = PostgresDb;
+    type FollowRequestRepo = PostgresDb;
+    type FollowingRepo = PostgresDb;
     type NoteRepo = PostgresDb;
+    type LikeRepo = PostgresDb;
+    type ShareRepo = PostgresDb;
     type ActivityRepo = HttpClient;
     type ActorRepo = PostgresDb;
-    fn user_service(&self) -> UserServiceImpl<Self::UserRepo, Self::ActorRepo, Self::RsaRepo> {
+    type SentActivityRepo = PostgresDb;
+    type DeliveryRepo = PostgresDb;
+    type InstanceRepo = PostgresDb;
+    type InboxRepo = PostgresDb;
+    type KeyPool = KeyPool;
+    fn user_service(&self) -> UserServiceOf<Self> {
         UserServiceImpl::new(
             self.postgres.clone(),
             self.postgres.clone(),
             self.postgres.clone(),
+            self.key_pool(),
             self.config(),
         )
     }
@@ -46,13 +80,13 @@ impl AppRegistryExt for AppRegistry {
         self.postgres.clone()
     }
 
-    fn activity_service(
-        &self,
-    ) -> ActivityServiceImpl<Self::ActivityRepo, Self::ActorRepo, Self::RsaRepo> {
+    fn activity_service(&self) -> ActivityServiceOf<Self> {
         ActivityServiceImpl::new(
             self.http_client.clone(),
             self.postgres.clone(),
             self.postgres.clone(),
+            self.postgres.clone(),
+            self.config(),
         )
     }
 
@@ -60,28 +94,146 @@ impl AppRegistryExt for AppRegistry {
         self.postgres.clone()
     }
 
+    fn follow_request_repository(&self) -> Self::FollowRequestRepo {
+        self.postgres.clone()
+    }
+
+    fn following_repository(&self) -> Self::FollowingRepo {
+        self.postgres.clone()
+    }
+
+    fn following_service(
+        &self,
+    ) -> FollowingServiceImpl<ActivityServiceOf<Self>, Self::RsaRepo, Self::FollowingRepo> {
+        FollowingServiceImpl::new(
+            self.activity_service(),
+            self.postgres.clone(),
+            self.postgres.clone(),
+            self.config(),
+        )
+    }
+
     fn note_repository(&self) -> Self::NoteRepo {
         self.postgres.clone()
     }
 
+    fn note_service(&self) -> NoteServiceOf<Self> {
+        NoteServiceImpl::new(
+            self.delivery_service(),
+            self.postgres.clone(),
+            self.config(),
+        )
+    }
+
+    fn actor_repository(&self) -> Self::ActorRepo {
+        self.postgres.clone()
+    }
+
+    fn like_repository(&self) -> Self::LikeRepo {
+        self.postgres.clone()
+    }
+
+    fn share_repository(&self) -> Self::ShareRepo {
+        self.postgres.clone()
+    }
+
+    fn sent_activity_repository(&self) -> Self::SentActivityRepo {
+        self.postgres.clone()
+    }
+
+    fn delivery_repository(&self) -> Self::DeliveryRepo {
+        self.postgres.clone()
+    }
+
+    fn instance_repository(&self) -> Self::InstanceRepo {
+        self.postgres.clone()
+    }
+
+    fn inbox_repository(&self) -> Self::InboxRepo {
+        self.postgres.clone()
+    }
+
+    fn delivery_service(&self) -> DeliveryServiceOf<Self> {
+        DeliveryServiceImpl::new(
+            self.postgres.clone(),
+            self.postgres.clone(),
+            self.postgres.clone(),
+            self.postgres.clone(),
+            self.config(),
+        )
+    }
+
     fn config(&self) -> Arc<AppConfig> {
         self.config.clone()
     }
 }
 
+/// `AppRegistryExt::activity_service`の型
+pub type ActivityServiceOf<R> = ActivityServiceImpl<
+    <R as AppRegistryExt>::ActivityRepo,
+    <R as AppRegistryExt>::ActorRepo,
+    <R as AppRegistryExt>::RsaRepo,
+    <R as AppRegistryExt>::SentActivityRepo,
+>;
+
+/// `AppRegistryExt::user_service`の型
+pub type UserServiceOf<R> = UserServiceImpl<
+    <R as AppRegistryExt>::UserRepo,
+    <R as AppRegistryExt>::ActorRepo,
+    <R as AppRegistryExt>::RsaRepo,
+    <R as AppRegistryExt>::KeyPool,
+>;
+
+/// `AppRegistryExt::delivery_service`の型
+pub type DeliveryServiceOf<R> = DeliveryServiceImpl<
+    <R as AppRegistryExt>::DeliveryRepo,
+    <R as AppRegistryExt>::FollowerRepo,
+    <R as AppRegistryExt>::SentActivityRepo,
+    <R as AppRegistryExt>::InstanceRepo,
+>;
+
+/// `AppRegistryExt::note_service`の型
+pub type NoteServiceOf<R> = NoteServiceImpl<DeliveryServiceOf<R>, <R as AppRegistryExt>::NoteRepo>;
+
 pub trait AppRegistryExt: Send + Sync {
     type UserRepo: UserRepository;
     type RsaRepo: RsaKeyRepository;
     type FollowerRepo: FollowerRepository;
-    type ActivityRepo: ActivityRepository;
+    type FollowRequestRepo: FollowRequestRepository;
+    type FollowingRepo: FollowingRepository;
+    type ActivityRepo: ActivityRepository + WebFingerResolver;
     type NoteRepo: NoteRepository;
+    type LikeRepo: LikeRepository;
+    type ShareRepo: ShareRepository;
     type ActorRepo: ActorRepository;
-    fn user_service(&self) -> UserServiceImpl<Self::UserRepo, Self::ActorRepo, Self::RsaRepo>;
+    type SentActivityRepo: SentActivityRepository;
+    type DeliveryRepo: DeliveryRepository;
+    type InstanceRepo: InstanceRepository;
+    type InboxRepo: InboxRepository;
+    type KeyPool: RsaKeyPool;
+    fn user_service(&self) -> UserServiceOf<Self>;
     fn rsa_key_repository(&self) -> Self::RsaRepo;
-    fn activity_service(
-        &self,
-    ) -> ActivityServiceImpl<Self::ActivityRepo, Self::ActorRepo, Self::RsaRepo>;
+    fn activity_service(&self) -> ActivityServiceOf<Self>;
     fn follower_repository(&self) -> Self::FollowerRepo;
+    fn follow_request_repository(&self) -> Self::FollowRequestRepo;
+    fn following_repository(&self) -> Self::FollowingRepo;
+    /// リモートの`Actor`をフォローする
+    fn following_service(
+        &self,
+    ) -> FollowingServiceImpl<ActivityServiceOf<Self>, Self::RsaRepo, Self::FollowingRepo>;
     fn note_repository(&self) -> Self::NoteRepo;
+    /// `Note`を保存して配送する
+    fn note_service(&self) -> NoteServiceOf<Self>;
+    fn actor_repository(&self) -> Self::ActorRepo;
+    fn like_repository(&self) -> Self::LikeRepo;
+    fn share_repository(&self) -> Self::ShareRepo;
+    fn sent_activity_repository(&self) -> Self::SentActivityRepo;
+    fn delivery_repository(&self) -> Self::DeliveryRepo;
+    /// 配送先のホストの到達状況
+    fn instance_repository(&self) -> Self::InstanceRepo;
+    /// 受け取ったActivityの処理キュー
+    fn inbox_repository(&self) -> Self::InboxRepo;
+    /// `Activity`を配送キューに積む
+    fn delivery_service(&self) -> DeliveryServiceOf<Self>;
     fn config(&self) -> Arc<AppConfig>;
 }
@@ -1,9 +1,10 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use apub_config::settings::LogFormat;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 pub struct TracingGuard;
 
-/// `tracing`を初期化する
-pub fn init() -> TracingGuard {
+/// `format`の形式で出力するように`tracing`を初期化する
+pub fn init(format: LogFormat) -> TracingGuard {
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
         let level = if cfg!(debug_assertions) {
             "trace"
@@ -17,10 +18,10 @@ pub fn init() -> TracingGuard {
         .with_file(true)
         .with_line_number(true);
 
-    #[cfg(debug_assertions)]
-    let fmt_layer = fmt_layer.with_ansi(true).pretty();
-    #[cfg(not(debug_assertions))]
-    let fmt_layer = fmt_layer.json();
+    let fmt_layer = match format {
+        LogFormat::Pretty => fmt_layer.with_ansi(true).pretty().boxed(),
+        LogFormat::Json => fmt_layer.json().boxed(),
+    };
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -1,44 +1,98 @@
-use std::net::{Ipv4Addr, SocketAddr};
-
-use apub_adapter::persistence::postgres::PostgresDb;
-use apub_config::AppConfig;
+use apub_adapter::persistence::{key_encryption::MasterKey, postgres::PostgresDb};
+use apub_config::settings::Settings;
 use apub_kernel::user::model::CreateUser;
 use apub_registry::{AppRegistry, AppRegistryExt};
 use axum::{http::StatusCode, routing, Router};
 use tokio::net::TcpListener;
 
-use apub_api::route::{person, send_note, user_inbox, webfinger};
+use apub_api::route::{
+    activity, delete_note, follow, follow_request, note, person, rotate_key, send_announce,
+    send_note, shared_inbox, update_note, update_profile, user_inbox, webfinger,
+};
+use apub_api::worker::inbox::InboxWorker;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let _guard = apub_tracing::init();
+    let settings = Settings::load()?;
+    let _guard = apub_tracing::init(settings.log.format);
 
-    bootstrap().await?;
+    match std::env::args().nth(1).as_deref() {
+        Some("reencrypt-keys") => reencrypt_keys(&settings).await?,
+        _ => bootstrap(&settings).await?,
+    }
 
     Ok(())
 }
 
-async fn bootstrap() -> anyhow::Result<()> {
+async fn bootstrap(settings: &Settings) -> anyhow::Result<()> {
     use tower::ServiceBuilder;
     use tower_http::{normalize_path::NormalizePathLayer, trace::TraceLayer};
 
-    let state = init_registry().await;
-    let _ = seed_db(&state).await.inspect_err(|e| tracing::error!(?e));
+    let state = init_registry(settings).await?;
+    let _ = seed_db(&state, &settings.seed.users)
+        .await
+        .inspect_err(|e| tracing::error!(?e));
+
+    let _key_pool = state.key_pool().spawn();
+    let _delivery_workers = state.delivery_worker().spawn(settings.workers.delivery);
+    let _inbox_workers = InboxWorker::new(state.clone()).spawn(settings.workers.inbox);
 
     let hosted_uri = state.config().host_uri().to_string();
 
     let app = Router::new()
         .route("/health", routing::get(health_check))
         .route("/users/:username", routing::get(person::person))
+        .route("/users/:username/outbox", routing::get(person::outbox))
         .route(
             "/users/:username/followers",
             routing::get(person::followers),
         )
+        .route(
+            "/users/:username/following",
+            routing::get(person::following),
+        )
         .route(
             "/users/:username/inbox",
             routing::post(user_inbox::user_inbox),
         )
-        .route("/send-note", routing::get(send_note::send_note))
+        .route(
+            "/users/:username/follow_requests",
+            routing::get(follow_request::follow_requests),
+        )
+        .route(
+            "/users/:username/follow_requests/accept",
+            routing::post(follow_request::accept_follow_request),
+        )
+        .route(
+            "/users/:username/follow_requests/reject",
+            routing::post(follow_request::reject_follow_request),
+        )
+        .route(
+            "/users/:username/profile",
+            routing::post(update_profile::update_profile),
+        )
+        .route(
+            "/users/:username/rotate-key",
+            routing::post(rotate_key::rotate_key),
+        )
+        .route("/notes/:note_id", routing::get(note::note))
+        .route(
+            "/notes/:note_id/activity",
+            routing::get(note::create_activity),
+        )
+        .route("/notes/:note_id/likes", routing::get(note::likes))
+        .route("/notes/:note_id/shares", routing::get(note::shares))
+        .route("/inbox", routing::post(shared_inbox::shared_inbox))
+        .route("/activities/:activity_id", routing::get(activity::activity))
+        .route("/send-note", routing::post(send_note::send_note))
+        .route(
+            "/send-announce",
+            routing::post(send_announce::send_announce),
+        )
+        .route("/update-note", routing::post(update_note::update_note))
+        .route("/delete-note", routing::post(delete_note::delete_note))
+        .route("/follow", routing::post(follow::follow))
+        .route("/unfollow", routing::post(follow::unfollow))
         .route("/.well-known/webfinger", routing::get(webfinger::webfinger))
         .layer(
             ServiceBuilder::new()
@@ -46,7 +100,7 @@ async fn bootstrap() -> anyhow::Result<()> {
                 .layer(TraceLayer::new_for_http()),
         )
         .with_state(state);
-    let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 8080);
+    let addr = settings.server.listen;
     let listener = TcpListener::bind(addr).await?;
 
     tracing::info!("Server started at {addr}");
@@ -61,32 +115,57 @@ async fn health_check() -> StatusCode {
     StatusCode::OK
 }
 
-async fn init_registry() -> AppRegistry {
-    let app_uri = std::env::var("APUB_LITE_URL").unwrap_or("http://example.com".to_string());
-    let config = AppConfig::new(&app_uri);
-    let postgres_db =
-        PostgresDb::connect("postgresql://postgres:5432/app?user=app&password=password")
-            .await
-            .unwrap();
+async fn init_registry(settings: &Settings) -> anyhow::Result<AppRegistry> {
+    let postgres_db = connect_db(settings).await?;
 
-    AppRegistry::new_postgres(postgres_db, config)
+    Ok(AppRegistry::new_postgres(
+        postgres_db,
+        settings.app_config(),
+    ))
 }
 
-async fn seed_db(registry: &AppRegistry) -> anyhow::Result<()> {
-    use apub_kernel::prelude::*;
-    let user_repo = registry.user_service();
+/// 秘密鍵を暗号化するマスター鍵があれば使う
+async fn connect_db(settings: &Settings) -> anyhow::Result<PostgresDb> {
+    let postgres_db = PostgresDb::connect(&settings.database.url).await?;
+
+    match settings.keys.master_key {
+        Some(ref master_key) => {
+            Ok(postgres_db.with_master_key(MasterKey::from_base64(master_key)?))
+        }
+        None => {
+            tracing::warn!("master key is not set, private keys are stored in plaintext");
+            Ok(postgres_db)
+        }
+    }
+}
 
-    user_repo
-        .create(CreateUser {
-            name: "alice".to_string(),
-        })
+/// 保存されている秘密鍵を設定のマスター鍵で暗号化し直す
+///
+/// 前のマスター鍵が設定されていれば、それで暗号化された鍵も暗号化し直す
+async fn reencrypt_keys(settings: &Settings) -> anyhow::Result<()> {
+    let postgres_db = connect_db(settings).await?;
+    let previous = settings
+        .keys
+        .previous_master_key
+        .as_deref()
+        .map(MasterKey::from_base64)
+        .transpose()?;
+
+    let count = postgres_db
+        .reencrypt_private_keys(previous.as_ref())
         .await?;
+    tracing::info!("Re-encrypted {count} private keys");
 
-    user_repo
-        .create(CreateUser {
-            name: "bob".to_string(),
-        })
-        .await?;
+    Ok(())
+}
+
+async fn seed_db(registry: &AppRegistry, users: &[String]) -> anyhow::Result<()> {
+    use apub_kernel::prelude::*;
+    let user_repo = registry.user_service();
+
+    for name in users {
+        user_repo.create(CreateUser { name: name.clone() }).await?;
+    }
 
     Ok(())
 }